@@ -0,0 +1,744 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use bank_core::{account::Money, clock::BusinessCalendar, product::Product};
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use thiserror::Error;
+
+pub const USAGE: &str = "\
+Usage: bank [OPTIONS]
+
+Options:
+  --config <PATH>               TOML configuration file (env: BANK_CONFIG)
+  --repository-backend <NAME>   memory or file (env: BANK_REPOSITORY_BACKEND)
+  --repository-path <PATH>      data file for the file backend (env: BANK_REPOSITORY_PATH)
+  --log-level <LEVEL>           error, warn, info, debug or trace (env: BANK_LOG_LEVEL)
+  --metrics-listen <ADDR>       serve Prometheus metrics over HTTP on this address
+                                (env: BANK_METRICS_LISTEN)
+  --rpc-listen <ADDR>           serve the bank over gRPC on this address
+                                (env: BANK_RPC_LISTEN)
+  --business-date-cutoff <HH:MM>
+                                time after which operations book to the next business day
+                                (env: BANK_BUSINESS_DATE_CUTOFF)
+  -h, --help                    print this help
+
+Later sources override earlier ones: built-in defaults, the config file,
+environment variables, then command-line flags.";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("cannot read config file {}: {source}", .path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("cannot parse config file {}: {message}", .path.display())]
+    Parse { path: PathBuf, message: String },
+    #[error("{0}\n\n{USAGE}")]
+    Usage(String),
+    #[error("invalid configuration:\n  - {}", .0.join("\n  - "))]
+    Invalid(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepositoryConfig {
+    Memory,
+    File { path: PathBuf },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            "trace" => Ok(LogLevel::Trace),
+            _ => Err(format!(
+                "{s:?} is not a log level; expected error, warn, info, debug or trace"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        };
+        f.write_str(level)
+    }
+}
+
+/// Validated settings for the `bank` binary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub repository: RepositoryConfig,
+    pub log_level: LogLevel,
+    /// Where to serve `/metrics`, if anywhere.
+    pub metrics_listen: Option<SocketAddr>,
+    /// Where to serve the bank's gRPC interface.
+    pub rpc_listen: SocketAddr,
+    /// Business days and the daily cutoff the bank books operations by.
+    pub business_date: BusinessCalendar,
+    pub products: Vec<Product>,
+}
+
+/// A number written either as a TOML number or, to keep exact decimals, as
+/// a string.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum RawDecimal {
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl RawDecimal {
+    fn parse(&self) -> Result<Decimal, String> {
+        match self {
+            RawDecimal::Integer(value) => Ok(Decimal::from(*value)),
+            RawDecimal::Float(value) => Decimal::from_str(&value.to_string())
+                .map_err(|_| format!("{value} is not representable as a decimal")),
+            RawDecimal::Text(text) => Decimal::from_str(text.trim())
+                .map_err(|_| format!("{text:?} is not a decimal number")),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRepository {
+    backend: Option<String>,
+    path: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawLogging {
+    level: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawMetrics {
+    listen: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRpc {
+    listen: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawBusinessDate {
+    cutoff: Option<String>,
+    non_working_days: Option<Vec<String>>,
+    holidays: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawProduct {
+    code: Option<String>,
+    name: Option<String>,
+    interest_rate: Option<RawDecimal>,
+    monthly_fee: Option<RawDecimal>,
+    overdraft_limit: Option<RawDecimal>,
+    daily_withdrawal_limit: Option<RawDecimal>,
+}
+
+/// One configuration layer; unset fields fall through to earlier layers.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    #[serde(default)]
+    repository: RawRepository,
+    #[serde(default)]
+    logging: RawLogging,
+    #[serde(default)]
+    metrics: RawMetrics,
+    #[serde(default)]
+    rpc: RawRpc,
+    #[serde(default)]
+    business_date: RawBusinessDate,
+    products: Option<Vec<RawProduct>>,
+}
+
+impl RawConfig {
+    fn defaults() -> Self {
+        RawConfig {
+            repository: RawRepository {
+                backend: Some("memory".into()),
+                path: None,
+            },
+            logging: RawLogging {
+                level: Some("info".into()),
+            },
+            metrics: RawMetrics::default(),
+            rpc: RawRpc {
+                listen: Some("127.0.0.1:50051".into()),
+            },
+            business_date: RawBusinessDate {
+                cutoff: Some("17:00".into()),
+                non_working_days: Some(vec!["Sat".into(), "Sun".into()]),
+                holidays: Some(Vec::new()),
+            },
+            products: Some(vec![RawProduct {
+                code: Some("CHK".into()),
+                name: Some("Checking".into()),
+                ..Default::default()
+            }]),
+        }
+    }
+
+    fn from_toml(path: &Path, text: &str) -> Result<Self, ConfigError> {
+        toml::from_str(text).map_err(|err| ConfigError::Parse {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })
+    }
+
+    fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_toml(path, &text)
+    }
+
+    fn from_env(env: &HashMap<String, String>) -> Self {
+        let var = |name: &str| env.get(name).cloned();
+        let list = |name: &str| {
+            var(name).map(|value| {
+                value
+                    .split(',')
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect()
+            })
+        };
+
+        RawConfig {
+            repository: RawRepository {
+                backend: var("BANK_REPOSITORY_BACKEND"),
+                path: var("BANK_REPOSITORY_PATH"),
+            },
+            logging: RawLogging {
+                level: var("BANK_LOG_LEVEL"),
+            },
+            metrics: RawMetrics {
+                listen: var("BANK_METRICS_LISTEN"),
+            },
+            rpc: RawRpc {
+                listen: var("BANK_RPC_LISTEN"),
+            },
+            business_date: RawBusinessDate {
+                cutoff: var("BANK_BUSINESS_DATE_CUTOFF"),
+                non_working_days: list("BANK_BUSINESS_DATE_NON_WORKING_DAYS"),
+                holidays: list("BANK_BUSINESS_DATE_HOLIDAYS"),
+            },
+            products: None,
+        }
+    }
+
+    /// Parses command-line flags, returning them as a layer together with
+    /// the `--config` path if one was given.
+    fn from_args(args: &[String]) -> Result<(Self, Option<PathBuf>), ConfigError> {
+        let mut raw = RawConfig::default();
+        let mut config_path = None;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next().cloned())
+                    .ok_or_else(|| ConfigError::Usage(format!("{flag} needs a value")))
+            };
+
+            match flag {
+                "--config" => config_path = Some(PathBuf::from(value()?)),
+                "--repository-backend" => raw.repository.backend = Some(value()?),
+                "--repository-path" => raw.repository.path = Some(value()?),
+                "--log-level" => raw.logging.level = Some(value()?),
+                "--metrics-listen" => raw.metrics.listen = Some(value()?),
+                "--rpc-listen" => raw.rpc.listen = Some(value()?),
+                "--business-date-cutoff" => raw.business_date.cutoff = Some(value()?),
+                _ => return Err(ConfigError::Usage(format!("unknown option {arg}"))),
+            }
+        }
+
+        Ok((raw, config_path))
+    }
+
+    fn merge(self, over: RawConfig) -> Self {
+        RawConfig {
+            repository: RawRepository {
+                backend: over.repository.backend.or(self.repository.backend),
+                path: over.repository.path.or(self.repository.path),
+            },
+            logging: RawLogging {
+                level: over.logging.level.or(self.logging.level),
+            },
+            metrics: RawMetrics {
+                listen: over.metrics.listen.or(self.metrics.listen),
+            },
+            rpc: RawRpc {
+                listen: over.rpc.listen.or(self.rpc.listen),
+            },
+            business_date: RawBusinessDate {
+                cutoff: over.business_date.cutoff.or(self.business_date.cutoff),
+                non_working_days: over
+                    .business_date
+                    .non_working_days
+                    .or(self.business_date.non_working_days),
+                holidays: over.business_date.holidays.or(self.business_date.holidays),
+            },
+            products: over.products.or(self.products),
+        }
+    }
+
+    /// Checks every value, reporting all problems at once rather than the
+    /// first one found.
+    fn validate(self) -> Result<Config, ConfigError> {
+        let mut errors = Vec::new();
+
+        let repository = match self.repository.backend.as_deref() {
+            Some("memory") => Some(RepositoryConfig::Memory),
+            Some("file") => match self.repository.path.as_deref().map(str::trim) {
+                Some(path) if !path.is_empty() => {
+                    Some(RepositoryConfig::File { path: path.into() })
+                }
+                _ => {
+                    errors.push(
+                        "repository.path: required when repository.backend is \"file\"".into(),
+                    );
+                    None
+                }
+            },
+            backend => {
+                errors.push(format!(
+                    "repository.backend: {:?} is not a backend; expected \"memory\" or \"file\"",
+                    backend.unwrap_or_default()
+                ));
+                None
+            }
+        };
+
+        let log_level = self
+            .logging
+            .level
+            .unwrap_or_default()
+            .parse::<LogLevel>()
+            .map_err(|err| errors.push(format!("logging.level: {err}")))
+            .ok();
+
+        let metrics_listen = self.metrics.listen.and_then(|listen| {
+            listen
+                .parse::<SocketAddr>()
+                .map_err(|_| {
+                    errors.push(format!(
+                        "metrics.listen: {listen:?} is not an address such as 127.0.0.1:9184"
+                    ))
+                })
+                .ok()
+        });
+
+        let listen = self.rpc.listen.unwrap_or_default();
+        let rpc_listen = listen
+            .parse::<SocketAddr>()
+            .map_err(|_| {
+                errors.push(format!(
+                    "rpc.listen: {listen:?} is not an address such as 127.0.0.1:50051"
+                ))
+            })
+            .ok();
+
+        let business_date = validate_business_date(self.business_date, &mut errors);
+
+        let products = self.products.unwrap_or_default();
+        if products.is_empty() {
+            errors.push("products: at least one product must be defined".into());
+        }
+        let mut codes = HashSet::new();
+        let products: Vec<Product> = products
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, product)| {
+                validate_product(index, product, &mut codes, &mut errors)
+            })
+            .collect();
+
+        match (repository, log_level, rpc_listen, business_date) {
+            (Some(repository), Some(log_level), Some(rpc_listen), Some(business_date))
+                if errors.is_empty() =>
+            {
+                Ok(Config {
+                    repository,
+                    log_level,
+                    metrics_listen,
+                    rpc_listen,
+                    business_date,
+                    products,
+                })
+            }
+            _ => Err(ConfigError::Invalid(errors)),
+        }
+    }
+}
+
+fn validate_business_date(
+    raw: RawBusinessDate,
+    errors: &mut Vec<String>,
+) -> Option<BusinessCalendar> {
+    let cutoff = raw.cutoff.unwrap_or_default();
+    let cutoff = NaiveTime::parse_from_str(&cutoff, "%H:%M")
+        .map_err(|_| {
+            errors.push(format!(
+                "business_date.cutoff: {cutoff:?} is not a time in HH:MM form"
+            ))
+        })
+        .ok();
+
+    let mut non_working_days = Vec::new();
+    for day in raw.non_working_days.unwrap_or_default() {
+        match day.parse::<Weekday>() {
+            Ok(day) if !non_working_days.contains(&day) => non_working_days.push(day),
+            Ok(_) => {}
+            Err(_) => errors.push(format!(
+                "business_date.non_working_days: {day:?} is not a weekday"
+            )),
+        }
+    }
+    if non_working_days.len() == 7 {
+        errors
+            .push("business_date.non_working_days: at least one day must be a working day".into());
+    }
+
+    let mut holidays = Vec::new();
+    for date in raw.holidays.unwrap_or_default() {
+        match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+            Ok(date) => holidays.push(date),
+            Err(_) => errors.push(format!(
+                "business_date.holidays: {date:?} is not a date in YYYY-MM-DD form"
+            )),
+        }
+    }
+    holidays.sort();
+    holidays.dedup();
+
+    Some(BusinessCalendar {
+        cutoff: cutoff?,
+        non_working_days,
+        holidays,
+    })
+}
+
+fn validate_product(
+    index: usize,
+    raw: RawProduct,
+    codes: &mut HashSet<String>,
+    errors: &mut Vec<String>,
+) -> Option<Product> {
+    let field = |name: &str| format!("products[{index}].{name}");
+    let before = errors.len();
+
+    let code = raw.code.unwrap_or_default().trim().to_string();
+    if code.is_empty() || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        errors.push(format!(
+            "{}: {code:?} must be a non-empty alphanumeric code",
+            field("code")
+        ));
+    } else if !codes.insert(code.clone()) {
+        errors.push(format!(
+            "{}: {code:?} is defined more than once",
+            field("code")
+        ));
+    }
+
+    let mut amount =
+        |name: &str, raw: Option<RawDecimal>, max: Option<Decimal>| -> Option<Decimal> {
+            let value = match raw.map(|raw| raw.parse()).transpose() {
+                Ok(value) => value,
+                Err(err) => {
+                    errors.push(format!("{}: {err}", field(name)));
+                    return None;
+                }
+            };
+            if let Some(value) = value {
+                if value < Decimal::ZERO {
+                    errors.push(format!("{}: {value} must not be negative", field(name)));
+                } else if max.is_some_and(|max| value > max) {
+                    errors.push(format!(
+                        "{}: {value} must be at most {}",
+                        field(name),
+                        max.unwrap()
+                    ));
+                }
+            }
+            value
+        };
+
+    let interest_rate = amount("interest_rate", raw.interest_rate, Some(Decimal::ONE));
+    let monthly_fee = amount("monthly_fee", raw.monthly_fee, None);
+    let overdraft_limit = amount("overdraft_limit", raw.overdraft_limit, None);
+    let daily_withdrawal_limit = amount("daily_withdrawal_limit", raw.daily_withdrawal_limit, None);
+
+    if errors.len() > before {
+        return None;
+    }
+
+    let mut product = Product::builder(&code)
+        .interest_rate(interest_rate.unwrap_or_default())
+        .monthly_fee(Money(monthly_fee.unwrap_or_default()))
+        .overdraft_limit(Money(overdraft_limit.unwrap_or_default()));
+    if let Some(name) = raw.name {
+        product = product.name(&name);
+    }
+    if let Some(limit) = daily_withdrawal_limit {
+        product = product.daily_withdrawal_limit(Money(limit));
+    }
+    Some(product.build())
+}
+
+/// Builds the configuration from defaults, the config file named by
+/// `--config` or `BANK_CONFIG`, environment variables and flags, in that
+/// order of precedence.
+pub fn load(args: &[String], env: &HashMap<String, String>) -> Result<Config, ConfigError> {
+    let (flags, config_path) = RawConfig::from_args(args)?;
+
+    let mut raw = RawConfig::defaults();
+    if let Some(path) = config_path.or_else(|| env.get("BANK_CONFIG").map(PathBuf::from)) {
+        raw = raw.merge(RawConfig::from_file(&path)?);
+    }
+
+    raw.merge(RawConfig::from_env(env)).merge(flags).validate()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{collections::HashMap, path::Path};
+
+    use bank_core::account::Money;
+    use chrono::{NaiveTime, Weekday};
+    use rust_decimal::Decimal;
+
+    use super::{ConfigError, LogLevel, RawConfig, RepositoryConfig, load};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn env(vars: &[(&str, &str)]) -> HashMap<String, String> {
+        vars.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn invalid(result: Result<super::Config, ConfigError>) -> Vec<String> {
+        match result {
+            Err(ConfigError::Invalid(errors)) => errors,
+            other => panic!("expected validation errors, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_config_will_use_defaults_without_other_sources() {
+        let config = load(&[], &HashMap::new()).unwrap();
+
+        assert_eq!(config.repository, RepositoryConfig::Memory);
+        assert_eq!(config.log_level, LogLevel::Info);
+        assert_eq!(config.metrics_listen, None);
+        assert_eq!(config.rpc_listen, "127.0.0.1:50051".parse().unwrap());
+        assert_eq!(
+            config.business_date.cutoff,
+            NaiveTime::from_hms_opt(17, 0, 0).unwrap()
+        );
+        assert_eq!(
+            config.business_date.non_working_days,
+            vec![Weekday::Sat, Weekday::Sun]
+        );
+        assert_eq!(config.products.len(), 1);
+        assert_eq!(config.products[0].code, "CHK");
+    }
+
+    #[test]
+    fn test_config_will_let_env_override_file_and_flags_override_env() {
+        let example = include_str!("../bank.example.toml");
+        let file = RawConfig::from_toml(Path::new("bank.example.toml"), example).unwrap();
+        let env = env(&[
+            ("BANK_LOG_LEVEL", "debug"),
+            ("BANK_REPOSITORY_PATH", "/var/lib/bank/env.json"),
+            ("BANK_METRICS_LISTEN", "127.0.0.1:9184"),
+        ]);
+        let (flags, _) = RawConfig::from_args(&args(&["--log-level=warn"])).unwrap();
+
+        let config = RawConfig::defaults()
+            .merge(file)
+            .merge(RawConfig::from_env(&env))
+            .merge(flags)
+            .validate()
+            .unwrap();
+
+        assert_eq!(
+            config.repository,
+            RepositoryConfig::File {
+                path: "/var/lib/bank/env.json".into()
+            }
+        );
+        assert_eq!(config.log_level, LogLevel::Warn);
+        assert_eq!(
+            config.metrics_listen,
+            Some("127.0.0.1:9184".parse().unwrap())
+        );
+        assert_eq!(config.products.len(), 2);
+        let savings = &config.products[1];
+        assert_eq!(savings.code, "SAV");
+        assert_eq!(savings.interest_rate, Decimal::new(35, 3));
+        assert_eq!(savings.daily_withdrawal_limit, Some(Money(500.into())));
+        assert_eq!(config.business_date.holidays.len(), 2);
+    }
+
+    #[test]
+    fn test_config_will_report_every_invalid_value() {
+        let file = RawConfig::from_toml(
+            Path::new("bad.toml"),
+            r#"
+            [repository]
+            backend = "file"
+
+            [business_date]
+            cutoff = "25:00"
+            non_working_days = ["Funday"]
+            holidays = ["2026-02-30"]
+
+            [[products]]
+            code = "SAV"
+            interest_rate = "1.5"
+            monthly_fee = -1
+
+            [[products]]
+            code = "SAV"
+            overdraft_limit = "lots"
+            "#,
+        )
+        .unwrap();
+
+        let errors = invalid(RawConfig::defaults().merge(file).validate());
+
+        assert_eq!(errors.len(), 8, "{errors:#?}");
+        assert!(errors.iter().any(|err| err.starts_with("repository.path:")));
+        assert!(
+            errors
+                .iter()
+                .any(|err| err.starts_with("business_date.cutoff:"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|err| err.contains("\"Funday\" is not a weekday"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|err| err.starts_with("business_date.holidays:"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|err| err.starts_with("products[0].interest_rate:"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|err| err.starts_with("products[0].monthly_fee:"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|err| err.contains("defined more than once"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|err| err.starts_with("products[1].overdraft_limit:"))
+        );
+    }
+
+    #[test]
+    fn test_config_will_reject_unknown_backend_level_and_flags() {
+        let errors = invalid(load(
+            &args(&[
+                "--repository-backend",
+                "postgres",
+                "--log-level",
+                "loud",
+                "--metrics-listen",
+                "nowhere",
+                "--rpc-listen",
+                "somewhere",
+            ]),
+            &HashMap::new(),
+        ));
+        assert_eq!(errors.len(), 4);
+
+        assert!(matches!(
+            load(&args(&["--verbose"]), &HashMap::new()),
+            Err(ConfigError::Usage(_))
+        ));
+        assert!(matches!(
+            load(&args(&["--log-level"]), &HashMap::new()),
+            Err(ConfigError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_will_reject_unknown_keys_in_file() {
+        let result =
+            RawConfig::from_toml(Path::new("typo.toml"), "[repository]\nbakend = \"file\"\n");
+
+        assert!(matches!(result, Err(ConfigError::Parse { .. })));
+    }
+
+    #[test]
+    fn test_config_will_fail_clearly_when_file_is_missing() {
+        let result = load(
+            &args(&["--config", "/nonexistent/bank.toml"]),
+            &HashMap::new(),
+        );
+
+        let err = result.unwrap_err();
+        assert!(matches!(err, ConfigError::Read { .. }));
+        assert!(err.to_string().contains("/nonexistent/bank.toml"));
+    }
+}
@@ -1,3 +1,130 @@
-fn main() {
-    println!("Hello, world!");
+mod config;
+mod metrics_server;
+
+use std::{collections::HashMap, net::TcpListener, process::ExitCode, sync::Arc, thread};
+
+use bank_core::{
+    account::AccountRepository,
+    errors::{AppError, ErrorResponse},
+    tenant::Tenant,
+};
+use bank_infra::{
+    file::FileRepo, instrumented::InstrumentedRepo, metrics::Metrics, storage::InMemoryRepo,
+};
+use bank_rpc::server;
+use bank_services::bank::Bank;
+use tracing::Level;
+
+use crate::config::{Config, LogLevel, RepositoryConfig};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", config::USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let env: HashMap<String, String> = std::env::vars().collect();
+    let config = match config::load(&args, &env) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("bank: {err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    tracing_subscriber::fmt()
+        .with_max_level(tracing_level(config.log_level))
+        .with_writer(std::io::stderr)
+        .init();
+
+    let result = match &config.repository {
+        RepositoryConfig::Memory => start(&config, InMemoryRepo::new()),
+        RepositoryConfig::File { path } => match FileRepo::open(path) {
+            Ok(repo) => start(&config, repo),
+            Err(err) => {
+                let response = ErrorResponse::from(&AppError::from(err));
+                eprintln!(
+                    "bank: cannot open repository {}: error[{}]: {}",
+                    path.display(),
+                    response.code,
+                    response.message
+                );
+                return ExitCode::from(response.exit_code());
+            }
+        },
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("bank: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Serves the bank over gRPC until the server fails, with metrics on a
+/// thread of their own if enabled.
+fn start<R>(config: &Config, repo: R) -> Result<(), String>
+where
+    R: AccountRepository + Send + Sync + 'static,
+{
+    let metrics = Arc::new(Metrics::new());
+    let repo = InstrumentedRepo::new(repo, Arc::clone(&metrics));
+    let tenant = Tenant {
+        products: config.products.clone(),
+        ..Tenant::default()
+    };
+    let bank = Bank::new(Arc::new(repo))
+        .with_tenant(tenant)
+        .with_metrics(Arc::clone(&metrics))
+        .with_clock(Arc::new(config.business_date.clone()));
+
+    println!("bank starting with {}", summary(config));
+
+    if let Some(addr) = config.metrics_listen {
+        let listener =
+            TcpListener::bind(addr).map_err(|err| format!("cannot listen on {addr}: {err}"))?;
+        tracing::info!(%addr, "serving metrics at /metrics");
+        thread::spawn(move || metrics_server::serve(listener, &metrics));
+    }
+
+    let runtime = tokio::runtime::Runtime::new()
+        .map_err(|err| format!("cannot start the async runtime: {err}"))?;
+    runtime.block_on(async {
+        let addr = config.rpc_listen;
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|err| format!("cannot listen on {addr}: {err}"))?;
+        tracing::info!(%addr, "serving the bank over gRPC");
+        server::serve(listener, Arc::new(bank))
+            .await
+            .map_err(|err| format!("gRPC server on {addr} failed: {err}"))
+    })
+}
+
+fn tracing_level(level: LogLevel) -> Level {
+    match level {
+        LogLevel::Error => Level::ERROR,
+        LogLevel::Warn => Level::WARN,
+        LogLevel::Info => Level::INFO,
+        LogLevel::Debug => Level::DEBUG,
+        LogLevel::Trace => Level::TRACE,
+    }
+}
+
+fn summary(config: &Config) -> String {
+    let repository = match &config.repository {
+        RepositoryConfig::Memory => "in-memory repository".to_string(),
+        RepositoryConfig::File { path } => format!("file repository at {}", path.display()),
+    };
+    let products: Vec<&str> = config.products.iter().map(|p| p.code.as_str()).collect();
+
+    format!(
+        "{repository}, log level {}, cutoff {}, products [{}]",
+        config.log_level,
+        config.business_date.cutoff.format("%H:%M"),
+        products.join(", ")
+    )
 }
@@ -0,0 +1,126 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    time::Duration,
+};
+
+use bank_infra::metrics::Metrics;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// How long a client may take to send its request or read the response.
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest request line read; anything past it is ignored.
+const MAX_REQUEST_LINE: u64 = 8 * 1024;
+
+/// Builds the HTTP response to a request line such as `GET /metrics HTTP/1.1`.
+pub fn respond(request_line: &str, metrics: &Metrics) -> String {
+    let mut parts = request_line.split_whitespace();
+    let (status, content_type, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", CONTENT_TYPE, metrics.render()),
+        (Some("GET"), _) => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n".to_string(),
+        ),
+    };
+
+    format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Answers scrapes one connection at a time; a scrape is cheap and
+/// Prometheus polls rarely, so nothing more elaborate is needed. A client
+/// that misbehaves only loses its own connection.
+pub fn serve(listener: TcpListener, metrics: &Metrics) {
+    for stream in listener.incoming() {
+        let result = stream.and_then(|stream| answer(stream, metrics));
+        if let Err(err) = result {
+            tracing::warn!(error = %err, "failed to answer metrics scrape");
+        }
+    }
+}
+
+/// Reads the request line and writes the response, giving up on clients
+/// that stall for longer than [`TIMEOUT`] so they cannot hold the server.
+fn answer(mut stream: TcpStream, metrics: &Metrics) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let mut request_line = Vec::new();
+    BufReader::new(&stream)
+        .take(MAX_REQUEST_LINE)
+        .read_until(b'\n', &mut request_line)?;
+
+    let request_line = String::from_utf8_lossy(&request_line);
+    stream.write_all(respond(&request_line, metrics).as_bytes())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{Shutdown, TcpListener, TcpStream},
+        sync::Arc,
+        thread,
+        time::Duration,
+    };
+
+    use bank_infra::metrics::Metrics;
+
+    use super::{respond, serve};
+
+    #[test]
+    fn test_metrics_server_will_serve_prometheus_text_on_metrics_path() {
+        let metrics = Metrics::new();
+        metrics.record_operation("process", "ok", Duration::from_micros(10));
+
+        let response = respond("GET /metrics HTTP/1.1\r\n", &metrics);
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        assert!(response.ends_with(&metrics.render()));
+        assert!(response.contains(r#"bank_operations_total{operation="process",outcome="ok"} 1"#));
+    }
+
+    #[test]
+    fn test_metrics_server_will_reject_other_paths_and_methods() {
+        let metrics = Metrics::new();
+
+        assert!(respond("GET / HTTP/1.1", &metrics).starts_with("HTTP/1.1 404"));
+        assert!(respond("POST /metrics HTTP/1.1", &metrics).starts_with("HTTP/1.1 405"));
+        assert!(respond("", &metrics).starts_with("HTTP/1.1 405"));
+    }
+
+    #[test]
+    fn test_metrics_server_will_keep_serving_after_bad_clients() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let metrics = Arc::new(Metrics::new());
+        let served = Arc::clone(&metrics);
+        thread::spawn(move || serve(listener, &served));
+
+        let request = |bytes: &[u8]| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(bytes).unwrap();
+            stream.shutdown(Shutdown::Write).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let garbage = request(b"\xff\xfe\x00 not http\r\n");
+        assert!(garbage.starts_with("HTTP/1.1 405"));
+        drop(TcpStream::connect(addr).unwrap());
+        assert!(request(b"").starts_with("HTTP/1.1 405"));
+
+        let response = request(b"GET /metrics HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(&metrics.render()));
+    }
+}
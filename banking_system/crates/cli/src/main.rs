@@ -1,3 +1,184 @@
-fn main() {
-    println!("Hello, world!");
+use std::{process::ExitCode, str::FromStr, sync::Arc};
+
+use bank_core::{
+    account::{AccountId, Money, Transaction},
+    context::RequestContext,
+    errors::{AppError, ErrorResponse},
+};
+use bank_infra::file::FileRepo;
+use bank_services::bank::Bank;
+use rust_decimal::Decimal;
+
+const USAGE: &str = "\
+Usage: bank-cli <REPOSITORY> <COMMAND>
+
+Commands:
+  open <OWNER>                  open an account for a customer and print its id
+  balance <ACCOUNT>             print the balance of an account
+  deposit <ACCOUNT> <AMOUNT>    deposit into an account
+  withdraw <ACCOUNT> <AMOUNT>   withdraw from an account
+  transfer <FROM> <TO> <AMOUNT> transfer between two accounts
+
+REPOSITORY is the data file of a file repository. Errors are printed with
+their code and the process exits with a sysexits.h status.";
+
+/// `sysexits.h` status for a command line that could not be parsed.
+const EX_USAGE: u8 = 64;
+
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    App(AppError),
+}
+
+impl From<AppError> for CliError {
+    fn from(err: AppError) -> Self {
+        CliError::App(err)
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    match run(&args) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(CliError::Usage(message)) => {
+            eprintln!("bank-cli: {message}\n\n{USAGE}");
+            ExitCode::from(EX_USAGE)
+        }
+        Err(CliError::App(err)) => {
+            let response = ErrorResponse::from(&err);
+            eprintln!("{}", report(&response));
+            ExitCode::from(response.exit_code())
+        }
+    }
+}
+
+/// Runs one command against the repository named first in `args` and
+/// returns what to print.
+fn run(args: &[String]) -> Result<String, CliError> {
+    let [repository, command, rest @ ..] = args else {
+        return Err(CliError::Usage("missing repository or command".into()));
+    };
+    let repo = FileRepo::open(repository).map_err(AppError::from)?;
+    let bank = Bank::new(Arc::new(repo));
+    let ctx = RequestContext::system();
+
+    match (command.as_str(), rest) {
+        ("open", [owner]) => {
+            let account_id = bank.create_account_as(&ctx, parse("owner", owner)?)?;
+            Ok(account_id.to_string())
+        }
+        ("balance", [account]) => {
+            let account = bank.account_as(&ctx, parse("account", account)?)?;
+            Ok(account.balance.to_string())
+        }
+        ("deposit", [account, amount]) => {
+            let txn = Transaction::Deposit(money(amount)?);
+            Ok(bank
+                .process_as(&ctx, parse("account", account)?, txn)?
+                .to_string())
+        }
+        ("withdraw", [account, amount]) => {
+            let txn = Transaction::Withdraw(money(amount)?);
+            Ok(bank
+                .process_as(&ctx, parse("account", account)?, txn)?
+                .to_string())
+        }
+        ("transfer", [from, to, amount]) => {
+            let txn = Transaction::Transfer {
+                to: parse("account", to)?,
+                amount: money(amount)?,
+            };
+            Ok(bank
+                .process_as(&ctx, parse("account", from)?, txn)?
+                .to_string())
+        }
+        _ => Err(CliError::Usage(format!(
+            "unknown command or wrong arguments: {}",
+            args[1..].join(" ")
+        ))),
+    }
+}
+
+/// The lines printed for a failed command: the message with its code,
+/// then one line per detail.
+fn report(response: &ErrorResponse) -> String {
+    let mut lines = vec![format!(
+        "bank-cli: error[{}]: {}",
+        response.code, response.message
+    )];
+    lines.extend(
+        response
+            .details
+            .iter()
+            .map(|(key, value)| format!("  {key}: {value}")),
+    );
+    lines.join("\n")
+}
+
+fn parse(what: &str, value: &str) -> Result<AccountId, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError::Usage(format!("invalid {what} {value:?}")))
+}
+
+fn money(value: &str) -> Result<Money, CliError> {
+    Decimal::from_str(value)
+        .map(Money)
+        .map_err(|_| CliError::Usage(format!("invalid amount {value:?}")))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use bank_core::errors::ErrorResponse;
+
+    use super::{CliError, report, run};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_cli_will_report_errors_with_code_and_exit_status() {
+        let path = std::env::temp_dir().join(format!("bank-cli-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let repo = path.to_str().unwrap();
+
+        let account = run(&args(&[repo, "open", "1"])).unwrap();
+        assert_eq!(
+            run(&args(&[repo, "deposit", &account, "50"])).unwrap(),
+            "50"
+        );
+
+        let Err(CliError::App(err)) = run(&args(&[repo, "withdraw", &account, "80"])) else {
+            panic!("withdrawing more than the balance must fail");
+        };
+        let response = ErrorResponse::from(&err);
+        assert_eq!(response.code, "insufficient_funds");
+        assert_eq!(response.exit_code(), 65);
+        let report = report(&response);
+        assert!(report.starts_with("bank-cli: error[insufficient_funds]: "));
+        assert!(report.contains("  requested: 80"));
+
+        let Err(CliError::App(err)) = run(&args(&[repo, "balance", "999"])) else {
+            panic!("an unknown account must fail");
+        };
+        assert_eq!(ErrorResponse::from(&err).exit_code(), 66);
+        assert_eq!(run(&args(&[repo, "balance", &account])).unwrap(), "50");
+
+        assert!(matches!(
+            run(&args(&[repo, "withdraw", &account])),
+            Err(CliError::Usage(_))
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+    }
 }
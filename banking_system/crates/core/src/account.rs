@@ -1,22 +1,165 @@
+use std::fmt;
+
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    account_number::AccountNumber,
     customer::CustomerId,
-    errors::{DomainError, RepoError},
+    errors::{AccountOperation, DomainError, RepoError},
+    product::Product,
+    query::{AccountQuery, MAX_PAGE_SIZE, Page, PageRequest},
+    tenant::TenantId,
 };
 
 pub type AccountId = u64;
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Money(pub Decimal);
 
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Stores accounts partitioned by tenant. Every call names the tenant it
+/// acts for: accounts of other tenants are invisible to reads and `modify`,
+/// and writes of accounts that belong elsewhere fail with
+/// [`RepoError::TenantMismatch`].
 pub trait AccountRepository {
-    fn create(&self, account: Account) -> Result<(), RepoError>;
-    fn get(&self, id: AccountId) -> Result<Option<Account>, RepoError>;
-    fn update(&self, account: Account) -> Result<(), RepoError>;
+    /// Allocates a fresh account id. The sequence belongs to the repository
+    /// so that persistent backends never hand out an id twice, even across
+    /// restarts. Ids are unique across tenants.
+    fn next_id(&self) -> Result<AccountId, RepoError>;
+    fn create(&self, tenant: &TenantId, account: Account) -> Result<(), RepoError>;
+    fn get(&self, tenant: &TenantId, id: AccountId) -> Result<Option<Account>, RepoError>;
+    fn update(&self, tenant: &TenantId, account: Account) -> Result<(), RepoError>;
+
+    /// Returns the tenant's accounts matching `query`, ordered by id, one
+    /// page at a time.
+    fn list(
+        &self,
+        tenant: &TenantId,
+        query: &AccountQuery,
+        page: PageRequest,
+    ) -> Result<Page<Account>, RepoError>;
+
+    /// Finds an account by its external number. The default implementation
+    /// pages through every account of the tenant.
+    fn get_by_number(
+        &self,
+        tenant: &TenantId,
+        number: &AccountNumber,
+    ) -> Result<Option<Account>, RepoError> {
+        let mut page = PageRequest::first(MAX_PAGE_SIZE);
+        loop {
+            let accounts = self.list(tenant, &AccountQuery::default(), page)?;
+            if let Some(account) = accounts
+                .items
+                .into_iter()
+                .find(|account| account.number.as_ref() == Some(number))
+            {
+                return Ok(Some(account));
+            }
+            match accounts.next {
+                Some(cursor) => page = PageRequest::next(cursor, MAX_PAGE_SIZE),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Stores a new `account` once `check` accepts the tenant's other
+    /// accounts with the same primary owner. Implementations that can should
+    /// check and store in one step, so that accounts opened at the same time
+    /// for one owner are each checked against the others.
+    ///
+    /// The default implementation pages through every account of the tenant
+    /// and is only atomic when the repository is not shared between threads.
+    fn create_checked<E>(
+        &self,
+        tenant: &TenantId,
+        account: Account,
+        check: impl FnOnce(&[Account]) -> Result<(), E>,
+    ) -> Result<(), E>
+    where
+        E: From<RepoError>,
+    {
+        let mut owned = Vec::new();
+        let mut page = PageRequest::first(MAX_PAGE_SIZE);
+        loop {
+            let accounts = self.list(tenant, &AccountQuery::default(), page)?;
+            owned.extend(
+                accounts
+                    .items
+                    .into_iter()
+                    .filter(|stored| stored.owner == account.owner),
+            );
+            match accounts.next {
+                Some(cursor) => page = PageRequest::next(cursor, MAX_PAGE_SIZE),
+                None => break,
+            }
+        }
+
+        check(&owned)?;
+        Ok(self.create(tenant, account)?)
+    }
+
+    /// Loads the accounts in `ids`, hands them to `f` in the same order and
+    /// stores them back only if `f` succeeds. Implementations that can should
+    /// hold every account exclusively for the duration so that concurrent
+    /// read-modify-write cycles (such as transfers) cannot interleave.
+    ///
+    /// The default implementation is built on `get` and `update` and is only
+    /// atomic when the repository is not shared between threads.
+    fn modify<T, E>(
+        &self,
+        tenant: &TenantId,
+        ids: &[AccountId],
+        f: impl FnOnce(&mut [Account]) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        E: From<RepoError>,
+    {
+        for (index, id) in ids.iter().enumerate() {
+            if ids[..index].contains(id) {
+                return Err(RepoError::DuplicateId(*id).into());
+            }
+        }
+
+        let mut accounts = ids
+            .iter()
+            .map(|id| self.get(tenant, *id)?.ok_or(RepoError::NotFound(*id)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let value = f(&mut accounts)?;
+
+        for account in accounts {
+            self.update(tenant, account)?;
+        }
+
+        Ok(value)
+    }
+}
+
+/// Checks that `tenant` may store `account` over `stored`, the account
+/// currently kept under the same id, for repository implementations.
+pub fn ensure_tenant(
+    tenant: &TenantId,
+    account: &Account,
+    stored: Option<&Account>,
+) -> Result<(), RepoError> {
+    if account.tenant != *tenant || stored.is_some_and(|stored| stored.tenant != *tenant) {
+        return Err(RepoError::TenantMismatch {
+            account_id: account.id,
+            tenant: tenant.clone(),
+        });
+    }
+    Ok(())
 }
 
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AccountStatus {
     #[default]
     Active,
@@ -24,18 +167,93 @@ pub enum AccountStatus {
     Closed,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Transaction {
     Deposit(Money),
     Withdraw(Money),
     Transfer { to: AccountId, amount: Money },
 }
 
-#[derive(Debug, Default, Clone, PartialEq)]
+impl Transaction {
+    pub fn amount(&self) -> Money {
+        match self {
+            Transaction::Deposit(amount) | Transaction::Withdraw(amount) => *amount,
+            Transaction::Transfer { amount, .. } => *amount,
+        }
+    }
+
+    /// Short lowercase name of the variant, for logs and metric labels.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Transaction::Deposit(_) => "deposit",
+            Transaction::Withdraw(_) => "withdraw",
+            Transaction::Transfer { .. } => "transfer",
+        }
+    }
+
+    /// Whether the transaction moves money out of the account it is applied to.
+    pub fn is_debit(&self) -> bool {
+        !matches!(self, Transaction::Deposit(_))
+    }
+}
+
+/// What a customer listed on an account is allowed to do with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HolderRole {
+    /// Full control, including managing the other holders.
+    Owner,
+    /// May move money out of the account but not manage it.
+    Signatory,
+    /// Read-only access.
+    Viewer,
+}
+
+impl HolderRole {
+    pub fn can_transact(&self) -> bool {
+        matches!(self, HolderRole::Owner | HolderRole::Signatory)
+    }
+
+    pub fn can_manage(&self) -> bool {
+        matches!(self, HolderRole::Owner)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountHolder {
+    pub customer: CustomerId,
+    pub role: HolderRole,
+}
+
+/// Money withdrawn from an account on one business day, for
+/// [`Product::daily_withdrawal_limit`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DailyWithdrawals {
+    pub date: NaiveDate,
+    pub total: Money,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Account {
     pub id: AccountId,
+    /// Accounts stored before tenants existed belong to the default tenant.
+    #[serde(default)]
+    pub tenant: TenantId,
+    /// External-facing number, if the bank issues them.
+    pub number: Option<AccountNumber>,
+    /// The primary owner, always treated as holding [`HolderRole::Owner`].
     pub owner: CustomerId,
     pub balance: Money,
     pub status: AccountStatus,
+    /// Joint holders and signatories in addition to `owner`.
+    pub holders: Vec<AccountHolder>,
+    /// Debits strictly above this amount need a second holder's approval.
+    pub approval_threshold: Option<Money>,
+    /// Code of the tenant's product the account was opened on, if any.
+    #[serde(default)]
+    pub product: Option<String>,
+    /// Withdrawals under the product's terms on the latest day with any.
+    #[serde(default)]
+    pub withdrawn: Option<DailyWithdrawals>,
 }
 
 impl Account {
@@ -49,15 +267,14 @@ impl Account {
 
     pub fn deposit(&mut self, amount: Money) -> Result<Money, DomainError> {
         if amount.0 <= 0.into() {
-            return Err(DomainError::NegativeAmount(
-                "amount must be greater than zero".to_string(),
-            ));
+            return Err(DomainError::InvalidAmount {
+                account_id: self.id,
+                amount,
+            });
         }
 
         if let AccountStatus::Closed = self.status {
-            return Err(DomainError::ClosedAccount(
-                "cannot deposit money into a closed account".to_string(),
-            ));
+            return Err(self.not_active(AccountOperation::Deposit));
         }
 
         self.balance.0 += amount.0;
@@ -66,30 +283,67 @@ impl Account {
     }
 
     pub fn withdraw(&mut self, amount: Money) -> Result<Money, DomainError> {
+        self.debit(amount, Money::default())
+    }
+
+    /// Withdraws `amount` on the business day `today` under `product`'s
+    /// terms: the balance may fall as far below zero as the overdraft
+    /// limit, and at most the daily withdrawal limit leaves per day.
+    pub fn withdraw_under(
+        &mut self,
+        amount: Money,
+        product: &Product,
+        today: NaiveDate,
+    ) -> Result<Money, DomainError> {
+        let withdrawn = self
+            .withdrawn
+            .filter(|withdrawn| withdrawn.date == today)
+            .map_or(Decimal::ZERO, |withdrawn| withdrawn.total.0);
+        if let Some(limit) = product.daily_withdrawal_limit {
+            if withdrawn + amount.0 > limit.0 {
+                return Err(DomainError::DailyWithdrawalLimitExceeded {
+                    account_id: self.id,
+                    requested: amount,
+                    remaining: Money((limit.0 - withdrawn).max(Decimal::ZERO)),
+                });
+            }
+        }
+
+        let balance = self.debit(amount, product.overdraft_limit)?;
+        self.withdrawn = Some(DailyWithdrawals {
+            date: today,
+            total: Money(withdrawn + amount.0),
+        });
+        Ok(balance)
+    }
+
+    /// Charges `product`'s monthly fee, which may use the overdraft but
+    /// does not count towards the daily withdrawal limit.
+    pub fn charge_fee(&mut self, product: &Product) -> Result<Money, DomainError> {
+        self.debit(product.monthly_fee, product.overdraft_limit)
+    }
+
+    /// Takes `amount` out, letting the balance go as far as `overdraft`
+    /// below zero.
+    fn debit(&mut self, amount: Money, overdraft: Money) -> Result<Money, DomainError> {
         if amount.0 <= 0.into() {
-            return Err(DomainError::NegativeAmount(
-                "amount must be greater than zero".to_string(),
-            ));
+            return Err(DomainError::InvalidAmount {
+                account_id: self.id,
+                amount,
+            });
         }
 
-        if amount.0 > self.balance.0 {
-            return Err(DomainError::InsufficientFunds(
-                "insufficient balance".into(),
-            ));
+        let available = Money(self.balance.0 + overdraft.0);
+        if amount.0 > available.0 {
+            return Err(DomainError::InsufficientFunds {
+                account_id: self.id,
+                requested: amount,
+                available,
+            });
         }
 
-        match self.status {
-            AccountStatus::Closed => {
-                return Err(DomainError::ClosedAccount(
-                    "cannot withdraw money from a closed account".to_string(),
-                ));
-            }
-            AccountStatus::Frozen => {
-                return Err(DomainError::FrozenAccount(
-                    "cannot withdraw money from a frozen account".to_string(),
-                ));
-            }
-            _ => {}
+        if self.status != AccountStatus::Active {
+            return Err(self.not_active(AccountOperation::Withdraw));
         }
 
         self.balance.0 -= amount.0;
@@ -97,11 +351,110 @@ impl Account {
         Ok(self.balance)
     }
 
+    pub fn freeze(&mut self) -> Result<(), DomainError> {
+        if let AccountStatus::Closed = self.status {
+            return Err(self.not_active(AccountOperation::Freeze));
+        }
+
+        self.status = AccountStatus::Frozen;
+        Ok(())
+    }
+
+    /// Closes the account; it must have been emptied first.
+    pub fn close(&mut self) -> Result<(), DomainError> {
+        if let AccountStatus::Closed = self.status {
+            return Err(self.not_active(AccountOperation::Close));
+        }
+
+        if !self.balance.0.is_zero() {
+            return Err(DomainError::BalanceNotZero {
+                account_id: self.id,
+                balance: self.balance,
+            });
+        }
+
+        self.status = AccountStatus::Closed;
+        Ok(())
+    }
+
+    pub fn role_of(&self, customer: CustomerId) -> Option<HolderRole> {
+        if customer == self.owner {
+            return Some(HolderRole::Owner);
+        }
+
+        self.holders
+            .iter()
+            .find(|holder| holder.customer == customer)
+            .map(|holder| holder.role)
+    }
+
+    /// Adds `customer` as a holder, or changes their role if already listed.
+    pub fn add_holder(
+        &mut self,
+        customer: CustomerId,
+        role: HolderRole,
+    ) -> Result<(), DomainError> {
+        if customer == self.owner {
+            return Err(DomainError::PrimaryOwnerImmutable {
+                account_id: self.id,
+            });
+        }
+
+        match self
+            .holders
+            .iter_mut()
+            .find(|holder| holder.customer == customer)
+        {
+            Some(holder) => holder.role = role,
+            None => self.holders.push(AccountHolder { customer, role }),
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_holder(&mut self, customer: CustomerId) -> Result<(), DomainError> {
+        if customer == self.owner {
+            return Err(DomainError::PrimaryOwnerImmutable {
+                account_id: self.id,
+            });
+        }
+
+        let before = self.holders.len();
+        self.holders.retain(|holder| holder.customer != customer);
+
+        if self.holders.len() == before {
+            return Err(DomainError::NotAHolder {
+                account_id: self.id,
+                customer,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn requires_approval(&self, txn: &Transaction) -> bool {
+        match self.approval_threshold {
+            Some(threshold) => txn.is_debit() && txn.amount().0 > threshold.0,
+            None => false,
+        }
+    }
+
     pub fn apply_transaction(&mut self, txn: Transaction) -> Result<Money, DomainError> {
         match txn {
             Transaction::Deposit(amount) => self.deposit(amount),
             Transaction::Withdraw(amount) => self.withdraw(amount),
-            Transaction::Transfer { .. } => Err(DomainError::Unsupported("use Bank::transfer instead".into())),
+            Transaction::Transfer { .. } => Err(DomainError::UnsupportedTransaction {
+                account_id: self.id,
+                kind: txn.kind(),
+            }),
+        }
+    }
+
+    fn not_active(&self, operation: AccountOperation) -> DomainError {
+        DomainError::AccountNotActive {
+            account_id: self.id,
+            status: self.status.clone(),
+            operation,
         }
     }
 }
@@ -109,12 +462,32 @@ impl Account {
 #[derive(Debug, Default)]
 pub struct AccountBuilder {
     pub id: AccountId,
+    pub tenant: TenantId,
     pub owner: CustomerId,
     pub balance: Option<Money>,
     pub status: Option<AccountStatus>,
+    pub holders: Vec<AccountHolder>,
+    pub approval_threshold: Option<Money>,
+    pub number: Option<AccountNumber>,
+    pub product: Option<String>,
 }
 
 impl AccountBuilder {
+    pub fn tenant(mut self, tenant: TenantId) -> Self {
+        self.tenant = tenant;
+        self
+    }
+
+    pub fn product(mut self, code: &str) -> Self {
+        self.product = Some(code.into());
+        self
+    }
+
+    pub fn number(mut self, number: AccountNumber) -> Self {
+        self.number = Some(number);
+        self
+    }
+
     pub fn balance(mut self, balance: Money) -> Self {
         self.balance = Some(balance);
         self
@@ -125,22 +498,41 @@ impl AccountBuilder {
         self
     }
 
+    pub fn holder(mut self, customer: CustomerId, role: HolderRole) -> Self {
+        self.holders.push(AccountHolder { customer, role });
+        self
+    }
+
+    pub fn approval_threshold(mut self, threshold: Money) -> Self {
+        self.approval_threshold = Some(threshold);
+        self
+    }
+
     pub fn build(self) -> Account {
         Account {
             id: self.id,
+            tenant: self.tenant,
+            number: self.number,
             owner: self.owner,
             balance: self.balance.unwrap_or_default(),
             status: self.status.unwrap_or_default(),
+            holders: self.holders,
+            approval_threshold: self.approval_threshold,
+            product: self.product,
+            withdrawn: None,
         }
     }
 }
 
 #[cfg(test)]
 pub mod tests {
+    use chrono::NaiveDate;
+
     use crate::{
-        account::{Account, AccountStatus, Money},
+        account::{Account, AccountStatus, HolderRole, Money, Transaction},
         customer::Customer,
-        errors::DomainError,
+        errors::{AccountOperation, DomainError},
+        product::Product,
     };
 
     #[test]
@@ -182,12 +574,14 @@ pub mod tests {
 
         let deposit = account.deposit(Money(0.into()));
 
-        assert!(matches!(deposit, Err(DomainError::NegativeAmount(_))));
-        assert!(deposit.is_err());
         assert_eq!(
-            deposit.err().unwrap(),
-            DomainError::NegativeAmount("amount must be greater than zero".to_string())
+            deposit,
+            Err(DomainError::InvalidAmount {
+                account_id: 1,
+                amount: Money(0.into())
+            })
         );
+        assert_eq!(deposit.unwrap_err().code(), "invalid_amount");
     }
 
     #[test]
@@ -201,11 +595,13 @@ pub mod tests {
 
         let deposit = account.deposit(Money(10.into()));
 
-        assert!(matches!(deposit, Err(DomainError::ClosedAccount(_))));
-        assert!(deposit.is_err());
         assert_eq!(
-            deposit.err().unwrap(),
-            DomainError::ClosedAccount("cannot deposit money into a closed account".to_string())
+            deposit,
+            Err(DomainError::AccountNotActive {
+                account_id: 1,
+                status: AccountStatus::Closed,
+                operation: AccountOperation::Deposit,
+            })
         );
     }
 
@@ -255,11 +651,15 @@ pub mod tests {
 
         let withdraw = account.withdraw(Money(101.into()));
 
-        assert!(withdraw.is_err());
         assert_eq!(
-            withdraw.err().unwrap(),
-            DomainError::InsufficientFunds("insufficient balance".into())
+            withdraw,
+            Err(DomainError::InsufficientFunds {
+                account_id: 1,
+                requested: Money(101.into()),
+                available: Money(100.into()),
+            })
         );
+        assert_eq!(account.balance, Money(100.into()));
     }
 
     #[test]
@@ -275,14 +675,17 @@ pub mod tests {
 
         let withdraw = account.withdraw(Money(10.into()));
 
-        assert!(withdraw.is_err());
         assert_eq!(
-            withdraw.err().unwrap(),
-            DomainError::FrozenAccount("cannot withdraw money from a frozen account".to_string())
+            withdraw,
+            Err(DomainError::AccountNotActive {
+                account_id: 1,
+                status: AccountStatus::Frozen,
+                operation: AccountOperation::Withdraw,
+            })
         );
     }
 
-        #[test]
+    #[test]
     fn test_will_not_allow_withdrawal_for_closed_account() {
         let customer = Customer::builder(1).build();
         let mut account = Account::builder(1, customer.id)
@@ -295,10 +698,148 @@ pub mod tests {
 
         let withdraw = account.withdraw(Money(10.into()));
 
-        assert!(withdraw.is_err());
         assert_eq!(
-            withdraw.err().unwrap(),
-            DomainError::ClosedAccount("cannot withdraw money from a closed account".to_string())
+            withdraw,
+            Err(DomainError::AccountNotActive {
+                account_id: 1,
+                status: AccountStatus::Closed,
+                operation: AccountOperation::Withdraw,
+            })
+        );
+    }
+
+    #[test]
+    fn test_account_will_resolve_holder_roles() {
+        let owner = Customer::builder(1).build();
+        let signatory = Customer::builder(2).build();
+        let account = Account::builder(1, owner.id)
+            .holder(signatory.id, HolderRole::Signatory)
+            .build();
+
+        assert_eq!(account.role_of(owner.id), Some(HolderRole::Owner));
+        assert_eq!(account.role_of(signatory.id), Some(HolderRole::Signatory));
+        assert_eq!(account.role_of(3), None);
+    }
+
+    #[test]
+    fn test_account_will_update_role_of_existing_holder() {
+        let owner = Customer::builder(1).build();
+        let mut account = Account::builder(1, owner.id).build();
+
+        assert!(account.add_holder(2, HolderRole::Viewer).is_ok());
+        assert!(account.add_holder(2, HolderRole::Signatory).is_ok());
+
+        assert_eq!(account.holders.len(), 1);
+        assert_eq!(account.role_of(2), Some(HolderRole::Signatory));
+    }
+
+    #[test]
+    fn test_account_will_not_allow_primary_owner_to_be_removed() {
+        let owner = Customer::builder(1).build();
+        let mut account = Account::builder(1, owner.id).build();
+
+        assert_eq!(
+            account.remove_holder(owner.id),
+            Err(DomainError::PrimaryOwnerImmutable { account_id: 1 })
+        );
+        assert_eq!(
+            account.remove_holder(2),
+            Err(DomainError::NotAHolder {
+                account_id: 1,
+                customer: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_account_will_require_approval_only_for_debits_above_threshold() {
+        let owner = Customer::builder(1).build();
+        let account = Account::builder(1, owner.id)
+            .approval_threshold(Money(100.into()))
+            .build();
+
+        assert!(!account.requires_approval(&Transaction::Withdraw(Money(100.into()))));
+        assert!(account.requires_approval(&Transaction::Withdraw(Money(101.into()))));
+        assert!(account.requires_approval(&Transaction::Transfer {
+            to: 2,
+            amount: Money(500.into())
+        }));
+        assert!(!account.requires_approval(&Transaction::Deposit(Money(500.into()))));
+    }
+
+    #[test]
+    fn test_account_will_only_close_when_empty() {
+        let customer = Customer::builder(1).build();
+        let mut account = Account::builder(1, customer.id)
+            .balance(Money(5.into()))
+            .build();
+
+        assert_eq!(
+            account.close(),
+            Err(DomainError::BalanceNotZero {
+                account_id: 1,
+                balance: Money(5.into())
+            })
+        );
+
+        account.withdraw(Money(5.into())).unwrap();
+        assert!(account.close().is_ok());
+        assert_eq!(account.status, AccountStatus::Closed);
+        assert!(matches!(
+            account.close(),
+            Err(DomainError::AccountNotActive {
+                operation: AccountOperation::Close,
+                ..
+            })
+        ));
+        assert_eq!(account.freeze().unwrap_err().code(), "account_closed");
+    }
+
+    #[test]
+    fn test_account_will_withdraw_within_product_overdraft_and_daily_limit() {
+        let product = Product::builder("CHK")
+            .overdraft_limit(Money(50.into()))
+            .daily_withdrawal_limit(Money(120.into()))
+            .monthly_fee(Money(20.into()))
+            .build();
+        let monday = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let mut account = Account::builder(1, 1).balance(Money(100.into())).build();
+
+        assert_eq!(
+            account.withdraw_under(Money(151.into()), &product, monday),
+            Err(DomainError::DailyWithdrawalLimitExceeded {
+                account_id: 1,
+                requested: Money(151.into()),
+                remaining: Money(120.into()),
+            })
+        );
+        assert_eq!(
+            account.withdraw_under(Money(110.into()), &product, monday),
+            Ok(Money((-10).into()))
+        );
+        assert_eq!(
+            account.withdraw_under(Money(20.into()), &product, monday),
+            Err(DomainError::DailyWithdrawalLimitExceeded {
+                account_id: 1,
+                requested: Money(20.into()),
+                remaining: Money(10.into()),
+            })
+        );
+
+        let tuesday = monday.succ_opt().unwrap();
+        assert_eq!(
+            account.withdraw_under(Money(41.into()), &product, tuesday),
+            Err(DomainError::InsufficientFunds {
+                account_id: 1,
+                requested: Money(41.into()),
+                available: Money(40.into()),
+            })
+        );
+        assert_eq!(account.charge_fee(&product), Ok(Money((-30).into())));
+        assert_eq!(
+            account.withdraw_under(Money(20.into()), &product, tuesday),
+            Ok(Money((-50).into()))
         );
+        assert!(account.withdraw(Money(1.into())).is_err());
     }
 }
@@ -0,0 +1,263 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::AccountId,
+    errors::{AccountNumberProblem, DomainError},
+};
+
+/// Customer-facing account number in IBAN layout: a two-letter country code,
+/// two check digits and an alphanumeric body, validated with ISO 7064
+/// mod-97-10 like an IBAN.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct AccountNumber(String);
+
+impl AccountNumber {
+    /// Accepts the printed form too: spaces are ignored and letters may be
+    /// lower case.
+    pub fn parse(s: &str) -> Result<Self, DomainError> {
+        let number: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+
+        let invalid = |problem| DomainError::InvalidAccountNumber {
+            input: s.to_string(),
+            problem,
+        };
+
+        if !(5..=34).contains(&number.len()) {
+            return Err(invalid(AccountNumberProblem::Length));
+        }
+        // Checked before slicing, which is by byte.
+        if !number.is_ascii() {
+            return Err(invalid(AccountNumberProblem::NotAlphanumeric));
+        }
+        if !number[..2].chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(invalid(AccountNumberProblem::CountryCode));
+        }
+        if !number[2..4].chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid(AccountNumberProblem::CheckDigitsNotNumeric));
+        }
+        if !number[4..].chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid(AccountNumberProblem::NotAlphanumeric));
+        }
+        if mod97(&rearranged(&number)) != 1 {
+            return Err(invalid(AccountNumberProblem::ChecksumMismatch));
+        }
+
+        Ok(Self(number))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn check_digits(&self) -> u8 {
+        self.0[2..4].parse().unwrap()
+    }
+}
+
+/// Moves the country code and check digits to the end, as the checksum is
+/// defined over that form.
+fn rearranged(number: &str) -> String {
+    format!("{}{}", &number[4..], &number[..4])
+}
+
+/// Remainder of the number formed by replacing each letter with its value
+/// (A = 10 ... Z = 35), computed digit by digit so it never overflows.
+fn mod97(s: &str) -> u32 {
+    s.chars().fold(0, |remainder, c| {
+        let value = c.to_digit(36).unwrap();
+        if value < 10 {
+            (remainder * 10 + value) % 97
+        } else {
+            (remainder * 100 + value) % 97
+        }
+    })
+}
+
+impl fmt::Display for AccountNumber {
+    /// Prints in groups of four characters, as IBANs are usually written.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let groups: Vec<&str> = self
+            .0
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap())
+            .collect();
+        write!(f, "{}", groups.join(" "))
+    }
+}
+
+impl FromStr for AccountNumber {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for AccountNumber {
+    type Error = DomainError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s)
+    }
+}
+
+impl From<AccountNumber> for String {
+    fn from(number: AccountNumber) -> Self {
+        number.0
+    }
+}
+
+/// Issues account numbers for one bank: the body is the bank code followed
+/// by the zero-padded account id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountNumbering {
+    pub country: String,
+    pub bank_code: String,
+}
+
+impl AccountNumbering {
+    pub fn new(country: &str, bank_code: &str) -> Result<Self, DomainError> {
+        let numbering = Self {
+            country: country.to_ascii_uppercase(),
+            bank_code: bank_code.to_ascii_uppercase(),
+        };
+        let invalid = |problem| DomainError::InvalidAccountNumber {
+            input: format!("{country}{bank_code}"),
+            problem,
+        };
+        if !numbering.country.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(invalid(AccountNumberProblem::CountryCode));
+        }
+        if !numbering
+            .bank_code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(invalid(AccountNumberProblem::NotAlphanumeric));
+        }
+        numbering.issue(0)?;
+        Ok(numbering)
+    }
+
+    pub fn issue(&self, id: AccountId) -> Result<AccountNumber, DomainError> {
+        let unchecked = format!("{}00{}{:010}", self.country, self.bank_code, id);
+        let check = 98 - mod97(&rearranged(&unchecked));
+        AccountNumber::parse(&format!(
+            "{}{:02}{}{:010}",
+            self.country, check, self.bank_code, id
+        ))
+    }
+}
+
+/// An account referred to by internal id or by external number.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountRef {
+    Id(AccountId),
+    Number(AccountNumber),
+}
+
+impl FromStr for AccountRef {
+    type Err = DomainError;
+
+    /// All-digit input is an internal id; anything else must be a valid
+    /// account number.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse::<AccountId>() {
+            Ok(id) => Ok(AccountRef::Id(id)),
+            Err(_) => Ok(AccountRef::Number(s.parse()?)),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{
+        account_number::{AccountNumber, AccountNumbering, AccountRef},
+        errors::{AccountNumberProblem, DomainError},
+    };
+
+    #[test]
+    fn test_account_number_will_accept_valid_iban() {
+        let number = AccountNumber::parse("gb82 west 1234 5698 7654 32").unwrap();
+
+        assert_eq!(number.as_str(), "GB82WEST12345698765432");
+        assert_eq!(number.check_digits(), 82);
+        assert_eq!(number.to_string(), "GB82 WEST 1234 5698 7654 32");
+    }
+
+    #[test]
+    fn test_account_number_will_reject_bad_check_digits_and_format() {
+        let problem = |input| match AccountNumber::parse(input) {
+            Err(DomainError::InvalidAccountNumber { problem, .. }) => Some(problem),
+            _ => None,
+        };
+
+        assert_eq!(
+            AccountNumber::parse("GB83WEST12345698765432"),
+            Err(DomainError::InvalidAccountNumber {
+                input: "GB83WEST12345698765432".into(),
+                problem: AccountNumberProblem::ChecksumMismatch,
+            })
+        );
+        assert_eq!(
+            problem("GB82WEST12345698765433"),
+            Some(AccountNumberProblem::ChecksumMismatch)
+        );
+        assert_eq!(
+            problem("1282WEST1234"),
+            Some(AccountNumberProblem::CountryCode)
+        );
+        assert_eq!(problem("GB8"), Some(AccountNumberProblem::Length));
+        assert_eq!(
+            problem("GB82-WEST"),
+            Some(AccountNumberProblem::NotAlphanumeric)
+        );
+        assert_eq!(
+            problem("GBX2WEST1234"),
+            Some(AccountNumberProblem::CheckDigitsNotNumeric)
+        );
+        assert_eq!(
+            problem("Aé345"),
+            Some(AccountNumberProblem::NotAlphanumeric)
+        );
+        assert_eq!(
+            problem("GB82WESTé2345698765432"),
+            Some(AccountNumberProblem::NotAlphanumeric)
+        );
+        assert!("Aé345".parse::<AccountRef>().is_err());
+    }
+
+    #[test]
+    fn test_numbering_will_issue_valid_distinct_numbers() {
+        let numbering = AccountNumbering::new("ng", "BANK").unwrap();
+
+        let first = numbering.issue(1).unwrap();
+        let second = numbering.issue(2).unwrap();
+
+        assert!(first.as_str().starts_with("NG"));
+        assert!(first.as_str().ends_with("BANK0000000001"));
+        assert_ne!(first, second);
+        assert_eq!(AccountNumber::parse(first.as_str()), Ok(first));
+        assert!(AccountNumbering::new("N1", "BANK").is_err());
+        assert!(AccountNumbering::new("é", "BANK").is_err());
+        assert!(AccountNumbering::new("NG", "BA-K").is_err());
+    }
+
+    #[test]
+    fn test_account_ref_will_parse_ids_and_numbers() {
+        assert_eq!("42".parse(), Ok(AccountRef::Id(42)));
+        assert!(matches!(
+            "GB82WEST12345698765432".parse(),
+            Ok(AccountRef::Number(_))
+        ));
+        assert!("GB00WEST".parse::<AccountRef>().is_err());
+    }
+}
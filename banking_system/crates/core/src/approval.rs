@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::{AccountId, Transaction},
+    context::Actor,
+};
+
+pub type ApprovalId = u64;
+
+/// A debit that exceeded its account's approval threshold and is waiting for
+/// a second holder to sign off on it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingApproval {
+    pub id: ApprovalId,
+    pub account_id: AccountId,
+    pub requested_by: Actor,
+    pub txn: Transaction,
+}
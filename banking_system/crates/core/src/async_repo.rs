@@ -0,0 +1,138 @@
+use std::future::Future;
+
+use crate::{
+    account::{Account, AccountId},
+    account_number::AccountNumber,
+    errors::RepoError,
+    query::{AccountQuery, MAX_PAGE_SIZE, Page, PageRequest},
+    tenant::TenantId,
+};
+
+/// Non-blocking counterpart of [`crate::account::AccountRepository`] for
+/// backends used from async services. Every future is `Send` so callers can
+/// spawn them onto a multi-threaded runtime.
+pub trait AsyncAccountRepository: Send + Sync {
+    fn next_id(&self) -> impl Future<Output = Result<AccountId, RepoError>> + Send;
+    fn create(
+        &self,
+        tenant: &TenantId,
+        account: Account,
+    ) -> impl Future<Output = Result<(), RepoError>> + Send;
+    fn get(
+        &self,
+        tenant: &TenantId,
+        id: AccountId,
+    ) -> impl Future<Output = Result<Option<Account>, RepoError>> + Send;
+    fn update(
+        &self,
+        tenant: &TenantId,
+        account: Account,
+    ) -> impl Future<Output = Result<(), RepoError>> + Send;
+    fn list(
+        &self,
+        tenant: &TenantId,
+        query: &AccountQuery,
+        page: PageRequest,
+    ) -> impl Future<Output = Result<Page<Account>, RepoError>> + Send;
+
+    /// See [`crate::account::AccountRepository::get_by_number`].
+    fn get_by_number(
+        &self,
+        tenant: &TenantId,
+        number: &AccountNumber,
+    ) -> impl Future<Output = Result<Option<Account>, RepoError>> + Send {
+        async move {
+            let mut page = PageRequest::first(MAX_PAGE_SIZE);
+            loop {
+                let accounts = self.list(tenant, &AccountQuery::default(), page).await?;
+                if let Some(account) = accounts
+                    .items
+                    .into_iter()
+                    .find(|account| account.number.as_ref() == Some(number))
+                {
+                    return Ok(Some(account));
+                }
+                match accounts.next {
+                    Some(cursor) => page = PageRequest::next(cursor, MAX_PAGE_SIZE),
+                    None => return Ok(None),
+                }
+            }
+        }
+    }
+
+    /// See [`crate::account::AccountRepository::create_checked`].
+    ///
+    /// The default implementation pages through every account of the tenant
+    /// and is not atomic with respect to concurrent tasks.
+    fn create_checked<E>(
+        &self,
+        tenant: &TenantId,
+        account: Account,
+        check: impl FnOnce(&[Account]) -> Result<(), E> + Send,
+    ) -> impl Future<Output = Result<(), E>> + Send
+    where
+        E: From<RepoError> + Send,
+    {
+        async move {
+            let mut owned = Vec::new();
+            let mut page = PageRequest::first(MAX_PAGE_SIZE);
+            loop {
+                let accounts = self.list(tenant, &AccountQuery::default(), page).await?;
+                owned.extend(
+                    accounts
+                        .items
+                        .into_iter()
+                        .filter(|stored| stored.owner == account.owner),
+                );
+                match accounts.next {
+                    Some(cursor) => page = PageRequest::next(cursor, MAX_PAGE_SIZE),
+                    None => break,
+                }
+            }
+
+            check(&owned)?;
+            Ok(self.create(tenant, account).await?)
+        }
+    }
+
+    /// See [`crate::account::AccountRepository::modify`]. `f` runs without
+    /// awaiting, so implementations may hold locks around it.
+    ///
+    /// The default implementation is built on `get` and `update` and is not
+    /// atomic with respect to concurrent tasks.
+    fn modify<T, E>(
+        &self,
+        tenant: &TenantId,
+        ids: &[AccountId],
+        f: impl FnOnce(&mut [Account]) -> Result<T, E> + Send,
+    ) -> impl Future<Output = Result<T, E>> + Send
+    where
+        T: Send,
+        E: From<RepoError> + Send,
+    {
+        async move {
+            for (index, id) in ids.iter().enumerate() {
+                if ids[..index].contains(id) {
+                    return Err(RepoError::DuplicateId(*id).into());
+                }
+            }
+
+            let mut accounts = Vec::with_capacity(ids.len());
+            for id in ids {
+                accounts.push(
+                    self.get(tenant, *id)
+                        .await?
+                        .ok_or(RepoError::NotFound(*id))?,
+                );
+            }
+
+            let value = f(&mut accounts)?;
+
+            for account in accounts {
+                self.update(tenant, account).await?;
+            }
+
+            Ok(value)
+        }
+    }
+}
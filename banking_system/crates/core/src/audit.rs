@@ -0,0 +1,483 @@
+use std::{
+    fmt::{Debug, Display},
+    sync::RwLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    account::{Account, AccountId, Money},
+    context::{Actor, RequestContext},
+    errors::AuditError,
+};
+
+/// The hash the first record in a chain links back to.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AuditOutcome {
+    Success(String),
+    Failure(String),
+}
+
+/// The state of one account before and after an audited operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountChange {
+    pub account_id: AccountId,
+    pub before: Option<Account>,
+    pub after: Option<Account>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub seq: u64,
+    pub at: SystemTime,
+    pub actor: Actor,
+    pub request_id: String,
+    pub action: String,
+    pub inputs: String,
+    pub outcome: AuditOutcome,
+    /// Every account the operation touched, whether or not it changed.
+    pub accounts: Vec<AccountId>,
+    pub changes: Vec<AccountChange>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditRecord {
+    /// Recomputes the hash of this record's contents chained onto `prev_hash`.
+    pub fn compute_hash(&self) -> String {
+        let nanos = self
+            .at
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default();
+
+        let mut hasher = Sha256::new();
+        hasher.update(self.prev_hash.as_bytes());
+        hasher.update(
+            format!(
+                "|{}|{}|{:?}|{}|{}|{}|{:?}|{:?}|{:?}",
+                self.seq,
+                nanos,
+                self.actor,
+                self.request_id,
+                self.action,
+                self.inputs,
+                self.outcome,
+                self.accounts,
+                self.changes
+            )
+            .as_bytes(),
+        );
+
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    /// The balance movements this record made, one per changed account.
+    pub fn ledger_entries(&self) -> Vec<LedgerEntry> {
+        let balance = |account: &Option<Account>| {
+            account
+                .as_ref()
+                .map(|account| account.balance.0)
+                .unwrap_or_default()
+        };
+
+        self.changes
+            .iter()
+            .filter_map(|change| {
+                let before = balance(&change.before);
+                let after = balance(&change.after);
+                (before != after).then(|| LedgerEntry {
+                    seq: self.seq,
+                    at: self.at,
+                    account_id: change.account_id,
+                    action: self.action.clone(),
+                    amount: Money(after - before),
+                    balance: Money(after),
+                })
+            })
+            .collect()
+    }
+}
+
+/// One movement of an account's balance, derived from the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Sequence number of the audit record that made the movement.
+    pub seq: u64,
+    pub at: SystemTime,
+    pub account_id: AccountId,
+    pub action: String,
+    /// Signed change in balance; debits are negative.
+    pub amount: Money,
+    /// Balance after the movement.
+    pub balance: Money,
+}
+
+/// Filters for [`AuditLog::query`]; unset fields match everything.
+#[derive(Debug, Default, Clone)]
+pub struct AuditQuery {
+    pub account: Option<AccountId>,
+    pub actor: Option<Actor>,
+    pub from: Option<SystemTime>,
+    pub until: Option<SystemTime>,
+}
+
+impl AuditQuery {
+    pub fn account(mut self, account: AccountId) -> Self {
+        self.account = Some(account);
+        self
+    }
+
+    pub fn actor(mut self, actor: Actor) -> Self {
+        self.actor = Some(actor);
+        self
+    }
+
+    /// Only records at or after `from`.
+    pub fn from(mut self, from: SystemTime) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    /// Only records strictly before `until`.
+    pub fn until(mut self, until: SystemTime) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    pub fn matches(&self, record: &AuditRecord) -> bool {
+        self.account.is_none_or(|id| record.accounts.contains(&id))
+            && self
+                .actor
+                .as_ref()
+                .is_none_or(|actor| &record.actor == actor)
+            && self.from.is_none_or(|from| record.at >= from)
+            && self.until.is_none_or(|until| record.at < until)
+    }
+}
+
+/// Append-only, hash-chained record of operations.
+#[derive(Debug, Default)]
+pub struct AuditLog {
+    records: RwLock<Vec<AuditRecord>>,
+}
+
+/// What an operation reports to the log; the log assigns sequence, time and hashes.
+#[derive(Debug)]
+pub struct AuditEntry {
+    pub action: String,
+    pub inputs: String,
+    pub outcome: AuditOutcome,
+    pub accounts: Vec<AccountId>,
+    pub changes: Vec<AccountChange>,
+}
+
+impl AuditEntry {
+    /// Builds the entry for an operation from its result and the state of
+    /// `accounts` before and after it ran; only accounts that differ are
+    /// kept as changes.
+    pub fn from_result<T: Debug, E: Display>(
+        action: &str,
+        inputs: String,
+        accounts: Vec<AccountId>,
+        before: Vec<Option<Account>>,
+        after: Vec<Option<Account>>,
+        result: &Result<T, E>,
+    ) -> Self {
+        let changes = accounts
+            .iter()
+            .zip(before.into_iter().zip(after))
+            .filter(|(_, (before, after))| before != after)
+            .map(|(account_id, (before, after))| AccountChange {
+                account_id: *account_id,
+                before,
+                after,
+            })
+            .collect();
+
+        let outcome = match result {
+            Ok(value) => AuditOutcome::Success(format!("{value:?}")),
+            Err(err) => AuditOutcome::Failure(err.to_string()),
+        };
+
+        Self {
+            action: action.into(),
+            inputs,
+            outcome,
+            accounts,
+            changes,
+        }
+    }
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn append(
+        &self,
+        ctx: &RequestContext,
+        entry: AuditEntry,
+    ) -> Result<AuditRecord, AuditError> {
+        let mut records = self.records.write().map_err(|_| AuditError::LockPoisened)?;
+        let prev_hash = records
+            .last()
+            .map(|record| record.hash.clone())
+            .unwrap_or_else(|| GENESIS_HASH.into());
+        // Never earlier than the record before, so looking records up by
+        // time agrees with their order even if the system clock steps back.
+        let at = records
+            .last()
+            .map_or_else(SystemTime::now, |last| last.at.max(SystemTime::now()));
+
+        let mut record = AuditRecord {
+            seq: records.len() as u64 + 1,
+            at,
+            actor: ctx.actor.clone(),
+            request_id: ctx.request_id.clone(),
+            action: entry.action,
+            inputs: entry.inputs,
+            outcome: entry.outcome,
+            accounts: entry.accounts,
+            changes: entry.changes,
+            prev_hash,
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash();
+
+        records.push(record.clone());
+        Ok(record)
+    }
+
+    pub fn records(&self) -> Result<Vec<AuditRecord>, AuditError> {
+        let records = self.records.read().map_err(|_| AuditError::LockPoisened)?;
+        Ok(records.clone())
+    }
+
+    pub fn query(&self, query: &AuditQuery) -> Result<Vec<AuditRecord>, AuditError> {
+        let records = self.records.read().map_err(|_| AuditError::LockPoisened)?;
+        Ok(records
+            .iter()
+            .filter(|record| query.matches(record))
+            .cloned()
+            .collect())
+    }
+
+    /// Every balance movement on `account_id`, oldest first.
+    pub fn ledger(&self, account_id: AccountId) -> Result<Vec<LedgerEntry>, AuditError> {
+        let records = self.records.read().map_err(|_| AuditError::LockPoisened)?;
+        Ok(records
+            .iter()
+            .flat_map(AuditRecord::ledger_entries)
+            .filter(|entry| entry.account_id == account_id)
+            .collect())
+    }
+
+    /// Every balance movement recorded after the record numbered `seq`,
+    /// oldest first, for following the ledger as it grows.
+    pub fn ledger_after(&self, seq: u64) -> Result<Vec<LedgerEntry>, AuditError> {
+        let records = self.records.read().map_err(|_| AuditError::LockPoisened)?;
+        let start = usize::try_from(seq)
+            .unwrap_or(usize::MAX)
+            .min(records.len());
+        Ok(records[start..]
+            .iter()
+            .flat_map(AuditRecord::ledger_entries)
+            .collect())
+    }
+
+    /// The account as it stood at `at`, taken from the last change the log
+    /// recorded at or before then; `None` if it did not exist yet.
+    pub fn account_at(
+        &self,
+        account_id: AccountId,
+        at: SystemTime,
+    ) -> Result<Option<Account>, AuditError> {
+        let records = self.records.read().map_err(|_| AuditError::LockPoisened)?;
+        Ok(records
+            .iter()
+            .rev()
+            .filter(|record| record.at <= at)
+            .flat_map(|record| &record.changes)
+            .find(|change| change.account_id == account_id)
+            .and_then(|change| change.after.clone()))
+    }
+
+    /// Replaces the log with `records` from another log, such as a
+    /// snapshot, after checking their hash chain.
+    pub fn restore(&self, records: Vec<AuditRecord>) -> Result<(), AuditError> {
+        Self::verify_chain(&records)?;
+        *self.records.write().map_err(|_| AuditError::LockPoisened)? = records;
+        Ok(())
+    }
+
+    pub fn verify(&self) -> Result<(), AuditError> {
+        Self::verify_chain(&self.records()?)
+    }
+
+    /// Checks that every record links to its predecessor and that no record
+    /// was altered after it was written.
+    pub fn verify_chain(records: &[AuditRecord]) -> Result<(), AuditError> {
+        let mut prev_hash = GENESIS_HASH.to_string();
+
+        for (index, record) in records.iter().enumerate() {
+            if record.seq != index as u64 + 1
+                || record.prev_hash != prev_hash
+                || record.hash != record.compute_hash()
+            {
+                return Err(AuditError::Tampered(record.seq));
+            }
+            prev_hash = record.hash.clone();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use crate::{
+        account::{Account, Money},
+        audit::{AccountChange, AuditEntry, AuditLog, AuditOutcome, AuditQuery},
+        context::{Actor, RequestContext},
+        errors::AuditError,
+    };
+
+    fn entry(account_id: u64) -> AuditEntry {
+        AuditEntry {
+            action: "deposit".into(),
+            inputs: "amount=10".into(),
+            outcome: AuditOutcome::Success("10".into()),
+            accounts: vec![account_id],
+            changes: vec![AccountChange {
+                account_id,
+                before: Some(Account::builder(account_id, 1).build()),
+                after: Some(Account::builder(account_id, 1).build()),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_audit_log_will_chain_records() {
+        let log = AuditLog::new();
+        let first = log
+            .append(&RequestContext::customer(1, "r1"), entry(1))
+            .unwrap();
+        let second = log
+            .append(&RequestContext::teller(7, "r2"), entry(2))
+            .unwrap();
+
+        assert_eq!(first.seq, 1);
+        assert_eq!(second.prev_hash, first.hash);
+        assert!(log.verify().is_ok());
+    }
+
+    #[test]
+    fn test_audit_log_will_detect_tampering() {
+        let log = AuditLog::new();
+        log.append(&RequestContext::customer(1, "r1"), entry(1))
+            .unwrap();
+        log.append(&RequestContext::customer(1, "r2"), entry(1))
+            .unwrap();
+        log.append(&RequestContext::customer(1, "r3"), entry(1))
+            .unwrap();
+
+        let mut records = log.records().unwrap();
+        records[1].inputs = "amount=1000".into();
+        assert_eq!(
+            AuditLog::verify_chain(&records),
+            Err(AuditError::Tampered(2))
+        );
+
+        let mut records = log.records().unwrap();
+        records.remove(0);
+        assert_eq!(
+            AuditLog::verify_chain(&records),
+            Err(AuditError::Tampered(2))
+        );
+    }
+
+    #[test]
+    fn test_audit_log_will_filter_by_account_actor_and_time() {
+        let log = AuditLog::new();
+        let start = SystemTime::now();
+        log.append(&RequestContext::customer(1, "r1"), entry(1))
+            .unwrap();
+        log.append(&RequestContext::teller(7, "r2"), entry(2))
+            .unwrap();
+
+        let by_account = log.query(&AuditQuery::default().account(2)).unwrap();
+        assert_eq!(by_account.len(), 1);
+        assert_eq!(by_account[0].actor, Actor::Teller(7));
+
+        let by_actor = log
+            .query(&AuditQuery::default().actor(Actor::Customer(1)))
+            .unwrap();
+        assert_eq!(by_actor.len(), 1);
+        assert_eq!(by_actor[0].accounts, vec![1]);
+
+        let in_range = log.query(&AuditQuery::default().from(start)).unwrap();
+        assert_eq!(in_range.len(), 2);
+        let before_start = log.query(&AuditQuery::default().until(start)).unwrap();
+        assert!(before_start.is_empty());
+        let future = start + Duration::from_secs(3600);
+        assert!(
+            log.query(&AuditQuery::default().from(future))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_audit_log_will_derive_ledger_from_balance_changes() {
+        let log = AuditLog::new();
+        let ctx = RequestContext::system();
+        let account = |balance: i64| {
+            Some(
+                Account::builder(1, 1)
+                    .balance(Money(balance.into()))
+                    .build(),
+            )
+        };
+        let change = |before, after| AuditEntry {
+            changes: vec![AccountChange {
+                account_id: 1,
+                before,
+                after,
+            }],
+            ..entry(1)
+        };
+
+        log.append(&ctx, change(None, account(0))).unwrap();
+        log.append(&ctx, change(account(0), account(100))).unwrap();
+        log.append(&ctx, entry(2)).unwrap();
+        log.append(&ctx, change(account(100), account(40))).unwrap();
+
+        let ledger = log.ledger(1).unwrap();
+        let movements: Vec<_> = ledger
+            .iter()
+            .map(|entry| (entry.seq, entry.amount, entry.balance))
+            .collect();
+        assert_eq!(
+            movements,
+            vec![
+                (2, Money(100.into()), Money(100.into())),
+                (4, Money((-60).into()), Money(40.into())),
+            ]
+        );
+        assert!(log.ledger(2).unwrap().is_empty());
+        assert_eq!(log.ledger_after(2).unwrap(), ledger[1..]);
+        assert!(log.ledger_after(4).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,376 @@
+use std::{collections::BTreeMap, fmt};
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::{AccountId, Money},
+    context::TellerId,
+    errors::DomainError,
+};
+
+pub type DrawerId = u64;
+
+/// Face value of a note or coin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Denomination(pub Decimal);
+
+impl fmt::Display for Denomination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Number of notes or coins held per denomination.
+pub type CashCount = BTreeMap<Denomination, u32>;
+
+/// Face value of all the notes and coins in `count`.
+pub fn cash_total(count: &CashCount) -> Money {
+    Money(
+        count
+            .iter()
+            .map(|(denomination, pieces)| denomination.0 * Decimal::from(*pieces))
+            .sum(),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CashDirection {
+    /// Taken in over the counter for a deposit.
+    In,
+    /// Paid out over the counter for a withdrawal.
+    Out,
+}
+
+/// One cash deposit or withdrawal handled at the drawer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CashMovement {
+    pub teller: TellerId,
+    pub account_id: AccountId,
+    pub direction: CashDirection,
+    pub notes: CashCount,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DrawerStatus {
+    Open,
+    Closed,
+}
+
+/// How a count compared to what the drawer should hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CashBalance {
+    Balanced,
+    /// More cash was counted than expected.
+    Over(Money),
+    /// Less cash was counted than expected.
+    Short(Money),
+}
+
+/// Balancing of one teller's time at a drawer, produced when the drawer is
+/// handed off or closed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShiftReport {
+    pub drawer_id: DrawerId,
+    pub teller: TellerId,
+    /// Cash the teller took over: the starting float or the count at the
+    /// previous hand-off.
+    pub opening: Money,
+    pub deposits: Money,
+    pub withdrawals: Money,
+    pub expected: Money,
+    pub counted: Money,
+    /// Counted minus expected pieces, for denominations that differ.
+    pub variances: BTreeMap<Denomination, i64>,
+    pub balance: CashBalance,
+}
+
+/// A teller's cash drawer. It tracks the notes and coins it should hold as
+/// cash moves over the counter, and is balanced against a physical count
+/// whenever it changes hands and at the end of the day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CashDrawer {
+    pub id: DrawerId,
+    /// The teller currently accountable for the drawer.
+    pub teller: TellerId,
+    pub opened_on: NaiveDate,
+    pub status: DrawerStatus,
+    /// Cash the current teller took over.
+    pub opening: CashCount,
+    /// Cash the drawer should hold now.
+    pub expected: CashCount,
+    pub movements: Vec<CashMovement>,
+    /// Index of the first movement of the current teller's shift.
+    pub shift_start: usize,
+    /// Reports of completed shifts, oldest first.
+    pub shifts: Vec<ShiftReport>,
+}
+
+impl CashDrawer {
+    pub fn open(
+        id: DrawerId,
+        teller: TellerId,
+        opened_on: NaiveDate,
+        float: CashCount,
+    ) -> Result<Self, DomainError> {
+        validate(&float)?;
+
+        Ok(CashDrawer {
+            id,
+            teller,
+            opened_on,
+            status: DrawerStatus::Open,
+            opening: float.clone(),
+            expected: float,
+            movements: Vec::new(),
+            shift_start: 0,
+            shifts: Vec::new(),
+        })
+    }
+
+    /// Checks that the drawer is open and held by `teller`.
+    pub fn check_holder(&self, teller: TellerId) -> Result<(), DomainError> {
+        if self.status != DrawerStatus::Open {
+            return Err(DomainError::DrawerClosed(self.id));
+        }
+        if self.teller != teller {
+            return Err(DomainError::DrawerNotHeld {
+                drawer_id: self.id,
+                teller,
+            });
+        }
+        Ok(())
+    }
+
+    /// Records cash `teller` took in or paid out for `account_id`. Paying
+    /// out needs the exact notes to be in the drawer.
+    pub fn record(
+        &mut self,
+        teller: TellerId,
+        account_id: AccountId,
+        direction: CashDirection,
+        notes: CashCount,
+    ) -> Result<(), DomainError> {
+        self.check_holder(teller)?;
+        validate(&notes)?;
+
+        if direction == CashDirection::Out {
+            for (denomination, pieces) in &notes {
+                let available = self.expected.get(denomination).copied().unwrap_or_default();
+                if available < *pieces {
+                    return Err(DomainError::InsufficientCash {
+                        drawer_id: self.id,
+                        denomination: *denomination,
+                        requested: *pieces,
+                        available,
+                    });
+                }
+            }
+        }
+
+        for (denomination, pieces) in &notes {
+            let held = self.expected.entry(*denomination).or_default();
+            match direction {
+                CashDirection::In => *held += pieces,
+                CashDirection::Out => *held -= pieces,
+            }
+        }
+        self.expected.retain(|_, pieces| *pieces > 0);
+
+        self.movements.push(CashMovement {
+            teller: self.teller,
+            account_id,
+            direction,
+            notes,
+        });
+        Ok(())
+    }
+
+    /// Balances the current shift against `counted` without ending it.
+    pub fn balance_shift(&self, counted: &CashCount) -> ShiftReport {
+        let moved = |direction| {
+            Money(
+                self.movements[self.shift_start..]
+                    .iter()
+                    .filter(|movement| movement.direction == direction)
+                    .map(|movement| cash_total(&movement.notes).0)
+                    .sum(),
+            )
+        };
+
+        let mut variances = BTreeMap::new();
+        for denomination in self.expected.keys().chain(counted.keys()) {
+            let expected = i64::from(self.expected.get(denomination).copied().unwrap_or_default());
+            let found = i64::from(counted.get(denomination).copied().unwrap_or_default());
+            if found != expected {
+                variances.insert(*denomination, found - expected);
+            }
+        }
+
+        let expected = cash_total(&self.expected);
+        let total = cash_total(counted);
+        let balance = match total.0 - expected.0 {
+            difference if difference > Decimal::ZERO => CashBalance::Over(Money(difference)),
+            difference if difference < Decimal::ZERO => CashBalance::Short(Money(-difference)),
+            _ => CashBalance::Balanced,
+        };
+
+        ShiftReport {
+            drawer_id: self.id,
+            teller: self.teller,
+            opening: cash_total(&self.opening),
+            deposits: moved(CashDirection::In),
+            withdrawals: moved(CashDirection::Out),
+            expected,
+            counted: total,
+            variances,
+            balance,
+        }
+    }
+
+    /// Ends the shift of `from`, the current holder, on `counted` and makes
+    /// `to` accountable for the drawer from that count on.
+    pub fn hand_off(
+        &mut self,
+        from: TellerId,
+        to: TellerId,
+        counted: CashCount,
+    ) -> Result<ShiftReport, DomainError> {
+        let report = self.end_shift(from, counted)?;
+        self.teller = to;
+        Ok(report)
+    }
+
+    /// Ends the last shift on `counted` and closes the drawer.
+    pub fn close(
+        &mut self,
+        teller: TellerId,
+        counted: CashCount,
+    ) -> Result<ShiftReport, DomainError> {
+        let report = self.end_shift(teller, counted)?;
+        self.status = DrawerStatus::Closed;
+        Ok(report)
+    }
+
+    fn end_shift(
+        &mut self,
+        teller: TellerId,
+        counted: CashCount,
+    ) -> Result<ShiftReport, DomainError> {
+        self.check_holder(teller)?;
+        validate(&counted)?;
+
+        let report = self.balance_shift(&counted);
+        self.shifts.push(report.clone());
+        self.opening = counted.clone();
+        self.expected = counted;
+        self.shift_start = self.movements.len();
+        Ok(report)
+    }
+}
+
+fn validate(count: &CashCount) -> Result<(), DomainError> {
+    match count
+        .keys()
+        .find(|denomination| denomination.0 <= Decimal::ZERO)
+    {
+        Some(denomination) => Err(DomainError::InvalidDenomination(*denomination)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use chrono::NaiveDate;
+    use rust_decimal::dec;
+
+    use crate::{
+        account::Money,
+        cash::{CashBalance, CashCount, CashDirection, CashDrawer, Denomination, cash_total},
+        errors::DomainError,
+    };
+
+    fn count(pieces: &[(i64, u32)]) -> CashCount {
+        pieces
+            .iter()
+            .map(|(value, pieces)| (Denomination((*value).into()), *pieces))
+            .collect()
+    }
+
+    fn drawer() -> CashDrawer {
+        let opened_on = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        CashDrawer::open(1, 7, opened_on, count(&[(50, 10), (20, 20), (5, 40)])).unwrap()
+    }
+
+    #[test]
+    fn test_cash_drawer_will_track_notes_and_refuse_missing_ones() {
+        let mut drawer = drawer();
+        assert_eq!(cash_total(&drawer.expected), Money(dec!(1100)));
+
+        drawer
+            .record(7, 1, CashDirection::In, count(&[(100, 2)]))
+            .unwrap();
+        drawer
+            .record(7, 2, CashDirection::Out, count(&[(50, 4), (5, 2)]))
+            .unwrap();
+        assert_eq!(cash_total(&drawer.expected), Money(dec!(1090)));
+        assert_eq!(
+            drawer.expected,
+            count(&[(100, 2), (50, 6), (20, 20), (5, 38)])
+        );
+
+        assert_eq!(
+            drawer.record(7, 2, CashDirection::Out, count(&[(100, 3)])),
+            Err(DomainError::InsufficientCash {
+                drawer_id: 1,
+                denomination: Denomination(dec!(100)),
+                requested: 3,
+                available: 2,
+            })
+        );
+        assert_eq!(
+            drawer.record(7, 2, CashDirection::In, count(&[(0, 1)])),
+            Err(DomainError::InvalidDenomination(Denomination(dec!(0))))
+        );
+        assert_eq!(drawer.movements.len(), 2);
+    }
+
+    #[test]
+    fn test_cash_drawer_will_balance_each_shift_against_its_count() {
+        let mut drawer = drawer();
+        drawer
+            .record(7, 1, CashDirection::In, count(&[(20, 5)]))
+            .unwrap();
+
+        let first = drawer
+            .hand_off(7, 8, count(&[(50, 10), (20, 25), (5, 38)]))
+            .unwrap();
+        assert_eq!(first.teller, 7);
+        assert_eq!(first.deposits, Money(dec!(100)));
+        assert_eq!(first.expected, Money(dec!(1200)));
+        assert_eq!(first.balance, CashBalance::Short(Money(dec!(10))));
+        assert_eq!(first.variances, [(Denomination(dec!(5)), -2)].into());
+        assert_eq!(
+            drawer.record(7, 1, CashDirection::In, count(&[(20, 1)])),
+            Err(DomainError::DrawerNotHeld {
+                drawer_id: 1,
+                teller: 7
+            })
+        );
+
+        drawer
+            .record(8, 3, CashDirection::Out, count(&[(50, 2)]))
+            .unwrap();
+        let last = drawer
+            .close(8, count(&[(50, 8), (20, 25), (5, 38), (1, 3)]))
+            .unwrap();
+        assert_eq!(last.teller, 8);
+        assert_eq!(last.opening, Money(dec!(1190)));
+        assert_eq!(last.deposits, Money(dec!(0)));
+        assert_eq!(last.withdrawals, Money(dec!(100)));
+        assert_eq!(last.balance, CashBalance::Over(Money(dec!(3))));
+        assert_eq!(drawer.shifts, vec![first, last]);
+        assert_eq!(drawer.check_holder(8), Err(DomainError::DrawerClosed(1)));
+    }
+}
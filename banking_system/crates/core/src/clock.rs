@@ -0,0 +1,107 @@
+use std::sync::Mutex;
+
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+
+/// Source of the current business date, injectable so that date-driven jobs
+/// such as maturity processing can be tested.
+pub trait Clock: Send + Sync {
+    fn today(&self) -> NaiveDate;
+}
+
+/// The real date, in UTC.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        Utc::now().date_naive()
+    }
+}
+
+/// A clock that only moves when told to.
+#[derive(Debug)]
+pub struct FixedClock(Mutex<NaiveDate>);
+
+impl FixedClock {
+    pub fn new(today: NaiveDate) -> Self {
+        Self(Mutex::new(today))
+    }
+
+    pub fn set(&self, today: NaiveDate) {
+        *self.0.lock().unwrap() = today;
+    }
+
+    pub fn advance_days(&self, days: u64) {
+        let mut today = self.0.lock().unwrap();
+        *today = *today + Days::new(days);
+    }
+}
+
+impl Clock for FixedClock {
+    fn today(&self) -> NaiveDate {
+        *self.0.lock().unwrap()
+    }
+}
+
+/// The bank's business days. As a [`Clock`] it gives the business date of
+/// the current UTC time: work at or after the cutoff books to the next day,
+/// and non-working days and holidays roll forward to the next business day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BusinessCalendar {
+    pub cutoff: NaiveTime,
+    pub non_working_days: Vec<Weekday>,
+    pub holidays: Vec<NaiveDate>,
+}
+
+impl BusinessCalendar {
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !self.non_working_days.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    /// The date work done at `at` books to. Needs at least one working day
+    /// in the week.
+    pub fn business_date(&self, at: NaiveDateTime) -> NaiveDate {
+        let mut date = at.date();
+        if at.time() >= self.cutoff {
+            date = date + Days::new(1);
+        }
+        while !self.is_business_day(date) {
+            date = date + Days::new(1);
+        }
+        date
+    }
+}
+
+impl Clock for BusinessCalendar {
+    fn today(&self) -> NaiveDate {
+        self.business_date(Utc::now().naive_utc())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use chrono::{NaiveDate, NaiveTime, Weekday};
+
+    use crate::clock::BusinessCalendar;
+
+    #[test]
+    fn test_business_calendar_will_roll_past_cutoff_weekends_and_holidays() {
+        let calendar = BusinessCalendar {
+            cutoff: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            non_working_days: vec![Weekday::Sat, Weekday::Sun],
+            holidays: vec![NaiveDate::from_ymd_opt(2026, 12, 28).unwrap()],
+        };
+        let at = |day, hour| {
+            NaiveDate::from_ymd_opt(2026, 12, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+        };
+        let date = |day| NaiveDate::from_ymd_opt(2026, 12, day).unwrap();
+
+        assert_eq!(calendar.business_date(at(23, 9)), date(23));
+        assert_eq!(calendar.business_date(at(23, 17)), date(24));
+        assert_eq!(calendar.business_date(at(25, 18)), date(29));
+        assert_eq!(calendar.business_date(at(26, 9)), date(29));
+    }
+}
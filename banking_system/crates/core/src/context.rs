@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use crate::customer::CustomerId;
+
+pub type TellerId = u64;
+
+/// Who is calling into the bank.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Actor {
+    Customer(CustomerId),
+    Teller(TellerId),
+    /// A scheduled or internal job, identified by name.
+    System(String),
+}
+
+/// Carried by every bank operation so it can be authorized and audited.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestContext {
+    pub actor: Actor,
+    pub request_id: String,
+}
+
+impl RequestContext {
+    pub fn new(actor: Actor, request_id: &str) -> Self {
+        Self {
+            actor,
+            request_id: request_id.into(),
+        }
+    }
+
+    pub fn customer(id: CustomerId, request_id: &str) -> Self {
+        Self::new(Actor::Customer(id), request_id)
+    }
+
+    pub fn teller(id: TellerId, request_id: &str) -> Self {
+        Self::new(Actor::Teller(id), request_id)
+    }
+
+    /// Context for calls made by the bank itself rather than on behalf of a user.
+    pub fn system() -> Self {
+        Self::new(Actor::System("bank".into()), "internal")
+    }
+}
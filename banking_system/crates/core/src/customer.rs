@@ -1,9 +1,17 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{errors::RepoError, tenant::TenantId};
+
 pub type CustomerId = u64;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Customer {
     pub id: CustomerId,
     pub name: String,
+    pub email: Option<String>,
+    /// Set once the customer's personal data has been erased.
+    pub erased_on: Option<NaiveDate>,
 }
 
 impl Customer {
@@ -13,12 +21,27 @@ impl Customer {
             ..Default::default()
         }
     }
+
+    /// Replaces the personal fields with `pseudonym`. It must be drawn at
+    /// random: anything derived from the personal data could be matched
+    /// back to it. The id is kept so retained financial records still
+    /// resolve to the same, now anonymous, customer.
+    pub fn pseudonymize(&mut self, on: NaiveDate, pseudonym: u64) {
+        if self.erased_on.is_some() {
+            return;
+        }
+
+        self.name = format!("erased-{pseudonym:016x}");
+        self.email = None;
+        self.erased_on = Some(on);
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct CustomerBuilder {
     pub id: CustomerId,
     pub name: Option<String>,
+    pub email: Option<String>,
 }
 
 impl CustomerBuilder {
@@ -27,16 +50,36 @@ impl CustomerBuilder {
         self
     }
 
+    pub fn email(mut self, email: &str) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+
     pub fn build(self) -> Customer {
         Customer {
             id: self.id,
             name: self.name.unwrap_or_default(),
+            email: self.email,
+            erased_on: None,
         }
     }
 }
 
+/// Where customer profiles are kept, apart from their accounts. Profiles
+/// are partitioned by tenant: customer ids only need to be unique within a
+/// tenant, and no call ever sees another tenant's profiles.
+pub trait CustomerStore: Send + Sync {
+    fn get(&self, tenant: &TenantId, id: CustomerId) -> Result<Option<Customer>, RepoError>;
+    /// Inserts the customer or replaces the tenant's profile with the same id.
+    fn save(&self, tenant: &TenantId, customer: Customer) -> Result<(), RepoError>;
+    /// Every customer of the tenant, ordered by id.
+    fn list(&self, tenant: &TenantId) -> Result<Vec<Customer>, RepoError>;
+}
+
 #[cfg(test)]
 pub mod tests {
+    use chrono::NaiveDate;
+
     use crate::customer::Customer;
 
     #[test]
@@ -51,4 +94,24 @@ pub mod tests {
         assert_eq!(new_customer.name, "name");
         assert_eq!(new_customer.id, 2);
     }
+
+    #[test]
+    fn test_customer_will_be_pseudonymized_once() {
+        let on = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let mut customer = Customer::builder(7)
+            .name("Ada Lovelace")
+            .email("ada@example.com")
+            .build();
+
+        customer.pseudonymize(on, 0x2a);
+        let erased = customer.clone();
+        assert_eq!(erased.id, 7);
+        assert_eq!(erased.name, "erased-000000000000002a");
+        assert!(!erased.name.contains("Ada"));
+        assert_eq!(erased.email, None);
+        assert_eq!(erased.erased_on, Some(on));
+
+        customer.pseudonymize(on.succ_opt().unwrap(), 0x2b);
+        assert_eq!(customer, erased);
+    }
 }
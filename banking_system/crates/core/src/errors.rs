@@ -1,6 +1,22 @@
+use std::{collections::BTreeMap, fmt};
+
+use chrono::NaiveDate;
+use serde::Serialize;
 use thiserror::Error;
 
-use crate::account::AccountId;
+use crate::{
+    account::{AccountId, AccountStatus, Money},
+    account_number::AccountNumber,
+    approval::ApprovalId,
+    cash::{Denomination, DrawerId},
+    context::TellerId,
+    customer::CustomerId,
+    interbank::ExternalPaymentStatus,
+    loan::{LoanId, LoanStatus},
+    notification::AlertRuleId,
+    tenant::TenantId,
+    term_deposit::{TermDepositId, TermDepositStatus},
+};
 
 #[derive(Debug, Error, PartialEq)]
 pub enum AppError {
@@ -8,28 +24,759 @@ pub enum AppError {
     Domain(#[from] DomainError),
     #[error("Repo error: {0}")]
     Repo(#[from] RepoError),
+    #[error("Audit error: {0}")]
+    Audit(#[from] AuditError),
+    #[error("Clearing error: {0}")]
+    Clearing(#[from] ClearingError),
+    #[error("Snapshot error: {0}")]
+    Snapshot(#[from] SnapshotError),
 }
 
 #[derive(Debug, Error, PartialEq)]
 pub enum DomainError {
-    #[error("deposit error: {0}")]
-    ClosedAccount(String),
-    #[error("deposit error: {0}")]
-    NegativeAmount(String),
-    #[error("deposit error: {0}")]
-    FrozenAccount(String),
-    #[error("withdrawal error: {0}")]
-    InsufficientFunds(String),
-    #[error("transaction error: {0}")]
-    Unsupported(String),
-    #[error("Account {0} NOT FOUND")]
+    #[error("amount {amount} for account {account_id} must be greater than zero")]
+    InvalidAmount {
+        account_id: AccountId,
+        amount: Money,
+    },
+    #[error("account {account_id} has {available} available, {requested} was requested")]
+    InsufficientFunds {
+        account_id: AccountId,
+        requested: Money,
+        available: Money,
+    },
+    #[error("account {account_id} is {status:?} and does not allow {operation}")]
+    AccountNotActive {
+        account_id: AccountId,
+        status: AccountStatus,
+        operation: AccountOperation,
+    },
+    #[error("account {account_id} still holds {balance} and cannot be closed")]
+    BalanceNotZero {
+        account_id: AccountId,
+        balance: Money,
+    },
+    #[error("the primary owner of account {account_id} cannot be changed or removed")]
+    PrimaryOwnerImmutable { account_id: AccountId },
+    #[error("customer {customer} is not a holder of account {account_id}")]
+    NotAHolder {
+        account_id: AccountId,
+        customer: CustomerId,
+    },
+    #[error("customer {customer} may not perform this operation on account {account_id}")]
+    Unauthorized {
+        account_id: AccountId,
+        customer: CustomerId,
+    },
+    #[error("approval {approval_id} cannot be given by its requester")]
+    SelfApproval { approval_id: ApprovalId },
+    #[error("approval {approval_id} must be given by another holder of the account or an approver")]
+    ApproverRequired { approval_id: ApprovalId },
+    #[error("{kind} transactions cannot be applied to account {account_id} directly")]
+    UnsupportedTransaction {
+        account_id: AccountId,
+        kind: &'static str,
+    },
+    #[error("account {0} not found")]
     AccountNotFound(AccountId),
-    #[error("Transaction failed: cannot transfer to self")]
-    TransferToSelf
+    #[error("account {0} cannot transfer to itself")]
+    TransferToSelf(AccountId),
+    #[error("transaction held for a second approval: approval {0}")]
+    ApprovalRequired(ApprovalId),
+    #[error("approval {0} not found")]
+    ApprovalNotFound(ApprovalId),
+    #[error("invalid account number {input:?}: {problem}")]
+    InvalidAccountNumber {
+        input: String,
+        problem: AccountNumberProblem,
+    },
+    #[error("account {0} has no account number")]
+    AccountNumberMissing(AccountId),
+    #[error("no account has number {0}")]
+    AccountNumberNotFound(AccountNumber),
+    #[error("invalid terms: {0}")]
+    InvalidTerms(TermsProblem),
+    #[error("loan {loan_id} is {status:?}")]
+    LoanStatusConflict { loan_id: LoanId, status: LoanStatus },
+    #[error("repayment of {amount} on loan {loan_id} exceeds the payoff amount of {payoff}")]
+    RepaymentExceedsPayoff {
+        loan_id: LoanId,
+        amount: Money,
+        payoff: Money,
+    },
+    #[error("repayment would clear loan {loan_id}; pay the payoff amount of {payoff}")]
+    PartialPayoff { loan_id: LoanId, payoff: Money },
+    #[error("loan {0} not found")]
+    LoanNotFound(LoanId),
+    #[error("term deposit {deposit_id} is {status:?}")]
+    TermDepositStatusConflict {
+        deposit_id: TermDepositId,
+        status: TermDepositStatus,
+    },
+    #[error("term deposit {deposit_id} matured on {maturity}; it is paid out at maturity")]
+    TermDepositMatured {
+        deposit_id: TermDepositId,
+        maturity: NaiveDate,
+    },
+    #[error("term deposit {deposit_id} matures on {maturity}")]
+    TermDepositNotMature {
+        deposit_id: TermDepositId,
+        maturity: NaiveDate,
+    },
+    #[error("term deposit {0} not found")]
+    TermDepositNotFound(TermDepositId),
+    #[error("external payment {end_to_end_id} cannot go from {status:?} to {requested:?}")]
+    ExternalPaymentStatusConflict {
+        end_to_end_id: String,
+        status: ExternalPaymentStatus,
+        requested: ExternalPaymentStatus,
+    },
+    #[error("external payment {0} not found")]
+    ExternalPaymentNotFound(String),
+    #[error("payment in {found} cannot be booked to accounts held in {expected}")]
+    CurrencyMismatch { expected: String, found: String },
+    #[error("{0} is not configured")]
+    NotConfigured(Feature),
+    #[error("customer {0} not found")]
+    CustomerNotFound(CustomerId),
+    #[error("customer {caller} may not access the data of customer {customer}")]
+    CustomerAccessDenied {
+        customer: CustomerId,
+        caller: CustomerId,
+    },
+    #[error("only tellers may handle cash")]
+    TellerRequired,
+    #[error("cash drawer {0} not found")]
+    DrawerNotFound(DrawerId),
+    #[error("cash drawer {0} is closed")]
+    DrawerClosed(DrawerId),
+    #[error("cash drawer {drawer_id} is not held by teller {teller}")]
+    DrawerNotHeld {
+        drawer_id: DrawerId,
+        teller: TellerId,
+    },
+    #[error("teller {teller} already holds open cash drawer {drawer_id}")]
+    DrawerAlreadyHeld {
+        teller: TellerId,
+        drawer_id: DrawerId,
+    },
+    #[error("{0} is not a valid denomination")]
+    InvalidDenomination(Denomination),
+    #[error("cash drawer {drawer_id} holds {available} of {denomination}, {requested} needed")]
+    InsufficientCash {
+        drawer_id: DrawerId,
+        denomination: Denomination,
+        requested: u32,
+        available: u32,
+    },
+    #[error("cash of {counted} does not match the {amount} for account {account_id}")]
+    CashMismatch {
+        account_id: AccountId,
+        amount: Money,
+        counted: Money,
+    },
+    #[error("alert rule {0} not found")]
+    AlertRuleNotFound(AlertRuleId),
+    #[error("product {product} is not offered by tenant {tenant}")]
+    UnknownProduct { tenant: TenantId, product: String },
+    #[error("{amount} for account {account_id} is over the transaction limit of {limit}")]
+    TransactionLimitExceeded {
+        account_id: AccountId,
+        amount: Money,
+        limit: Money,
+    },
+    #[error("customer {owner} already owns the maximum of {limit} accounts")]
+    AccountLimitReached { owner: CustomerId, limit: usize },
+    #[error("account {account_id} may withdraw {remaining} more today, {requested} was requested")]
+    DailyWithdrawalLimitExceeded {
+        account_id: AccountId,
+        requested: Money,
+        remaining: Money,
+    },
+}
+
+/// The account operations an account's status can forbid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountOperation {
+    Deposit,
+    Withdraw,
+    Freeze,
+    Close,
+}
+
+impl fmt::Display for AccountOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AccountOperation::Deposit => "deposits",
+            AccountOperation::Withdraw => "withdrawals",
+            AccountOperation::Freeze => "freezing",
+            AccountOperation::Close => "closing",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountNumberProblem {
+    Length,
+    CountryCode,
+    CheckDigitsNotNumeric,
+    NotAlphanumeric,
+    ChecksumMismatch,
+}
+
+impl fmt::Display for AccountNumberProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AccountNumberProblem::Length => "must be between 5 and 34 characters",
+            AccountNumberProblem::CountryCode => "must start with a country code",
+            AccountNumberProblem::CheckDigitsNotNumeric => "check digits must be numeric",
+            AccountNumberProblem::NotAlphanumeric => "must be alphanumeric",
+            AccountNumberProblem::ChecksumMismatch => "check digits do not match",
+        })
+    }
+}
+
+/// Why the terms of a loan or term deposit were refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TermsProblem {
+    PrincipalNotPositive,
+    NegativeRate,
+    ZeroTerm,
+    NegativeLateFee,
+    DateOutOfRange,
+}
+
+impl fmt::Display for TermsProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TermsProblem::PrincipalNotPositive => "principal must be greater than zero",
+            TermsProblem::NegativeRate => "rate must not be negative",
+            TermsProblem::ZeroTerm => "term must be at least one month",
+            TermsProblem::NegativeLateFee => "late fee must not be negative",
+            TermsProblem::DateOutOfRange => "dates run past the supported range",
+        })
+    }
+}
+
+/// Optional parts of the bank an operation may depend on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    SuspenseAccount,
+    ClearingGateway,
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Feature::SuspenseAccount => "a suspense account",
+            Feature::ClearingGateway => "a clearing gateway",
+        })
+    }
 }
 
 #[derive(Debug, Error, PartialEq)]
 pub enum RepoError {
     #[error("Lock Error: Lock poisened")]
     LockPoisened,
+    #[error("Account {0} NOT FOUND in repository")]
+    NotFound(AccountId),
+    #[error("Account {0} was requested more than once")]
+    DuplicateId(AccountId),
+    #[error("Account {account_id} does not belong to tenant {tenant}")]
+    TenantMismatch {
+        account_id: AccountId,
+        tenant: TenantId,
+    },
+    #[error("Storage error: {0}")]
+    Storage(String),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AuditError {
+    #[error("Lock Error: Lock poisened")]
+    LockPoisened,
+    #[error("Audit record {0} does not match the hash chain")]
+    Tampered(u64),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ClearingError {
+    /// The clearing house could not be reached; the request can be retried.
+    #[error("clearing house unavailable: {0}")]
+    Unavailable(String),
+    /// The clearing house refused the payment outright.
+    #[error("payment rejected: {0}")]
+    Rejected(String),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum NotifyError {
+    /// The customer has no address the channel can deliver to.
+    #[error("customer {0} has no address for this channel")]
+    NoRecipient(CustomerId),
+    #[error("notification could not be delivered: {0}")]
+    Delivery(String),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum SnapshotError {
+    #[error("snapshot file could not be read or written: {0}")]
+    Io(String),
+    #[error("snapshot is malformed: {0}")]
+    Malformed(String),
+    #[error("snapshot format version {0} is not supported")]
+    UnsupportedVersion(u32),
+    /// Snapshots are only restored into a bank without accounts or history.
+    #[error("the bank already holds data; restore into an empty bank")]
+    TargetNotEmpty,
+    /// Snapshots only restore into a bank of the tenant they were taken from.
+    #[error("snapshot holds accounts of tenant {0}")]
+    TenantMismatch(TenantId),
+}
+
+impl DomainError {
+    /// Stable, machine-readable identifier of the error. Codes are never
+    /// renamed or reused, so clients may match on them and look up their
+    /// own translations.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DomainError::InvalidAmount { .. } => "invalid_amount",
+            DomainError::InsufficientFunds { .. } => "insufficient_funds",
+            DomainError::AccountNotActive {
+                status: AccountStatus::Closed,
+                ..
+            } => "account_closed",
+            DomainError::AccountNotActive { .. } => "account_frozen",
+            DomainError::BalanceNotZero { .. } => "balance_not_zero",
+            DomainError::PrimaryOwnerImmutable { .. } => "primary_owner_immutable",
+            DomainError::NotAHolder { .. } => "not_a_holder",
+            DomainError::Unauthorized { .. } => "unauthorized",
+            DomainError::SelfApproval { .. } => "self_approval",
+            DomainError::ApproverRequired { .. } => "approver_required",
+            DomainError::UnsupportedTransaction { .. } => "unsupported_transaction",
+            DomainError::AccountNotFound(_) => "account_not_found",
+            DomainError::TransferToSelf(_) => "transfer_to_self",
+            DomainError::ApprovalRequired(_) => "approval_required",
+            DomainError::ApprovalNotFound(_) => "approval_not_found",
+            DomainError::InvalidAccountNumber { .. } => "invalid_account_number",
+            DomainError::AccountNumberMissing(_) => "account_number_missing",
+            DomainError::AccountNumberNotFound(_) => "account_number_not_found",
+            DomainError::InvalidTerms(_) => "invalid_terms",
+            DomainError::LoanStatusConflict { .. } => "loan_status_conflict",
+            DomainError::RepaymentExceedsPayoff { .. } => "repayment_exceeds_payoff",
+            DomainError::PartialPayoff { .. } => "partial_payoff",
+            DomainError::LoanNotFound(_) => "loan_not_found",
+            DomainError::TermDepositStatusConflict { .. } => "term_deposit_status_conflict",
+            DomainError::TermDepositMatured { .. } => "term_deposit_matured",
+            DomainError::TermDepositNotMature { .. } => "term_deposit_not_mature",
+            DomainError::TermDepositNotFound(_) => "term_deposit_not_found",
+            DomainError::ExternalPaymentStatusConflict { .. } => "external_payment_status_conflict",
+            DomainError::ExternalPaymentNotFound(_) => "external_payment_not_found",
+            DomainError::CurrencyMismatch { .. } => "currency_mismatch",
+            DomainError::NotConfigured(_) => "not_configured",
+            DomainError::CustomerNotFound(_) => "customer_not_found",
+            DomainError::CustomerAccessDenied { .. } => "customer_access_denied",
+            DomainError::TellerRequired => "teller_required",
+            DomainError::DrawerNotFound(_) => "drawer_not_found",
+            DomainError::DrawerClosed(_) => "drawer_closed",
+            DomainError::DrawerNotHeld { .. } => "drawer_not_held",
+            DomainError::DrawerAlreadyHeld { .. } => "drawer_already_held",
+            DomainError::InvalidDenomination(_) => "invalid_denomination",
+            DomainError::InsufficientCash { .. } => "insufficient_cash",
+            DomainError::CashMismatch { .. } => "cash_mismatch",
+            DomainError::AlertRuleNotFound(_) => "alert_rule_not_found",
+            DomainError::UnknownProduct { .. } => "unknown_product",
+            DomainError::TransactionLimitExceeded { .. } => "transaction_limit_exceeded",
+            DomainError::AccountLimitReached { .. } => "account_limit_reached",
+            DomainError::DailyWithdrawalLimitExceeded { .. } => "daily_withdrawal_limit_exceeded",
+        }
+    }
+
+    /// The values the message is built from, by name, for clients that
+    /// render their own message from the code.
+    pub fn details(&self) -> BTreeMap<&'static str, String> {
+        let fields: Vec<(&'static str, String)> = match self {
+            DomainError::InvalidAmount { account_id, amount } => {
+                vec![
+                    ("account_id", account_id.to_string()),
+                    ("amount", amount.to_string()),
+                ]
+            }
+            DomainError::InsufficientFunds {
+                account_id,
+                requested,
+                available,
+            } => vec![
+                ("account_id", account_id.to_string()),
+                ("requested", requested.to_string()),
+                ("available", available.to_string()),
+            ],
+            DomainError::AccountNotActive {
+                account_id,
+                status,
+                operation,
+            } => vec![
+                ("account_id", account_id.to_string()),
+                ("status", format!("{status:?}")),
+                ("operation", format!("{operation:?}")),
+            ],
+            DomainError::BalanceNotZero {
+                account_id,
+                balance,
+            } => vec![
+                ("account_id", account_id.to_string()),
+                ("balance", balance.to_string()),
+            ],
+            DomainError::PrimaryOwnerImmutable { account_id }
+            | DomainError::AccountNotFound(account_id)
+            | DomainError::TransferToSelf(account_id)
+            | DomainError::AccountNumberMissing(account_id) => {
+                vec![("account_id", account_id.to_string())]
+            }
+            DomainError::NotAHolder {
+                account_id,
+                customer,
+            }
+            | DomainError::Unauthorized {
+                account_id,
+                customer,
+            } => vec![
+                ("account_id", account_id.to_string()),
+                ("customer", customer.to_string()),
+            ],
+            DomainError::SelfApproval { approval_id }
+            | DomainError::ApproverRequired { approval_id }
+            | DomainError::ApprovalRequired(approval_id)
+            | DomainError::ApprovalNotFound(approval_id) => {
+                vec![("approval_id", approval_id.to_string())]
+            }
+            DomainError::UnsupportedTransaction { account_id, kind } => {
+                vec![
+                    ("account_id", account_id.to_string()),
+                    ("kind", kind.to_string()),
+                ]
+            }
+            DomainError::InvalidAccountNumber { input, problem } => {
+                vec![
+                    ("input", input.clone()),
+                    ("problem", format!("{problem:?}")),
+                ]
+            }
+            DomainError::AccountNumberNotFound(number) => {
+                vec![("account_number", number.as_str().to_string())]
+            }
+            DomainError::InvalidTerms(problem) => vec![("problem", format!("{problem:?}"))],
+            DomainError::LoanStatusConflict { loan_id, status } => {
+                vec![
+                    ("loan_id", loan_id.to_string()),
+                    ("status", format!("{status:?}")),
+                ]
+            }
+            DomainError::RepaymentExceedsPayoff {
+                loan_id,
+                amount,
+                payoff,
+            } => vec![
+                ("loan_id", loan_id.to_string()),
+                ("amount", amount.to_string()),
+                ("payoff", payoff.to_string()),
+            ],
+            DomainError::PartialPayoff { loan_id, payoff } => {
+                vec![
+                    ("loan_id", loan_id.to_string()),
+                    ("payoff", payoff.to_string()),
+                ]
+            }
+            DomainError::LoanNotFound(loan_id) => vec![("loan_id", loan_id.to_string())],
+            DomainError::TermDepositStatusConflict { deposit_id, status } => vec![
+                ("deposit_id", deposit_id.to_string()),
+                ("status", format!("{status:?}")),
+            ],
+            DomainError::TermDepositMatured {
+                deposit_id,
+                maturity,
+            }
+            | DomainError::TermDepositNotMature {
+                deposit_id,
+                maturity,
+            } => vec![
+                ("deposit_id", deposit_id.to_string()),
+                ("maturity", maturity.to_string()),
+            ],
+            DomainError::TermDepositNotFound(deposit_id) => {
+                vec![("deposit_id", deposit_id.to_string())]
+            }
+            DomainError::ExternalPaymentStatusConflict {
+                end_to_end_id,
+                status,
+                requested,
+            } => vec![
+                ("end_to_end_id", end_to_end_id.clone()),
+                ("status", format!("{status:?}")),
+                ("requested", format!("{requested:?}")),
+            ],
+            DomainError::ExternalPaymentNotFound(end_to_end_id) => {
+                vec![("end_to_end_id", end_to_end_id.clone())]
+            }
+            DomainError::CurrencyMismatch { expected, found } => {
+                vec![("expected", expected.clone()), ("found", found.clone())]
+            }
+            DomainError::NotConfigured(feature) => vec![("feature", format!("{feature:?}"))],
+            DomainError::CustomerNotFound(customer) => vec![("customer", customer.to_string())],
+            DomainError::CustomerAccessDenied { customer, caller } => {
+                vec![
+                    ("customer", customer.to_string()),
+                    ("caller", caller.to_string()),
+                ]
+            }
+            DomainError::TellerRequired => vec![],
+            DomainError::DrawerNotFound(drawer_id) | DomainError::DrawerClosed(drawer_id) => {
+                vec![("drawer_id", drawer_id.to_string())]
+            }
+            DomainError::DrawerNotHeld { drawer_id, teller }
+            | DomainError::DrawerAlreadyHeld { teller, drawer_id } => {
+                vec![
+                    ("drawer_id", drawer_id.to_string()),
+                    ("teller", teller.to_string()),
+                ]
+            }
+            DomainError::InvalidDenomination(denomination) => {
+                vec![("denomination", denomination.to_string())]
+            }
+            DomainError::InsufficientCash {
+                drawer_id,
+                denomination,
+                requested,
+                available,
+            } => vec![
+                ("drawer_id", drawer_id.to_string()),
+                ("denomination", denomination.to_string()),
+                ("requested", requested.to_string()),
+                ("available", available.to_string()),
+            ],
+            DomainError::CashMismatch {
+                account_id,
+                amount,
+                counted,
+            } => vec![
+                ("account_id", account_id.to_string()),
+                ("amount", amount.to_string()),
+                ("counted", counted.to_string()),
+            ],
+            DomainError::AlertRuleNotFound(rule_id) => vec![("rule_id", rule_id.to_string())],
+            DomainError::UnknownProduct { tenant, product } => {
+                vec![("tenant", tenant.to_string()), ("product", product.clone())]
+            }
+            DomainError::TransactionLimitExceeded {
+                account_id,
+                amount,
+                limit,
+            } => vec![
+                ("account_id", account_id.to_string()),
+                ("amount", amount.to_string()),
+                ("limit", limit.to_string()),
+            ],
+            DomainError::AccountLimitReached { owner, limit } => {
+                vec![("owner", owner.to_string()), ("limit", limit.to_string())]
+            }
+            DomainError::DailyWithdrawalLimitExceeded {
+                account_id,
+                requested,
+                remaining,
+            } => vec![
+                ("account_id", account_id.to_string()),
+                ("requested", requested.to_string()),
+                ("remaining", remaining.to_string()),
+            ],
+        };
+        fields.into_iter().collect()
+    }
+
+    /// The HTTP status an API reports the error with.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            DomainError::AccountNotFound(_)
+            | DomainError::AccountNumberNotFound(_)
+            | DomainError::ApprovalNotFound(_)
+            | DomainError::LoanNotFound(_)
+            | DomainError::TermDepositNotFound(_)
+            | DomainError::ExternalPaymentNotFound(_)
+            | DomainError::CustomerNotFound(_)
+            | DomainError::DrawerNotFound(_)
+            | DomainError::AlertRuleNotFound(_) => 404,
+            DomainError::Unauthorized { .. }
+            | DomainError::SelfApproval { .. }
+            | DomainError::ApproverRequired { .. }
+            | DomainError::CustomerAccessDenied { .. }
+            | DomainError::TellerRequired
+            | DomainError::DrawerNotHeld { .. } => 403,
+            DomainError::ApprovalRequired(_) => 202,
+            DomainError::AccountNotActive { .. }
+            | DomainError::BalanceNotZero { .. }
+            | DomainError::LoanStatusConflict { .. }
+            | DomainError::TermDepositStatusConflict { .. }
+            | DomainError::TermDepositMatured { .. }
+            | DomainError::TermDepositNotMature { .. }
+            | DomainError::ExternalPaymentStatusConflict { .. }
+            | DomainError::DrawerClosed(_)
+            | DomainError::DrawerAlreadyHeld { .. }
+            | DomainError::AccountLimitReached { .. } => 409,
+            DomainError::NotConfigured(_) => 501,
+            _ => 422,
+        }
+    }
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Domain(err) => err.code(),
+            AppError::Repo(RepoError::NotFound(_)) => "account_not_found",
+            AppError::Repo(_) => "repository_error",
+            AppError::Audit(_) => "audit_error",
+            AppError::Clearing(ClearingError::Unavailable(_)) => "clearing_unavailable",
+            AppError::Clearing(ClearingError::Rejected(_)) => "clearing_rejected",
+            AppError::Snapshot(SnapshotError::Io(_)) => "snapshot_io",
+            AppError::Snapshot(SnapshotError::Malformed(_)) => "snapshot_malformed",
+            AppError::Snapshot(SnapshotError::UnsupportedVersion(_)) => {
+                "snapshot_version_unsupported"
+            }
+            AppError::Snapshot(SnapshotError::TargetNotEmpty) => "snapshot_target_not_empty",
+            AppError::Snapshot(SnapshotError::TenantMismatch(_)) => "snapshot_tenant_mismatch",
+        }
+    }
+}
+
+/// An error as reported to API and CLI clients.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// See [`DomainError::code`].
+    pub code: &'static str,
+    /// English message for people; clients translating should build their
+    /// own from `code` and `details`.
+    pub message: String,
+    pub details: BTreeMap<&'static str, String>,
+}
+
+impl ErrorResponse {
+    /// Process exit status for the CLI, following the BSD `sysexits.h`
+    /// conventions.
+    pub fn exit_code(&self) -> u8 {
+        match self.status {
+            202 => 75,
+            403 => 77,
+            404 => 66,
+            501 => 78,
+            503 => 69,
+            400..=499 => 65,
+            _ => 70,
+        }
+    }
+}
+
+impl From<&AppError> for ErrorResponse {
+    fn from(err: &AppError) -> Self {
+        let (status, details) = match err {
+            AppError::Domain(domain) => (domain.http_status(), domain.details()),
+            AppError::Repo(RepoError::NotFound(id)) => {
+                (404, BTreeMap::from([("account_id", id.to_string())]))
+            }
+            AppError::Clearing(ClearingError::Unavailable(_)) => (503, BTreeMap::new()),
+            AppError::Clearing(ClearingError::Rejected(reason)) => {
+                (422, BTreeMap::from([("reason", reason.clone())]))
+            }
+            AppError::Snapshot(SnapshotError::UnsupportedVersion(version)) => {
+                (422, BTreeMap::from([("version", version.to_string())]))
+            }
+            AppError::Snapshot(SnapshotError::Malformed(_)) => (422, BTreeMap::new()),
+            AppError::Snapshot(SnapshotError::TargetNotEmpty) => (409, BTreeMap::new()),
+            AppError::Snapshot(SnapshotError::TenantMismatch(tenant)) => {
+                (409, BTreeMap::from([("tenant", tenant.to_string())]))
+            }
+            AppError::Repo(_) | AppError::Audit(_) | AppError::Snapshot(SnapshotError::Io(_)) => {
+                (500, BTreeMap::new())
+            }
+        };
+
+        ErrorResponse {
+            status,
+            code: err.code(),
+            message: match err {
+                AppError::Domain(domain) => domain.to_string(),
+                _ => err.to_string(),
+            },
+            details,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use rust_decimal::dec;
+
+    use crate::{
+        account::{AccountStatus, Money},
+        errors::{
+            AccountOperation, AppError, ClearingError, DomainError, ErrorResponse, RepoError,
+        },
+    };
+
+    #[test]
+    fn test_error_response_will_carry_code_status_and_details() {
+        let err = AppError::Domain(DomainError::InsufficientFunds {
+            account_id: 7,
+            requested: Money(dec!(150)),
+            available: Money(dec!(100.50)),
+        });
+
+        let response = ErrorResponse::from(&err);
+
+        assert_eq!(response.status, 422);
+        assert_eq!(response.code, "insufficient_funds");
+        assert_eq!(
+            response.message,
+            "account 7 has 100.50 available, 150 was requested"
+        );
+        assert_eq!(response.details["account_id"], "7");
+        assert_eq!(response.details["requested"], "150");
+        assert_eq!(response.details["available"], "100.50");
+        assert_eq!(response.exit_code(), 65);
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["code"], "insufficient_funds");
+        assert_eq!(json["details"]["available"], "100.50");
+    }
+
+    #[test]
+    fn test_error_codes_will_tell_closed_from_frozen_accounts() {
+        let inactive = |status| DomainError::AccountNotActive {
+            account_id: 1,
+            status,
+            operation: AccountOperation::Withdraw,
+        };
+
+        assert_eq!(inactive(AccountStatus::Closed).code(), "account_closed");
+        assert_eq!(inactive(AccountStatus::Frozen).code(), "account_frozen");
+        assert_eq!(inactive(AccountStatus::Frozen).http_status(), 409);
+    }
+
+    #[test]
+    fn test_error_response_will_map_infrastructure_errors() {
+        let missing = ErrorResponse::from(&AppError::Repo(RepoError::NotFound(3)));
+        assert_eq!((missing.status, missing.code), (404, "account_not_found"));
+        assert_eq!(missing.exit_code(), 66);
+
+        let storage = ErrorResponse::from(&AppError::Repo(RepoError::Storage("disk".into())));
+        assert_eq!((storage.status, storage.code), (500, "repository_error"));
+        assert_eq!(storage.exit_code(), 70);
+
+        let down = AppError::Clearing(ClearingError::Unavailable("timeout".into()));
+        assert_eq!(ErrorResponse::from(&down).exit_code(), 69);
+    }
 }
@@ -0,0 +1,234 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::{AccountId, Money},
+    account_number::AccountNumber,
+    errors::{ClearingError, DomainError},
+};
+
+/// A payment to an account held at another institution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalTransfer {
+    pub creditor_name: String,
+    pub creditor_account: AccountNumber,
+    /// BIC of the creditor's bank, when known.
+    pub creditor_agent: Option<String>,
+    pub amount: Money,
+    pub remittance: Option<String>,
+}
+
+/// An external transfer debited from a customer and waiting to be sent in a
+/// payment file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutgoingPayment {
+    /// Travels with the payment end to end, so returns and statements can be
+    /// matched back to it.
+    pub end_to_end_id: String,
+    pub debtor_account: AccountId,
+    pub debtor_name: String,
+    pub debtor_number: AccountNumber,
+    pub transfer: ExternalTransfer,
+    pub requested_execution: NaiveDate,
+}
+
+/// A credit reported on a statement from another institution, for one of
+/// our accounts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncomingPayment {
+    /// Unique per statement entry; used to ignore entries imported before.
+    pub reference: String,
+    pub creditor_account: AccountNumber,
+    pub debtor_name: Option<String>,
+    pub amount: Money,
+    pub currency: String,
+    pub booking_date: NaiveDate,
+    pub remittance: Option<String>,
+}
+
+/// Where an outgoing payment is on its way through clearing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ExternalPaymentStatus {
+    /// Debited and waiting to be sent.
+    Pending,
+    /// Accepted by the clearing house, not yet settled.
+    Submitted,
+    /// Paid to the other institution.
+    Settled,
+    /// Refused by the clearing house before settlement.
+    Rejected(String),
+    /// Sent back by the other institution after settlement.
+    Returned(String),
+}
+
+/// An outgoing payment and its clearing status. The funds sit in the bank's
+/// suspense account from the debit until the payment settles or is rejected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalPayment {
+    pub payment: OutgoingPayment,
+    pub status: ExternalPaymentStatus,
+}
+
+impl ExternalPayment {
+    pub fn new(payment: OutgoingPayment) -> Self {
+        Self {
+            payment,
+            status: ExternalPaymentStatus::Pending,
+        }
+    }
+
+    pub fn submit(&mut self) -> Result<(), DomainError> {
+        self.transition(
+            ExternalPaymentStatus::Pending,
+            ExternalPaymentStatus::Submitted,
+        )
+    }
+
+    pub fn settle(&mut self) -> Result<(), DomainError> {
+        self.transition(
+            ExternalPaymentStatus::Submitted,
+            ExternalPaymentStatus::Settled,
+        )
+    }
+
+    /// Payments can be rejected when they are handed over or while they wait
+    /// for settlement.
+    pub fn reject(&mut self, reason: &str) -> Result<(), DomainError> {
+        let rejected = ExternalPaymentStatus::Rejected(reason.into());
+        match self.status {
+            ExternalPaymentStatus::Pending => {
+                self.status = rejected;
+                Ok(())
+            }
+            _ => self.transition(ExternalPaymentStatus::Submitted, rejected),
+        }
+    }
+
+    pub fn return_funds(&mut self, reason: &str) -> Result<(), DomainError> {
+        self.transition(
+            ExternalPaymentStatus::Settled,
+            ExternalPaymentStatus::Returned(reason.into()),
+        )
+    }
+
+    fn transition(
+        &mut self,
+        from: ExternalPaymentStatus,
+        to: ExternalPaymentStatus,
+    ) -> Result<(), DomainError> {
+        if self.status != from {
+            return Err(DomainError::ExternalPaymentStatusConflict {
+                end_to_end_id: self.payment.end_to_end_id.clone(),
+                status: self.status.clone(),
+                requested: to,
+            });
+        }
+        self.status = to;
+        Ok(())
+    }
+}
+
+/// What the clearing house reports back about a submitted payment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ClearingOutcome {
+    Settled,
+    Rejected(String),
+    Returned(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClearingEvent {
+    pub end_to_end_id: String,
+    pub outcome: ClearingOutcome,
+}
+
+/// A clearing event that cannot apply however often it is retried, such as
+/// one for an unknown payment or a second settlement of the same payment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub event: ClearingEvent,
+    /// Error code of the failure, as in [`crate::errors::ErrorResponse`].
+    pub code: String,
+    pub message: String,
+}
+
+/// Connection to the clearing system that carries payments to other
+/// institutions.
+pub trait ClearingGateway: Send + Sync {
+    /// Hands a payment over for clearing. Settlement is reported later by
+    /// [`ClearingGateway::poll`].
+    fn submit(&self, payment: &OutgoingPayment) -> Result<(), ClearingError>;
+
+    /// Returns the outcomes reported since the previous poll.
+    fn poll(&self) -> Result<Vec<ClearingEvent>, ClearingError>;
+}
+
+#[cfg(test)]
+pub mod tests {
+    use chrono::NaiveDate;
+    use rust_decimal::dec;
+
+    use crate::{
+        account::Money,
+        errors::DomainError,
+        interbank::{ExternalPayment, ExternalPaymentStatus, ExternalTransfer, OutgoingPayment},
+    };
+
+    fn payment() -> ExternalPayment {
+        ExternalPayment::new(OutgoingPayment {
+            end_to_end_id: "E2E000000000001".into(),
+            debtor_account: 1,
+            debtor_name: "Ada Lovelace".into(),
+            debtor_number: "GB82WEST12345698765432".parse().unwrap(),
+            transfer: ExternalTransfer {
+                creditor_name: "Charles Babbage".into(),
+                creditor_account: "DE89370400440532013000".parse().unwrap(),
+                creditor_agent: None,
+                amount: Money(dec!(10)),
+                remittance: None,
+            },
+            requested_execution: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+        })
+    }
+
+    #[test]
+    fn test_external_payment_will_only_return_after_settlement() {
+        let mut payment = payment();
+
+        assert_eq!(
+            payment.return_funds("AC04"),
+            Err(DomainError::ExternalPaymentStatusConflict {
+                end_to_end_id: "E2E000000000001".into(),
+                status: ExternalPaymentStatus::Pending,
+                requested: ExternalPaymentStatus::Returned("AC04".into()),
+            })
+        );
+        assert!(payment.settle().is_err());
+
+        payment.submit().unwrap();
+        payment.settle().unwrap();
+        assert!(payment.reject("late").is_err());
+        payment.return_funds("AC04").unwrap();
+
+        assert_eq!(
+            payment.status,
+            ExternalPaymentStatus::Returned("AC04".into())
+        );
+    }
+
+    #[test]
+    fn test_external_payment_will_be_rejected_before_settlement_only() {
+        let mut pending = payment();
+        pending.reject("RC01").unwrap();
+        assert_eq!(
+            pending.status,
+            ExternalPaymentStatus::Rejected("RC01".into())
+        );
+        assert!(pending.submit().is_err());
+
+        let mut submitted = payment();
+        submitted.submit().unwrap();
+        submitted.reject("AM04").unwrap();
+        assert!(submitted.settle().is_err());
+    }
+}
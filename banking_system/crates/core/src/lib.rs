@@ -1,3 +1,19 @@
 pub mod account;
+pub mod account_number;
+pub mod approval;
+pub mod async_repo;
+pub mod audit;
+pub mod cash;
+pub mod clock;
+pub mod context;
 pub mod customer;
 pub mod errors;
+pub mod interbank;
+pub mod loan;
+pub mod notification;
+pub mod privacy;
+pub mod product;
+pub mod query;
+pub mod snapshot;
+pub mod tenant;
+pub mod term_deposit;
@@ -0,0 +1,650 @@
+use chrono::{Months, NaiveDate};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::{AccountId, Money},
+    customer::CustomerId,
+    errors::{DomainError, TermsProblem},
+};
+
+pub type LoanId = u64;
+
+const DAYS_PER_YEAR: u32 = 365;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AmortizationMethod {
+    /// Equal total payments; the interest share shrinks over time.
+    Annuity,
+    /// Equal principal payments plus interest on the remaining balance.
+    EqualPrincipal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoanTerms {
+    pub principal: Money,
+    /// Annual rate as a fraction, compounded monthly.
+    pub annual_rate: Decimal,
+    pub term_months: u32,
+    pub method: AmortizationMethod,
+    /// Disbursement date; installments fall due monthly from here.
+    pub start: NaiveDate,
+    /// Charged once for each installment that is overdue when fees are
+    /// assessed.
+    pub late_fee: Money,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Installment {
+    pub number: u32,
+    pub due: NaiveDate,
+    pub payment: Money,
+    pub interest: Money,
+    pub principal: Money,
+    /// Principal still owed once this installment is paid.
+    pub balance_after: Money,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LoanStatus {
+    /// Originated but the funds have not been paid out yet.
+    #[default]
+    Approved,
+    Active,
+    PaidOff,
+}
+
+/// How a repayment was allocated: fees first, then interest, then principal.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RepaymentSplit {
+    pub fees: Money,
+    pub interest: Money,
+    pub principal: Money,
+}
+
+impl RepaymentSplit {
+    pub fn total(&self) -> Money {
+        Money(self.fees.0 + self.interest.0 + self.principal.0)
+    }
+}
+
+/// What it costs to settle the loan in full on a given date.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PayoffQuote {
+    pub on: NaiveDate,
+    pub principal: Money,
+    /// Scheduled interest already due but unpaid, plus interest accrued daily
+    /// since the last due date.
+    pub interest: Money,
+    pub fees: Money,
+}
+
+impl PayoffQuote {
+    pub fn total(&self) -> Money {
+        Money(self.principal.0 + self.interest.0 + self.fees.0)
+    }
+}
+
+/// A loan paid out into a deposit account and repaid against a monthly
+/// schedule. Payments are tracked cumulatively rather than per installment:
+/// whatever has been paid covers the earliest installments first. Principal
+/// prepaid ahead of the schedule re-amortizes the installments not yet due.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Loan {
+    pub id: LoanId,
+    pub borrower: CustomerId,
+    /// Deposit account the loan is paid into and repaid from.
+    pub account_id: AccountId,
+    pub terms: LoanTerms,
+    pub schedule: Vec<Installment>,
+    pub status: LoanStatus,
+    pub principal_paid: Money,
+    /// Part of `principal_paid` paid ahead of the schedule. It is not part
+    /// of any installment, so it never counts towards one.
+    #[serde(default)]
+    pub principal_prepaid: Money,
+    pub interest_paid: Money,
+    pub fees_charged: Money,
+    pub fees_paid: Money,
+    /// Installments a late fee has already been charged for.
+    pub late_installments: Vec<u32>,
+}
+
+impl Loan {
+    pub fn originate(
+        id: LoanId,
+        borrower: CustomerId,
+        account_id: AccountId,
+        terms: LoanTerms,
+    ) -> Result<Self, DomainError> {
+        if terms.principal.0 <= Decimal::ZERO {
+            return Err(DomainError::InvalidTerms(
+                TermsProblem::PrincipalNotPositive,
+            ));
+        }
+        if terms.annual_rate < Decimal::ZERO {
+            return Err(DomainError::InvalidTerms(TermsProblem::NegativeRate));
+        }
+        if terms.term_months == 0 {
+            return Err(DomainError::InvalidTerms(TermsProblem::ZeroTerm));
+        }
+        if terms.late_fee.0 < Decimal::ZERO {
+            return Err(DomainError::InvalidTerms(TermsProblem::NegativeLateFee));
+        }
+
+        Ok(Loan {
+            id,
+            borrower,
+            account_id,
+            schedule: amortization_schedule(&terms)?,
+            terms,
+            status: LoanStatus::Approved,
+            principal_paid: Money::default(),
+            principal_prepaid: Money::default(),
+            interest_paid: Money::default(),
+            fees_charged: Money::default(),
+            fees_paid: Money::default(),
+            late_installments: Vec::new(),
+        })
+    }
+
+    /// Marks the loan as paid out and returns the amount to credit to the
+    /// linked account.
+    pub fn disburse(&mut self) -> Result<Money, DomainError> {
+        if self.status != LoanStatus::Approved {
+            return Err(self.status_conflict());
+        }
+
+        self.status = LoanStatus::Active;
+        Ok(self.terms.principal)
+    }
+
+    pub fn outstanding_principal(&self) -> Money {
+        Money(self.terms.principal.0 - self.principal_paid.0)
+    }
+
+    /// Scheduled payments due on or before `on` that have not been paid.
+    pub fn arrears(&self, on: NaiveDate) -> Money {
+        let due: Decimal = self
+            .due_by(on)
+            .map(|installment| installment.payment.0)
+            .sum();
+        Money((due - self.paid_on_schedule()).max(Decimal::ZERO))
+    }
+
+    /// Charges the late fee for every installment due before `on` that is
+    /// still not fully covered, once per installment, and returns the amount
+    /// newly charged.
+    pub fn assess_late_fees(&mut self, on: NaiveDate) -> Result<Money, DomainError> {
+        self.ensure_active()?;
+
+        let paid = self.paid_on_schedule();
+        let mut cumulative = Decimal::ZERO;
+        let mut charged = Decimal::ZERO;
+
+        for installment in self
+            .schedule
+            .iter()
+            .filter(|installment| installment.due < on)
+        {
+            cumulative += installment.payment.0;
+            if cumulative > paid && !self.late_installments.contains(&installment.number) {
+                self.late_installments.push(installment.number);
+                charged += self.terms.late_fee.0;
+            }
+        }
+
+        self.fees_charged.0 += charged;
+        Ok(Money(charged))
+    }
+
+    pub fn payoff_quote(&self, on: NaiveDate) -> PayoffQuote {
+        let principal = self.outstanding_principal();
+
+        let last_due = self
+            .due_by(on)
+            .last()
+            .map_or(self.terms.start, |installment| installment.due);
+        let days = (on - last_due).num_days().max(0);
+        let accrued = principal.0 * self.terms.annual_rate * Decimal::from(days)
+            / Decimal::from(DAYS_PER_YEAR);
+
+        PayoffQuote {
+            on,
+            principal,
+            interest: Money(round(self.interest_due(on) + accrued)),
+            fees: Money(self.fees_charged.0 - self.fees_paid.0),
+        }
+    }
+
+    /// Applies a repayment made on `on`. It settles outstanding fees, then
+    /// the interest and principal of installments that have fallen due. With
+    /// nothing overdue it pays the next unpaid installment early instead.
+    /// Anything beyond that is a prepayment of principal, which lowers the
+    /// installments still to come. Paying exactly the payoff quote closes
+    /// the loan.
+    pub fn repay(&mut self, amount: Money, on: NaiveDate) -> Result<RepaymentSplit, DomainError> {
+        self.ensure_active()?;
+        if amount.0 <= Decimal::ZERO {
+            return Err(DomainError::InvalidAmount {
+                account_id: self.account_id,
+                amount,
+            });
+        }
+
+        let quote = self.payoff_quote(on);
+        if amount.0 > quote.total().0 {
+            return Err(DomainError::RepaymentExceedsPayoff {
+                loan_id: self.id,
+                amount,
+                payoff: quote.total(),
+            });
+        }
+
+        let due = self.due_by(on).count();
+        let covered = self.next_unpaid().map_or(due, |next| due.max(next + 1));
+
+        let split = if amount == quote.total() {
+            self.status = LoanStatus::PaidOff;
+            RepaymentSplit {
+                fees: quote.fees,
+                interest: quote.interest,
+                principal: quote.principal,
+            }
+        } else {
+            let mut remaining = amount.0;
+            let mut take = |owed: Decimal| {
+                let part = remaining.min(owed.max(Decimal::ZERO));
+                remaining -= part;
+                part
+            };
+            let fees = take(quote.fees.0);
+            let (mut interest, mut principal) = (Decimal::ZERO, Decimal::ZERO);
+            for through in [due, covered] {
+                let scheduled: Decimal = self.schedule[..through]
+                    .iter()
+                    .map(|installment| installment.interest.0)
+                    .sum();
+                interest += take(scheduled - self.interest_paid.0 - interest);
+                principal += take(quote.principal.0 - principal - self.scheduled_balance(through));
+            }
+            principal += take(quote.principal.0 - principal);
+            let split = RepaymentSplit {
+                fees: Money(fees),
+                interest: Money(interest),
+                principal: Money(principal),
+            };
+
+            if split.principal == quote.principal {
+                return Err(DomainError::PartialPayoff {
+                    loan_id: self.id,
+                    payoff: quote.total(),
+                });
+            }
+            split
+        };
+
+        self.fees_paid.0 += split.fees.0;
+        self.interest_paid.0 += split.interest.0;
+        self.principal_paid.0 += split.principal.0;
+        if self.status == LoanStatus::Active {
+            self.reamortize(covered)?;
+        }
+        Ok(split)
+    }
+
+    /// Spreads the principal still owed over the installments after the
+    /// first `covered` if it has fallen below what the schedule expects
+    /// once those are paid, so their interest is charged only on what is
+    /// actually owed.
+    fn reamortize(&mut self, covered: usize) -> Result<(), DomainError> {
+        let scheduled_balance = self.scheduled_balance(covered);
+        let outstanding = self.outstanding_principal().0;
+        if outstanding >= scheduled_balance {
+            return Ok(());
+        }
+
+        let remaining = amortize(&self.terms, outstanding, covered as u32 + 1)?;
+        self.schedule.truncate(covered);
+        self.schedule.extend(remaining);
+        self.principal_prepaid.0 += scheduled_balance - outstanding;
+        Ok(())
+    }
+
+    /// Principal the schedule expects to be owed once the first `count`
+    /// installments are paid.
+    fn scheduled_balance(&self, count: usize) -> Decimal {
+        match count {
+            0 => self.terms.principal.0,
+            count => self.schedule[count - 1].balance_after.0,
+        }
+    }
+
+    /// Index of the earliest installment payments have not fully covered.
+    fn next_unpaid(&self) -> Option<usize> {
+        let paid = self.paid_on_schedule();
+        let mut cumulative = Decimal::ZERO;
+        self.schedule.iter().position(|installment| {
+            cumulative += installment.payment.0;
+            cumulative > paid
+        })
+    }
+
+    /// Payments that went towards scheduled installments.
+    fn paid_on_schedule(&self) -> Decimal {
+        self.principal_paid.0 - self.principal_prepaid.0 + self.interest_paid.0
+    }
+
+    fn due_by(&self, on: NaiveDate) -> impl Iterator<Item = &Installment> {
+        self.schedule
+            .iter()
+            .take_while(move |installment| installment.due <= on)
+    }
+
+    /// Scheduled interest on installments due by `on` that is still unpaid.
+    fn interest_due(&self, on: NaiveDate) -> Decimal {
+        let scheduled: Decimal = self
+            .due_by(on)
+            .map(|installment| installment.interest.0)
+            .sum();
+        (scheduled - self.interest_paid.0).max(Decimal::ZERO)
+    }
+
+    fn ensure_active(&self) -> Result<(), DomainError> {
+        match self.status {
+            LoanStatus::Active => Ok(()),
+            LoanStatus::Approved | LoanStatus::PaidOff => Err(self.status_conflict()),
+        }
+    }
+
+    fn status_conflict(&self) -> DomainError {
+        DomainError::LoanStatusConflict {
+            loan_id: self.id,
+            status: self.status.clone(),
+        }
+    }
+}
+
+/// Builds the monthly repayment plan for `terms`. Amounts are rounded to
+/// cents each month and the last installment absorbs the rounding, so the
+/// principal portions always add up to the amount lent.
+pub fn amortization_schedule(terms: &LoanTerms) -> Result<Vec<Installment>, DomainError> {
+    amortize(terms, terms.principal.0, 1)
+}
+
+/// Installments `first` to the end of the term, repaying `principal` over
+/// them at the terms' rate and method.
+fn amortize(
+    terms: &LoanTerms,
+    principal: Decimal,
+    first: u32,
+) -> Result<Vec<Installment>, DomainError> {
+    let count = terms.term_months + 1 - first;
+    let months = Decimal::from(count);
+    let rate = terms.annual_rate / Decimal::from(12);
+
+    let annuity_payment = if rate.is_zero() {
+        round(principal / months)
+    } else {
+        let growth = (0..count).fold(Decimal::ONE, |acc, _| acc * (Decimal::ONE + rate));
+        round(principal * rate * growth / (growth - Decimal::ONE))
+    };
+    let equal_principal = round(principal / months);
+
+    let mut balance = principal;
+    let mut schedule = Vec::with_capacity(count as usize);
+
+    for number in first..=terms.term_months {
+        let due = terms
+            .start
+            .checked_add_months(Months::new(number))
+            .ok_or(DomainError::InvalidTerms(TermsProblem::DateOutOfRange))?;
+        let interest = round(balance * rate);
+
+        let principal_part = if number == terms.term_months {
+            balance
+        } else {
+            match terms.method {
+                AmortizationMethod::Annuity => (annuity_payment - interest).min(balance),
+                AmortizationMethod::EqualPrincipal => equal_principal.min(balance),
+            }
+        };
+        balance -= principal_part;
+
+        schedule.push(Installment {
+            number,
+            due,
+            payment: Money(principal_part + interest),
+            interest: Money(interest),
+            principal: Money(principal_part),
+            balance_after: Money(balance),
+        });
+    }
+
+    Ok(schedule)
+}
+
+fn round(amount: Decimal) -> Decimal {
+    amount.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use chrono::NaiveDate;
+    use rust_decimal::{Decimal, dec};
+
+    use crate::{
+        account::Money,
+        errors::{DomainError, TermsProblem},
+        loan::{AmortizationMethod, Loan, LoanStatus, LoanTerms, amortization_schedule},
+    };
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn terms(principal: Decimal, method: AmortizationMethod) -> LoanTerms {
+        LoanTerms {
+            principal: Money(principal),
+            annual_rate: dec!(0.12),
+            term_months: 12,
+            method,
+            start: date(2026, 1, 15),
+            late_fee: Money(dec!(25)),
+        }
+    }
+
+    fn active_loan() -> Loan {
+        let mut loan = Loan::originate(
+            1,
+            7,
+            3,
+            terms(dec!(1200), AmortizationMethod::EqualPrincipal),
+        )
+        .unwrap();
+        loan.disburse().unwrap();
+        loan
+    }
+
+    #[test]
+    fn test_schedule_will_amortize_annuity_with_equal_payments() {
+        let schedule =
+            amortization_schedule(&terms(dec!(10000), AmortizationMethod::Annuity)).unwrap();
+
+        assert_eq!(schedule.len(), 12);
+        assert_eq!(schedule[0].due, date(2026, 2, 15));
+        assert_eq!(schedule[0].payment, Money(dec!(888.49)));
+        assert_eq!(schedule[0].interest, Money(dec!(100.00)));
+        assert!(
+            schedule[..11]
+                .iter()
+                .all(|i| i.payment == Money(dec!(888.49)))
+        );
+        assert!(schedule[1].interest.0 < schedule[0].interest.0);
+
+        let principal: Decimal = schedule.iter().map(|i| i.principal.0).sum();
+        assert_eq!(principal, dec!(10000));
+        assert_eq!(schedule[11].balance_after, Money(Decimal::ZERO));
+        assert!((schedule[11].payment.0 - dec!(888.49)).abs() < dec!(0.05));
+    }
+
+    #[test]
+    fn test_schedule_will_amortize_equal_principal_with_falling_payments() {
+        let schedule =
+            amortization_schedule(&terms(dec!(1000), AmortizationMethod::EqualPrincipal)).unwrap();
+
+        assert_eq!(schedule[0].principal, Money(dec!(83.33)));
+        assert_eq!(schedule[0].interest, Money(dec!(10.00)));
+        assert_eq!(schedule[1].interest, Money(dec!(9.17)));
+        assert_eq!(schedule[11].principal, Money(dec!(83.37)));
+        let principal: Decimal = schedule.iter().map(|i| i.principal.0).sum();
+        assert_eq!(principal, dec!(1000));
+    }
+
+    #[test]
+    fn test_loan_will_reject_invalid_terms_and_double_disbursement() {
+        let mut zero_term = terms(dec!(1000), AmortizationMethod::Annuity);
+        zero_term.term_months = 0;
+        assert_eq!(
+            Loan::originate(1, 1, 1, zero_term),
+            Err(DomainError::InvalidTerms(TermsProblem::ZeroTerm))
+        );
+        assert_eq!(
+            Loan::originate(1, 1, 1, terms(dec!(0), AmortizationMethod::Annuity)),
+            Err(DomainError::InvalidTerms(
+                TermsProblem::PrincipalNotPositive
+            ))
+        );
+
+        let mut loan = active_loan();
+        assert_eq!(
+            loan.disburse(),
+            Err(DomainError::LoanStatusConflict {
+                loan_id: loan.id,
+                status: LoanStatus::Active
+            })
+        );
+    }
+
+    #[test]
+    fn test_loan_will_split_repayment_into_interest_then_principal() {
+        let mut loan = active_loan();
+
+        let split = loan.repay(Money(dec!(112)), date(2026, 2, 15)).unwrap();
+
+        assert_eq!(split.fees, Money(Decimal::ZERO));
+        assert_eq!(split.interest, Money(dec!(12.00)));
+        assert_eq!(split.principal, Money(dec!(100.00)));
+        assert_eq!(loan.outstanding_principal(), Money(dec!(1100)));
+        assert_eq!(loan.arrears(date(2026, 2, 15)), Money(Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_loan_will_apply_early_payment_to_next_installment_before_prepaying() {
+        let mut loan = active_loan();
+
+        let split = loan.repay(Money(dec!(112)), date(2026, 2, 12)).unwrap();
+        assert_eq!(split.interest, Money(dec!(12.00)));
+        assert_eq!(split.principal, Money(dec!(100.00)));
+        assert_eq!(loan.principal_prepaid, Money(Decimal::ZERO));
+        assert_eq!(loan.arrears(date(2026, 2, 16)), Money(Decimal::ZERO));
+        assert_eq!(
+            loan.assess_late_fees(date(2026, 2, 20)).unwrap(),
+            Money(Decimal::ZERO)
+        );
+
+        let split = loan.repay(Money(dec!(211)), date(2026, 2, 20)).unwrap();
+        assert_eq!(split.interest, Money(dec!(11.00)));
+        assert_eq!(split.principal, Money(dec!(200.00)));
+        assert_eq!(loan.principal_prepaid, Money(dec!(100)));
+        assert_eq!(loan.schedule[1].payment, Money(dec!(111.00)));
+        assert_eq!(loan.arrears(date(2026, 3, 16)), Money(Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_loan_will_track_arrears_and_charge_late_fees_once() {
+        let mut loan = active_loan();
+
+        assert_eq!(loan.arrears(date(2026, 3, 20)), Money(dec!(223.00)));
+        assert_eq!(
+            loan.assess_late_fees(date(2026, 3, 20)).unwrap(),
+            Money(dec!(50))
+        );
+        assert_eq!(
+            loan.assess_late_fees(date(2026, 3, 21)).unwrap(),
+            Money(Decimal::ZERO)
+        );
+
+        let split = loan.repay(Money(dec!(100)), date(2026, 3, 21)).unwrap();
+        assert_eq!(split.fees, Money(dec!(50)));
+        assert_eq!(split.interest, Money(dec!(23.00)));
+        assert_eq!(split.principal, Money(dec!(27.00)));
+        assert_eq!(loan.arrears(date(2026, 3, 21)), Money(dec!(173.00)));
+    }
+
+    #[test]
+    fn test_loan_will_reamortize_after_prepayment_and_charge_less_interest() {
+        let mut loan = active_loan();
+        assert_eq!(loan.schedule[1].interest, Money(dec!(11.00)));
+
+        let split = loan.repay(Money(dec!(612)), date(2026, 2, 15)).unwrap();
+        assert_eq!(split.interest, Money(dec!(12.00)));
+        assert_eq!(split.principal, Money(dec!(600.00)));
+        assert_eq!(loan.principal_prepaid, Money(dec!(500)));
+
+        assert_eq!(loan.schedule.len(), 12);
+        assert_eq!(loan.schedule[0].payment, Money(dec!(112.00)));
+        assert_eq!(loan.schedule[1].number, 2);
+        assert_eq!(loan.schedule[1].due, date(2026, 3, 15));
+        assert_eq!(loan.schedule[1].interest, Money(dec!(6.00)));
+        assert_eq!(loan.schedule[1].principal, Money(dec!(54.55)));
+        let principal: Decimal = loan.schedule[1..].iter().map(|i| i.principal.0).sum();
+        assert_eq!(principal, dec!(600));
+
+        assert_eq!(loan.arrears(date(2026, 2, 16)), Money(Decimal::ZERO));
+        assert_eq!(loan.arrears(date(2026, 3, 15)), Money(dec!(60.55)));
+        assert_eq!(
+            loan.payoff_quote(date(2026, 3, 15)).interest,
+            Money(dec!(6.00))
+        );
+
+        let schedule = loan.schedule.clone();
+        for installment in &schedule[1..11] {
+            loan.repay(installment.payment, installment.due).unwrap();
+        }
+        let last = &schedule[11];
+        assert_eq!(loan.payoff_quote(last.due).total(), last.payment);
+        loan.repay(last.payment, last.due).unwrap();
+        assert_eq!(loan.status, LoanStatus::PaidOff);
+    }
+
+    #[test]
+    fn test_loan_will_quote_payoff_with_accrued_interest_and_close_when_paid() {
+        let mut loan = active_loan();
+        loan.repay(Money(dec!(112)), date(2026, 2, 15)).unwrap();
+
+        let quote = loan.payoff_quote(date(2026, 3, 1));
+        assert_eq!(quote.principal, Money(dec!(1100)));
+        // 14 days on 1100 at 12% a year.
+        assert_eq!(quote.interest, Money(dec!(5.06)));
+        assert_eq!(quote.total(), Money(dec!(1105.06)));
+
+        assert_eq!(
+            loan.repay(Money(dec!(1105.07)), date(2026, 3, 1)),
+            Err(DomainError::RepaymentExceedsPayoff {
+                loan_id: loan.id,
+                amount: Money(dec!(1105.07)),
+                payoff: Money(dec!(1105.06)),
+            })
+        );
+        loan.repay(quote.total(), date(2026, 3, 1)).unwrap();
+        assert_eq!(loan.status, LoanStatus::PaidOff);
+        assert_eq!(loan.outstanding_principal(), Money(Decimal::ZERO));
+        assert_eq!(
+            loan.repay(Money(dec!(1)), date(2026, 3, 2))
+                .unwrap_err()
+                .code(),
+            "loan_status_conflict"
+        );
+    }
+}
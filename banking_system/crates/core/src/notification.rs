@@ -0,0 +1,256 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::{AccountId, Money},
+    audit::AccountChange,
+    customer::CustomerId,
+    errors::NotifyError,
+};
+
+pub type AlertRuleId = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AlertCondition {
+    /// A single operation takes at least this much out of the account.
+    LargeWithdrawal(Money),
+    /// The balance falls below this amount. Alerts fire when it crosses the
+    /// threshold, not on every operation while it stays below.
+    LowBalance(Money),
+}
+
+/// A customer's request to be told when `condition` holds for an account
+/// they hold.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub id: AlertRuleId,
+    pub customer: CustomerId,
+    pub account_id: AccountId,
+    pub condition: AlertCondition,
+}
+
+/// What triggered a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Alert {
+    LargeWithdrawal { amount: Money, balance: Money },
+    LowBalance { balance: Money, threshold: Money },
+}
+
+impl AlertRule {
+    /// The alert `change` raises under this rule, if any. Customers who no
+    /// longer hold the account are not told about it.
+    pub fn evaluate(&self, change: &AccountChange) -> Option<Alert> {
+        if change.account_id != self.account_id {
+            return None;
+        }
+        let before = change.before.as_ref()?.balance;
+        let account = change.after.as_ref()?;
+        account.role_of(self.customer)?;
+        let after = account.balance;
+
+        match self.condition {
+            AlertCondition::LargeWithdrawal(threshold) => {
+                let amount = before.0 - after.0;
+                (amount > Decimal::ZERO && amount >= threshold.0).then_some(
+                    Alert::LargeWithdrawal {
+                        amount: Money(amount),
+                        balance: after,
+                    },
+                )
+            }
+            AlertCondition::LowBalance(threshold) => {
+                (before.0 >= threshold.0 && after.0 < threshold.0).then_some(Alert::LowBalance {
+                    balance: after,
+                    threshold,
+                })
+            }
+        }
+    }
+}
+
+/// A message to one customer about one of their accounts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    pub rule_id: AlertRuleId,
+    pub customer: CustomerId,
+    /// The customer's email address, if they gave one.
+    pub recipient: Option<String>,
+    pub account_id: AccountId,
+    pub alert: Alert,
+    pub at: SystemTime,
+}
+
+impl Notification {
+    pub fn subject(&self) -> String {
+        match self.alert {
+            Alert::LargeWithdrawal { .. } => {
+                format!("Large withdrawal from account {}", self.account_id)
+            }
+            Alert::LowBalance { .. } => format!("Low balance on account {}", self.account_id),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        match self.alert {
+            Alert::LargeWithdrawal { amount, balance } => format!(
+                "{amount} was taken out of account {}. The balance is now {balance}.",
+                self.account_id
+            ),
+            Alert::LowBalance { balance, threshold } => format!(
+                "Account {} is down to {balance}, below your alert threshold of {threshold}.",
+                self.account_id
+            ),
+        }
+    }
+}
+
+/// Delivers notifications to customers.
+pub trait Notifier: Send + Sync {
+    fn notify(&self, notification: &Notification) -> Result<(), NotifyError>;
+}
+
+/// At most `max` notifications per account in any window of `per`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub max: usize,
+    pub per: Duration,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            max: 5,
+            per: Duration::from_secs(3600),
+        }
+    }
+}
+
+/// Sliding-window limiter that keeps one busy account from flooding the
+/// notification channel.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    pub limit: RateLimit,
+    sent: Mutex<HashMap<AccountId, VecDeque<SystemTime>>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            sent: Mutex::default(),
+        }
+    }
+
+    /// Whether a notification about `account_id` may go out at `at`; if so
+    /// it counts against the limit.
+    pub fn allow(&self, account_id: AccountId, at: SystemTime) -> bool {
+        let mut sent = self.sent.lock().unwrap();
+        let window = sent.entry(account_id).or_default();
+        while window
+            .front()
+            .is_some_and(|first| at.duration_since(*first).unwrap_or_default() >= self.limit.per)
+        {
+            window.pop_front();
+        }
+
+        if window.len() >= self.limit.max {
+            return false;
+        }
+        window.push_back(at);
+        true
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use rust_decimal::dec;
+
+    use crate::{
+        account::{Account, Money},
+        audit::AccountChange,
+        notification::{Alert, AlertCondition, AlertRule, RateLimit, RateLimiter},
+    };
+
+    fn change(before: Money, after: Money) -> AccountChange {
+        AccountChange {
+            account_id: 3,
+            before: Some(Account::builder(3, 1).balance(before).build()),
+            after: Some(Account::builder(3, 1).balance(after).build()),
+        }
+    }
+
+    fn rule(condition: AlertCondition) -> AlertRule {
+        AlertRule {
+            id: 1,
+            customer: 1,
+            account_id: 3,
+            condition,
+        }
+    }
+
+    #[test]
+    fn test_alert_rule_will_fire_on_large_debits_and_on_crossing_low_balance() {
+        let large = rule(AlertCondition::LargeWithdrawal(Money(dec!(500))));
+        assert_eq!(
+            large.evaluate(&change(Money(dec!(1000)), Money(dec!(400)))),
+            Some(Alert::LargeWithdrawal {
+                amount: Money(dec!(600)),
+                balance: Money(dec!(400)),
+            })
+        );
+        assert_eq!(
+            large.evaluate(&change(Money(dec!(1000)), Money(dec!(600)))),
+            None
+        );
+        assert_eq!(
+            large.evaluate(&change(Money(dec!(0)), Money(dec!(900)))),
+            None
+        );
+
+        let low = rule(AlertCondition::LowBalance(Money(dec!(100))));
+        assert_eq!(
+            low.evaluate(&change(Money(dec!(150)), Money(dec!(90)))),
+            Some(Alert::LowBalance {
+                balance: Money(dec!(90)),
+                threshold: Money(dec!(100)),
+            })
+        );
+        assert_eq!(
+            low.evaluate(&change(Money(dec!(90)), Money(dec!(50)))),
+            None
+        );
+
+        let removed = AlertRule {
+            customer: 2,
+            ..large
+        };
+        assert_eq!(
+            removed.evaluate(&change(Money(dec!(1000)), Money(dec!(400)))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rate_limiter_will_cap_notifications_per_account_and_window() {
+        let limiter = RateLimiter::new(RateLimit {
+            max: 2,
+            per: Duration::from_secs(60),
+        });
+        let start = SystemTime::now();
+
+        assert!(limiter.allow(3, start));
+        assert!(limiter.allow(3, start + Duration::from_secs(10)));
+        assert!(!limiter.allow(3, start + Duration::from_secs(20)));
+        assert!(limiter.allow(4, start + Duration::from_secs(20)));
+        assert!(limiter.allow(3, start + Duration::from_secs(60)));
+        assert!(!limiter.allow(3, start + Duration::from_secs(65)));
+    }
+}
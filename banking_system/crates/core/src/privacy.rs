@@ -0,0 +1,40 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::Account,
+    audit::{AuditRecord, LedgerEntry},
+    customer::Customer,
+    interbank::ExternalPayment,
+    loan::Loan,
+    term_deposit::TermDeposit,
+};
+
+/// Everything the bank holds about one customer, as handed over on a data
+/// access request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomerDataExport {
+    pub customer: Customer,
+    pub exported_on: NaiveDate,
+    /// Accounts the customer holds in any role.
+    pub accounts: Vec<Account>,
+    /// Balance movements on those accounts, oldest first.
+    pub ledger: Vec<LedgerEntry>,
+    /// Operations the customer made or that touched their accounts.
+    pub audit: Vec<AuditRecord>,
+    pub loans: Vec<Loan>,
+    pub term_deposits: Vec<TermDeposit>,
+    /// External transfers paid from the customer's accounts.
+    pub external_payments: Vec<ExternalPayment>,
+}
+
+impl CustomerDataExport {
+    /// The archive as a single JSON document.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
@@ -0,0 +1,86 @@
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::account::Money;
+
+/// Terms offered on a kind of account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Product {
+    pub code: String,
+    pub name: String,
+    /// Annual rate as a fraction, e.g. `0.025` for 2.5%.
+    pub interest_rate: Decimal,
+    pub monthly_fee: Money,
+    pub overdraft_limit: Money,
+    /// Maximum total withdrawn per business day, if limited.
+    pub daily_withdrawal_limit: Option<Money>,
+}
+
+impl Product {
+    pub fn builder(code: &str) -> ProductBuilder {
+        ProductBuilder {
+            code: code.into(),
+            ..Default::default()
+        }
+    }
+
+    /// A month's interest on `balance`, rounded to the cent. Balances at or
+    /// below zero earn nothing.
+    pub fn monthly_interest(&self, balance: Money) -> Money {
+        if balance.0 <= Decimal::ZERO {
+            return Money::default();
+        }
+        Money(
+            (balance.0 * self.interest_rate / Decimal::from(12))
+                .round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero),
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ProductBuilder {
+    pub code: String,
+    pub name: Option<String>,
+    pub interest_rate: Option<Decimal>,
+    pub monthly_fee: Option<Money>,
+    pub overdraft_limit: Option<Money>,
+    pub daily_withdrawal_limit: Option<Money>,
+}
+
+impl ProductBuilder {
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn interest_rate(mut self, rate: Decimal) -> Self {
+        self.interest_rate = Some(rate);
+        self
+    }
+
+    pub fn monthly_fee(mut self, fee: Money) -> Self {
+        self.monthly_fee = Some(fee);
+        self
+    }
+
+    pub fn overdraft_limit(mut self, limit: Money) -> Self {
+        self.overdraft_limit = Some(limit);
+        self
+    }
+
+    pub fn daily_withdrawal_limit(mut self, limit: Money) -> Self {
+        self.daily_withdrawal_limit = Some(limit);
+        self
+    }
+
+    pub fn build(self) -> Product {
+        Product {
+            name: self.name.unwrap_or_else(|| self.code.clone()),
+            code: self.code,
+            interest_rate: self.interest_rate.unwrap_or_default(),
+            monthly_fee: self.monthly_fee.unwrap_or_default(),
+            overdraft_limit: self.overdraft_limit.unwrap_or_default(),
+            daily_withdrawal_limit: self.daily_withdrawal_limit,
+        }
+    }
+}
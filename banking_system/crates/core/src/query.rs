@@ -0,0 +1,248 @@
+use std::{fmt, str::FromStr};
+
+use crate::{
+    account::{Account, AccountId, AccountStatus, HolderRole, Money},
+    customer::CustomerId,
+};
+
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// Filters for listing accounts; unset fields match everything.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AccountQuery {
+    /// Accounts on which this customer holds [`HolderRole::Owner`], whether
+    /// as primary or joint owner.
+    pub owner: Option<CustomerId>,
+    pub status: Option<AccountStatus>,
+    /// Inclusive lower bound on the balance.
+    pub min_balance: Option<Money>,
+    /// Inclusive upper bound on the balance.
+    pub max_balance: Option<Money>,
+}
+
+impl AccountQuery {
+    pub fn owner(mut self, owner: CustomerId) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    pub fn status(mut self, status: AccountStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub fn min_balance(mut self, min: Money) -> Self {
+        self.min_balance = Some(min);
+        self
+    }
+
+    pub fn max_balance(mut self, max: Money) -> Self {
+        self.max_balance = Some(max);
+        self
+    }
+
+    pub fn matches(&self, account: &Account) -> bool {
+        self.owner
+            .is_none_or(|owner| account.role_of(owner) == Some(HolderRole::Owner))
+            && self
+                .status
+                .as_ref()
+                .is_none_or(|status| &account.status == status)
+            && self
+                .min_balance
+                .is_none_or(|min| account.balance.0 >= min.0)
+            && self
+                .max_balance
+                .is_none_or(|max| account.balance.0 <= max.0)
+    }
+}
+
+/// Opaque position in a listing. Pages are ordered by account id, so a
+/// cursor stays valid while accounts are added or changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor(AccountId);
+
+impl Cursor {
+    pub fn after(self) -> AccountId {
+        self.0
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "acc-{}", self.0)
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix("acc-")
+            .and_then(|id| id.parse().ok())
+            .map(Cursor)
+            .ok_or_else(|| format!("invalid cursor: {s}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRequest {
+    pub after: Option<Cursor>,
+    pub limit: usize,
+}
+
+impl PageRequest {
+    /// First page of up to `limit` items, clamped to `1..=MAX_PAGE_SIZE`.
+    pub fn first(limit: usize) -> Self {
+        Self {
+            after: None,
+            limit: limit.clamp(1, MAX_PAGE_SIZE),
+        }
+    }
+
+    pub fn next(cursor: Cursor, limit: usize) -> Self {
+        Self {
+            after: Some(cursor),
+            ..Self::first(limit)
+        }
+    }
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self::first(DEFAULT_PAGE_SIZE)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Where the next page starts, or `None` on the last page.
+    pub next: Option<Cursor>,
+}
+
+impl Page<Account> {
+    /// Builds a page from accounts already in id order, such as a range scan
+    /// of an ordered index from the cursor. Only the accounts kept for the
+    /// page are cloned, and the scan stops as soon as the page is full.
+    pub fn from_ordered<'a>(
+        accounts: impl IntoIterator<Item = &'a Account>,
+        query: &AccountQuery,
+        page: PageRequest,
+    ) -> Self {
+        let after = page.after.map(Cursor::after);
+        let mut items: Vec<Account> = accounts
+            .into_iter()
+            .filter(|account| after.is_none_or(|after| account.id > after))
+            .filter(|account| query.matches(account))
+            .take(page.limit + 1)
+            .cloned()
+            .collect();
+
+        let next = if items.len() > page.limit {
+            items.truncate(page.limit);
+            items.last().map(|account| Cursor(account.id))
+        } else {
+            None
+        };
+
+        Self { items, next }
+    }
+
+    /// Builds a page from every account a backend holds, so that each
+    /// backend only has to supply its accounts in any order. This sorts all
+    /// of them for every page; backends with an ordered index should use
+    /// [`Page::from_ordered`] instead.
+    pub fn from_accounts(
+        accounts: impl IntoIterator<Item = Account>,
+        query: &AccountQuery,
+        page: PageRequest,
+    ) -> Self {
+        let mut accounts: Vec<Account> = accounts.into_iter().collect();
+        accounts.sort_by_key(|account| account.id);
+        Self::from_ordered(&accounts, query, page)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{
+        account::{Account, AccountStatus, HolderRole, Money},
+        query::{AccountQuery, Cursor, Page, PageRequest},
+    };
+
+    fn accounts() -> Vec<Account> {
+        vec![
+            Account::builder(3, 1).balance(Money(300.into())).build(),
+            Account::builder(1, 1).balance(Money(100.into())).build(),
+            Account::builder(2, 2)
+                .holder(1, HolderRole::Viewer)
+                .status(AccountStatus::Frozen)
+                .build(),
+            Account::builder(4, 2)
+                .holder(1, HolderRole::Owner)
+                .balance(Money(50.into()))
+                .build(),
+        ]
+    }
+
+    #[test]
+    fn test_query_will_filter_by_owner_status_and_balance() {
+        let owner = AccountQuery::default().owner(1);
+        let owned = Page::from_accounts(accounts(), &owner, PageRequest::default());
+        let ids: Vec<_> = owned.items.iter().map(|account| account.id).collect();
+        assert_eq!(ids, vec![1, 3, 4]);
+
+        let frozen = AccountQuery::default().status(AccountStatus::Frozen);
+        let page = Page::from_accounts(accounts(), &frozen, PageRequest::default());
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].id, 2);
+
+        let range = AccountQuery::default()
+            .min_balance(Money(50.into()))
+            .max_balance(Money(100.into()));
+        let page = Page::from_accounts(accounts(), &range, PageRequest::default());
+        let ids: Vec<_> = page.items.iter().map(|account| account.id).collect();
+        assert_eq!(ids, vec![1, 4]);
+    }
+
+    #[test]
+    fn test_page_will_continue_from_cursor_in_id_order() {
+        let query = AccountQuery::default();
+        let first = Page::from_accounts(accounts(), &query, PageRequest::first(3));
+        let ids: Vec<_> = first.items.iter().map(|account| account.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        let cursor = first.next.unwrap();
+        let second = Page::from_accounts(accounts(), &query, PageRequest::next(cursor, 3));
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].id, 4);
+        assert_eq!(second.next, None);
+    }
+
+    #[test]
+    fn test_page_will_stop_reading_ordered_accounts_once_full() {
+        let accounts: Vec<Account> = (1..=1000)
+            .map(|id| Account::builder(id, 1).build())
+            .collect();
+        let mut read = 0;
+        let page = Page::from_ordered(
+            accounts.iter().inspect(|_| read += 1),
+            &AccountQuery::default(),
+            PageRequest::first(10),
+        );
+
+        assert_eq!(page.items.len(), 10);
+        assert_eq!(page.next.map(Cursor::after), Some(10));
+        assert_eq!(read, 11);
+    }
+
+    #[test]
+    fn test_cursor_will_round_trip_through_string() {
+        let cursor: Cursor = "acc-42".parse().unwrap();
+        assert_eq!(cursor.after(), 42);
+        assert_eq!(cursor.to_string(), "acc-42");
+        assert!("42".parse::<Cursor>().is_err());
+    }
+}
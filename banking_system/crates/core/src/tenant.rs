@@ -0,0 +1,114 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{account::Money, context::TellerId, product::Product};
+
+/// Identifies one of the institutions sharing a deployment. Every account
+/// and customer belongs to exactly one tenant, and repositories only ever
+/// show a tenant its own.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct TenantId(pub String);
+
+impl TenantId {
+    /// The tenant of single-tenant deployments and of data stored before
+    /// tenants existed.
+    pub const DEFAULT: &'static str = "default";
+
+    pub fn new(id: &str) -> Self {
+        TenantId(id.into())
+    }
+}
+
+impl Default for TenantId {
+    fn default() -> Self {
+        TenantId::new(Self::DEFAULT)
+    }
+}
+
+impl fmt::Display for TenantId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Caps a tenant puts on its customers; unset fields are unlimited.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TenantLimits {
+    /// Largest amount a single deposit, withdrawal or transfer may move.
+    pub max_transaction: Option<Money>,
+    /// Most accounts a customer may hold open as primary owner.
+    pub max_accounts_per_owner: Option<usize>,
+}
+
+/// A tenant and the configuration its bank runs with.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tenant {
+    pub id: TenantId,
+    pub name: String,
+    /// The products the tenant offers accounts on.
+    pub products: Vec<Product>,
+    pub limits: TenantLimits,
+    /// Tellers who may approve held debits on any account.
+    #[serde(default)]
+    pub approvers: Vec<TellerId>,
+}
+
+impl Tenant {
+    pub fn builder(id: &str) -> TenantBuilder {
+        TenantBuilder {
+            id: TenantId::new(id),
+            ..Default::default()
+        }
+    }
+
+    pub fn product(&self, code: &str) -> Option<&Product> {
+        self.products.iter().find(|product| product.code == code)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TenantBuilder {
+    pub id: TenantId,
+    pub name: Option<String>,
+    pub products: Vec<Product>,
+    pub limits: TenantLimits,
+    pub approvers: Vec<TellerId>,
+}
+
+impl TenantBuilder {
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn product(mut self, product: Product) -> Self {
+        self.products.push(product);
+        self
+    }
+
+    pub fn max_transaction(mut self, max: Money) -> Self {
+        self.limits.max_transaction = Some(max);
+        self
+    }
+
+    pub fn max_accounts_per_owner(mut self, max: usize) -> Self {
+        self.limits.max_accounts_per_owner = Some(max);
+        self
+    }
+
+    pub fn approver(mut self, teller: TellerId) -> Self {
+        self.approvers.push(teller);
+        self
+    }
+
+    pub fn build(self) -> Tenant {
+        Tenant {
+            name: self.name.unwrap_or_else(|| self.id.0.clone()),
+            id: self.id,
+            products: self.products,
+            limits: self.limits,
+            approvers: self.approvers,
+        }
+    }
+}
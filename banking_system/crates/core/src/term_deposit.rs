@@ -0,0 +1,266 @@
+use chrono::{Months, NaiveDate};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::{AccountId, Money},
+    customer::CustomerId,
+    errors::{DomainError, TermsProblem},
+};
+
+pub type TermDepositId = u64;
+
+const DAYS_PER_YEAR: u32 = 365;
+
+/// What the customer wants done with the deposit when it matures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaturityInstruction {
+    /// Pay principal and interest back into the source account.
+    Payout,
+    /// Pay the interest out and lock the principal again for the same term.
+    RolloverPrincipal,
+    /// Lock principal and interest again for the same term.
+    RolloverAll,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TermDepositTerms {
+    pub principal: Money,
+    /// Annual rate as a fraction, paid as simple interest at maturity.
+    pub annual_rate: Decimal,
+    pub term_months: u32,
+    /// Days of interest forfeited when the deposit is broken early.
+    pub penalty_days: u32,
+    pub instruction: MaturityInstruction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TermDepositStatus {
+    Active,
+    /// Paid out at maturity.
+    Matured,
+    /// Broken before maturity.
+    Withdrawn,
+    /// Replaced at maturity by the given deposit.
+    RolledOver(TermDepositId),
+}
+
+/// Funds taken from `source_account` and locked until `maturity` at a fixed
+/// rate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TermDeposit {
+    pub id: TermDepositId,
+    pub owner: CustomerId,
+    /// Where the funds came from and where payouts go.
+    pub source_account: AccountId,
+    pub terms: TermDepositTerms,
+    pub start: NaiveDate,
+    pub maturity: NaiveDate,
+    pub status: TermDepositStatus,
+}
+
+/// What maturity processing did with one deposit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaturityOutcome {
+    pub deposit_id: TermDepositId,
+    pub payout: TermDepositPayout,
+    /// Credited to the source account.
+    pub paid_out: Money,
+    /// The new deposit, if the funds were rolled over.
+    pub rolled_into: Option<TermDepositId>,
+}
+
+/// Amounts released when a deposit is closed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TermDepositPayout {
+    pub principal: Money,
+    pub interest: Money,
+    pub penalty: Money,
+}
+
+impl TermDepositPayout {
+    pub fn total(&self) -> Money {
+        Money(self.principal.0 + self.interest.0 - self.penalty.0)
+    }
+}
+
+impl TermDeposit {
+    pub fn open(
+        id: TermDepositId,
+        owner: CustomerId,
+        source_account: AccountId,
+        terms: TermDepositTerms,
+        start: NaiveDate,
+    ) -> Result<Self, DomainError> {
+        if terms.principal.0 <= Decimal::ZERO {
+            return Err(DomainError::InvalidTerms(
+                TermsProblem::PrincipalNotPositive,
+            ));
+        }
+        if terms.annual_rate < Decimal::ZERO {
+            return Err(DomainError::InvalidTerms(TermsProblem::NegativeRate));
+        }
+        if terms.term_months == 0 {
+            return Err(DomainError::InvalidTerms(TermsProblem::ZeroTerm));
+        }
+
+        let maturity = start
+            .checked_add_months(Months::new(terms.term_months))
+            .ok_or(DomainError::InvalidTerms(TermsProblem::DateOutOfRange))?;
+
+        Ok(TermDeposit {
+            id,
+            owner,
+            source_account,
+            terms,
+            start,
+            maturity,
+            status: TermDepositStatus::Active,
+        })
+    }
+
+    /// Simple interest earned from the start up to `on`, capped at maturity.
+    pub fn accrued_interest(&self, on: NaiveDate) -> Money {
+        let days = (on.min(self.maturity) - self.start).num_days().max(0);
+        Money(self.interest_for_days(days))
+    }
+
+    pub fn is_mature(&self, on: NaiveDate) -> bool {
+        on >= self.maturity
+    }
+
+    /// Breaks the deposit before maturity. The penalty forfeits
+    /// `penalty_days` of interest but never eats into the principal.
+    pub fn withdraw_early(&mut self, on: NaiveDate) -> Result<TermDepositPayout, DomainError> {
+        self.ensure_active()?;
+        if self.is_mature(on) {
+            return Err(DomainError::TermDepositMatured {
+                deposit_id: self.id,
+                maturity: self.maturity,
+            });
+        }
+
+        let interest = self.accrued_interest(on);
+        let penalty = self
+            .interest_for_days(self.terms.penalty_days.into())
+            .min(interest.0);
+
+        self.status = TermDepositStatus::Withdrawn;
+        Ok(TermDepositPayout {
+            principal: self.terms.principal,
+            interest,
+            penalty: Money(penalty),
+        })
+    }
+
+    /// Closes the deposit at maturity, returning the full principal and
+    /// interest. The caller applies the maturity instruction.
+    pub fn mature(&mut self, on: NaiveDate) -> Result<TermDepositPayout, DomainError> {
+        self.ensure_active()?;
+        if !self.is_mature(on) {
+            return Err(DomainError::TermDepositNotMature {
+                deposit_id: self.id,
+                maturity: self.maturity,
+            });
+        }
+
+        self.status = TermDepositStatus::Matured;
+        Ok(TermDepositPayout {
+            principal: self.terms.principal,
+            interest: self.accrued_interest(self.maturity),
+            penalty: Money::default(),
+        })
+    }
+
+    fn interest_for_days(&self, days: i64) -> Decimal {
+        (self.terms.principal.0 * self.terms.annual_rate * Decimal::from(days)
+            / Decimal::from(DAYS_PER_YEAR))
+        .round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+    }
+
+    fn ensure_active(&self) -> Result<(), DomainError> {
+        match self.status {
+            TermDepositStatus::Active => Ok(()),
+            _ => Err(DomainError::TermDepositStatusConflict {
+                deposit_id: self.id,
+                status: self.status.clone(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use chrono::NaiveDate;
+    use rust_decimal::dec;
+
+    use crate::{
+        account::Money,
+        errors::DomainError,
+        term_deposit::{MaturityInstruction, TermDeposit, TermDepositStatus, TermDepositTerms},
+    };
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn deposit() -> TermDeposit {
+        let terms = TermDepositTerms {
+            principal: Money(dec!(10000)),
+            annual_rate: dec!(0.0365),
+            term_months: 12,
+            penalty_days: 90,
+            instruction: MaturityInstruction::Payout,
+        };
+        TermDeposit::open(1, 7, 3, terms, date(2026, 1, 1)).unwrap()
+    }
+
+    #[test]
+    fn test_term_deposit_will_pay_full_interest_only_at_maturity() {
+        let mut deposit = deposit();
+        assert_eq!(deposit.maturity, date(2027, 1, 1));
+
+        assert_eq!(
+            deposit.mature(date(2026, 12, 31)),
+            Err(DomainError::TermDepositNotMature {
+                deposit_id: 1,
+                maturity: date(2027, 1, 1)
+            })
+        );
+
+        let payout = deposit.mature(date(2027, 1, 3)).unwrap();
+        assert_eq!(payout.interest, Money(dec!(365.00)));
+        assert_eq!(payout.total(), Money(dec!(10365.00)));
+        assert_eq!(deposit.status, TermDepositStatus::Matured);
+        assert_eq!(
+            deposit.mature(date(2027, 1, 3)),
+            Err(DomainError::TermDepositStatusConflict {
+                deposit_id: 1,
+                status: TermDepositStatus::Matured
+            })
+        );
+    }
+
+    #[test]
+    fn test_term_deposit_will_charge_penalty_on_early_withdrawal() {
+        let mut deposit = deposit();
+
+        // 200 days earned, 90 days forfeited.
+        let payout = deposit.withdraw_early(date(2026, 7, 20)).unwrap();
+        assert_eq!(payout.interest, Money(dec!(200.00)));
+        assert_eq!(payout.penalty, Money(dec!(90.00)));
+        assert_eq!(payout.total(), Money(dec!(10110.00)));
+        assert_eq!(deposit.status, TermDepositStatus::Withdrawn);
+    }
+
+    #[test]
+    fn test_term_deposit_will_never_penalize_principal() {
+        let mut deposit = deposit();
+
+        let payout = deposit.withdraw_early(date(2026, 1, 11)).unwrap();
+
+        assert_eq!(payout.interest, Money(dec!(10.00)));
+        assert_eq!(payout.penalty, Money(dec!(10.00)));
+        assert_eq!(payout.total(), Money(dec!(10000)));
+    }
+}
@@ -0,0 +1,208 @@
+//! Compares the sharded, per-account-locked `InMemoryRepo` against the
+//! original single `RwLock<HashMap>` store on deposit, withdraw and transfer
+//! workloads, both single-threaded and with several threads at once.
+
+use std::{
+    collections::HashMap,
+    hint::black_box,
+    sync::{LazyLock, RwLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+use bank_core::{
+    account::{Account, AccountId, AccountRepository, Money, ensure_tenant},
+    errors::RepoError,
+    query::{AccountQuery, Page, PageRequest},
+    tenant::TenantId,
+};
+use bank_infra::storage::InMemoryRepo;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rust_decimal::Decimal;
+
+const ACCOUNTS: u64 = 1_000;
+const OPS_PER_THREAD: u64 = 1_000;
+
+static TENANT: LazyLock<TenantId> = LazyLock::new(TenantId::default);
+
+/// The store as it was before sharding: every write blocks every reader.
+#[derive(Default)]
+struct GlobalLockRepo {
+    store: RwLock<HashMap<AccountId, Account>>,
+}
+
+impl AccountRepository for GlobalLockRepo {
+    fn next_id(&self) -> Result<AccountId, RepoError> {
+        let store = self.store.read().map_err(|_| RepoError::LockPoisened)?;
+        Ok(store.keys().max().map_or(1, |id| id + 1))
+    }
+
+    fn create(&self, tenant: &TenantId, account: Account) -> Result<(), RepoError> {
+        let mut store = self.store.write().map_err(|_| RepoError::LockPoisened)?;
+        ensure_tenant(tenant, &account, store.get(&account.id))?;
+        store.insert(account.id, account);
+        Ok(())
+    }
+
+    fn get(&self, tenant: &TenantId, id: AccountId) -> Result<Option<Account>, RepoError> {
+        let store = self.store.read().map_err(|_| RepoError::LockPoisened)?;
+        Ok(store
+            .get(&id)
+            .filter(|account| account.tenant == *tenant)
+            .cloned())
+    }
+
+    fn update(&self, tenant: &TenantId, account: Account) -> Result<(), RepoError> {
+        self.create(tenant, account)
+    }
+
+    fn list(
+        &self,
+        tenant: &TenantId,
+        query: &AccountQuery,
+        page: PageRequest,
+    ) -> Result<Page<Account>, RepoError> {
+        let store = self.store.read().map_err(|_| RepoError::LockPoisened)?;
+        let accounts = store.values().filter(|account| account.tenant == *tenant);
+        Ok(Page::from_accounts(accounts.cloned(), query, page))
+    }
+
+    fn modify<T, E>(
+        &self,
+        tenant: &TenantId,
+        ids: &[AccountId],
+        f: impl FnOnce(&mut [Account]) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        E: From<RepoError>,
+    {
+        let mut store = self.store.write().map_err(|_| RepoError::LockPoisened)?;
+        let mut accounts = ids
+            .iter()
+            .map(|id| {
+                let account = store.get(id).filter(|account| account.tenant == *tenant);
+                account.cloned().ok_or(RepoError::NotFound(*id))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let value = f(&mut accounts)?;
+        for account in accounts {
+            store.insert(account.id, account);
+        }
+        Ok(value)
+    }
+}
+
+fn seed<R: AccountRepository + Default>() -> R {
+    let repo = R::default();
+    for id in 0..ACCOUNTS {
+        let account = Account::builder(id, id)
+            .balance(Money(Decimal::from(1_000_000)))
+            .build();
+        repo.create(&TENANT, account).unwrap();
+    }
+    repo
+}
+
+fn deposit<R: AccountRepository>(repo: &R, id: AccountId) {
+    let result: Result<Money, RepoError> = repo.modify(&TENANT, &[id], |accounts| {
+        Ok(accounts[0].deposit(Money(Decimal::ONE)).unwrap())
+    });
+    black_box(result.unwrap());
+}
+
+fn withdraw<R: AccountRepository>(repo: &R, id: AccountId) {
+    let result: Result<Money, RepoError> = repo.modify(&TENANT, &[id], |accounts| {
+        Ok(accounts[0].withdraw(Money(Decimal::ONE)).unwrap())
+    });
+    black_box(result.unwrap());
+}
+
+fn transfer<R: AccountRepository>(repo: &R, from: AccountId, to: AccountId) {
+    let result: Result<(), RepoError> = repo.modify(&TENANT, &[from, to], |accounts| {
+        accounts[0].withdraw(Money(Decimal::ONE)).unwrap();
+        accounts[1].deposit(Money(Decimal::ONE)).unwrap();
+        Ok(())
+    });
+    result.unwrap();
+}
+
+fn single_threaded<R: AccountRepository + Default>(c: &mut Criterion, name: &str) {
+    let repo: R = seed();
+    let mut group = c.benchmark_group(format!("single_thread/{name}"));
+    let mut id = 0;
+
+    group.bench_function("deposit", |b| {
+        b.iter(|| {
+            id = (id + 1) % ACCOUNTS;
+            deposit(&repo, id)
+        })
+    });
+    group.bench_function("withdraw", |b| {
+        b.iter(|| {
+            id = (id + 1) % ACCOUNTS;
+            withdraw(&repo, id)
+        })
+    });
+    group.bench_function("transfer", |b| {
+        b.iter(|| {
+            id = (id + 1) % ACCOUNTS;
+            transfer(&repo, id, (id + 7) % ACCOUNTS)
+        })
+    });
+
+    group.finish();
+}
+
+/// Each thread works on its own stripe of accounts, mixing reads with
+/// deposits, withdrawals and transfers into the neighbouring stripe.
+fn mixed_workload<R: AccountRepository + Sync>(repo: &R, threads: u64) -> Duration {
+    let start = Instant::now();
+
+    thread::scope(|scope| {
+        for thread_index in 0..threads {
+            scope.spawn(move || {
+                for op in 0..OPS_PER_THREAD {
+                    let id = (thread_index + op * threads) % ACCOUNTS;
+                    match op % 4 {
+                        0 => deposit(repo, id),
+                        1 => withdraw(repo, id),
+                        2 => transfer(repo, id, (id + 1) % ACCOUNTS),
+                        _ => {
+                            black_box(repo.get(&TENANT, id).unwrap());
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    start.elapsed()
+}
+
+fn multi_threaded<R: AccountRepository + Default + Sync>(c: &mut Criterion, name: &str) {
+    let repo: R = seed();
+    let mut group = c.benchmark_group(format!("multi_thread/{name}"));
+
+    for threads in [1, 2, 4, 8] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(threads),
+            &threads,
+            |b, &threads| {
+                b.iter_custom(|iters| (0..iters).map(|_| mixed_workload(&repo, threads)).sum())
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn benches(c: &mut Criterion) {
+    single_threaded::<GlobalLockRepo>(c, "global_lock");
+    single_threaded::<InMemoryRepo>(c, "sharded");
+    multi_threaded::<GlobalLockRepo>(c, "global_lock");
+    multi_threaded::<InMemoryRepo>(c, "sharded");
+}
+
+criterion_group!(repo, benches);
+criterion_main!(repo);
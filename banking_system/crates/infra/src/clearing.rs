@@ -0,0 +1,233 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use bank_core::{
+    clock::Clock,
+    errors::ClearingError,
+    interbank::{ClearingEvent, ClearingGateway, ClearingOutcome, OutgoingPayment},
+};
+use chrono::{Days, NaiveDate};
+
+/// An in-process clearing house for tests. Payments settle a fixed number of
+/// days after submission by the injected clock; payments to accounts
+/// registered with [`FakeClearingHouse::reject_account`] are refused, and
+/// those registered with [`FakeClearingHouse::return_account`] settle and
+/// then come back.
+pub struct FakeClearingHouse {
+    clock: Arc<dyn Clock>,
+    settlement_days: u64,
+    state: Mutex<ClearingState>,
+}
+
+#[derive(Default)]
+struct ClearingState {
+    unavailable: bool,
+    /// Refusal reason per creditor account number.
+    rejections: HashMap<String, String>,
+    /// Days after settlement and reason, per creditor account number.
+    returns: HashMap<String, (u64, String)>,
+    submitted: Vec<OutgoingPayment>,
+    in_flight: Vec<InFlight>,
+}
+
+struct InFlight {
+    end_to_end_id: String,
+    due: NaiveDate,
+    settled: bool,
+    /// Days after settlement and reason, if the payment will come back.
+    returned: Option<(u64, String)>,
+}
+
+impl FakeClearingHouse {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            settlement_days: 0,
+            state: Mutex::new(ClearingState::default()),
+        }
+    }
+
+    /// Days between submission and settlement; same-day by default.
+    pub fn settlement_days(mut self, days: u64) -> Self {
+        self.settlement_days = days;
+        self
+    }
+
+    /// Refuses payments to `account` when they are submitted.
+    pub fn reject_account(&self, account: &str, reason: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.rejections.insert(account.into(), reason.into());
+    }
+
+    /// Settles payments to `account` and returns them `after_days` later.
+    pub fn return_account(&self, account: &str, after_days: u64, reason: &str) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .returns
+            .insert(account.into(), (after_days, reason.into()));
+    }
+
+    /// Simulates an outage: submissions and polls fail until it is lifted.
+    pub fn set_available(&self, available: bool) {
+        self.state.lock().unwrap().unavailable = !available;
+    }
+
+    /// Every payment accepted so far.
+    pub fn submitted(&self) -> Vec<OutgoingPayment> {
+        self.state.lock().unwrap().submitted.clone()
+    }
+}
+
+impl ClearingGateway for FakeClearingHouse {
+    fn submit(&self, payment: &OutgoingPayment) -> Result<(), ClearingError> {
+        let mut state = self.state.lock().unwrap();
+        if state.unavailable {
+            return Err(ClearingError::Unavailable("clearing house is down".into()));
+        }
+
+        let creditor = payment.transfer.creditor_account.as_str();
+        if let Some(reason) = state.rejections.get(creditor) {
+            return Err(ClearingError::Rejected(reason.clone()));
+        }
+
+        let returned = state.returns.get(creditor).cloned();
+        state.in_flight.push(InFlight {
+            end_to_end_id: payment.end_to_end_id.clone(),
+            due: self.clock.today() + Days::new(self.settlement_days),
+            settled: false,
+            returned,
+        });
+        state.submitted.push(payment.clone());
+        Ok(())
+    }
+
+    fn poll(&self) -> Result<Vec<ClearingEvent>, ClearingError> {
+        let mut state = self.state.lock().unwrap();
+        if state.unavailable {
+            return Err(ClearingError::Unavailable("clearing house is down".into()));
+        }
+
+        let today = self.clock.today();
+        let mut events = Vec::new();
+        state.in_flight.retain_mut(|payment| {
+            if payment.due > today {
+                return true;
+            }
+
+            if !payment.settled {
+                events.push(ClearingEvent {
+                    end_to_end_id: payment.end_to_end_id.clone(),
+                    outcome: ClearingOutcome::Settled,
+                });
+                payment.settled = true;
+                match &payment.returned {
+                    Some((after_days, _)) => payment.due = payment.due + Days::new(*after_days),
+                    None => return false,
+                }
+                if payment.due > today {
+                    return true;
+                }
+            }
+
+            let (_, reason) = payment.returned.take().unwrap();
+            events.push(ClearingEvent {
+                end_to_end_id: payment.end_to_end_id.clone(),
+                outcome: ClearingOutcome::Returned(reason),
+            });
+            false
+        });
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use bank_core::{
+        account::Money,
+        clock::FixedClock,
+        errors::ClearingError,
+        interbank::{ClearingGateway, ClearingOutcome, ExternalTransfer, OutgoingPayment},
+    };
+    use chrono::NaiveDate;
+    use rust_decimal::dec;
+
+    use crate::clearing::FakeClearingHouse;
+
+    fn payment(id: &str, creditor: &str) -> OutgoingPayment {
+        OutgoingPayment {
+            end_to_end_id: id.into(),
+            debtor_account: 1,
+            debtor_name: "Ada Lovelace".into(),
+            debtor_number: "GB82WEST12345698765432".parse().unwrap(),
+            transfer: ExternalTransfer {
+                creditor_name: "Charles Babbage".into(),
+                creditor_account: creditor.parse().unwrap(),
+                creditor_agent: None,
+                amount: Money(dec!(10)),
+                remittance: None,
+            },
+            requested_execution: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_clearing_house_will_settle_after_delay_then_return() {
+        let clock = Arc::new(FixedClock::new(
+            NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+        ));
+        let house = FakeClearingHouse::new(clock.clone()).settlement_days(1);
+        house.return_account("GB33BUKB20201555555555", 2, "AC04");
+
+        house
+            .submit(&payment("E2E1", "DE89370400440532013000"))
+            .unwrap();
+        house
+            .submit(&payment("E2E2", "GB33BUKB20201555555555"))
+            .unwrap();
+        assert!(house.poll().unwrap().is_empty());
+
+        clock.advance_days(1);
+        let events = house.poll().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(
+            events
+                .iter()
+                .all(|event| event.outcome == ClearingOutcome::Settled)
+        );
+
+        clock.advance_days(1);
+        assert!(house.poll().unwrap().is_empty());
+        clock.advance_days(1);
+        let events = house.poll().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].end_to_end_id, "E2E2");
+        assert_eq!(events[0].outcome, ClearingOutcome::Returned("AC04".into()));
+        assert!(house.poll().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_clearing_house_will_refuse_rejected_accounts_and_outages() {
+        let clock = Arc::new(FixedClock::new(
+            NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+        ));
+        let house = FakeClearingHouse::new(clock);
+        house.reject_account("DE89370400440532013000", "AC01");
+
+        assert_eq!(
+            house.submit(&payment("E2E1", "DE89370400440532013000")),
+            Err(ClearingError::Rejected("AC01".into()))
+        );
+
+        house.set_available(false);
+        assert!(matches!(
+            house.submit(&payment("E2E2", "GB33BUKB20201555555555")),
+            Err(ClearingError::Unavailable(_))
+        ));
+        assert!(house.poll().is_err());
+        assert!(house.submitted().is_empty());
+    }
+}
@@ -0,0 +1,352 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    ops::Bound,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+use bank_core::{
+    account::{Account, AccountId, AccountRepository, ensure_tenant},
+    account_number::AccountNumber,
+    customer::CustomerId,
+    errors::RepoError,
+    query::{AccountQuery, Page, PageRequest},
+    tenant::TenantId,
+};
+use serde::{Deserialize, Serialize};
+
+/// Everything the repository persists, including the id sequence, so that
+/// ids keep increasing across restarts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FileState {
+    last_id: AccountId,
+    accounts: BTreeMap<AccountId, Account>,
+    /// Account ids by tenant and external number, rebuilt on open.
+    #[serde(skip)]
+    numbers: HashMap<(TenantId, AccountNumber), AccountId>,
+    /// Account ids by tenant and primary owner, rebuilt on open.
+    #[serde(skip)]
+    owners: HashMap<(TenantId, CustomerId), BTreeSet<AccountId>>,
+}
+
+impl FileState {
+    fn index(&mut self, account: &Account) {
+        if let Some(number) = &account.number {
+            self.numbers
+                .insert((account.tenant.clone(), number.clone()), account.id);
+        }
+        self.owners
+            .entry((account.tenant.clone(), account.owner))
+            .or_default()
+            .insert(account.id);
+    }
+}
+
+/// Keeps accounts in a JSON file, rewritten atomically after every change.
+/// Suited to small deployments and test environments rather than volume.
+pub struct FileRepo {
+    path: PathBuf,
+    state: Mutex<FileState>,
+}
+
+impl FileRepo {
+    /// Opens the repository at `path`, starting empty if the file does not
+    /// exist yet.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, RepoError> {
+        let path = path.as_ref().to_path_buf();
+        let mut state: FileState = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(storage_error)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => FileState::default(),
+            Err(err) => return Err(storage_error(err)),
+        };
+        let accounts = std::mem::take(&mut state.accounts);
+        for account in accounts.values() {
+            state.index(account);
+        }
+        state.accounts = accounts;
+
+        Ok(Self {
+            path,
+            state: Mutex::new(state),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, FileState>, RepoError> {
+        self.state.lock().map_err(|_| RepoError::LockPoisened)
+    }
+
+    /// Writes to a sibling temporary file and renames it over the original,
+    /// so a crash mid-write never leaves a truncated file behind.
+    fn persist(&self, state: &FileState) -> Result<(), RepoError> {
+        let bytes = serde_json::to_vec_pretty(state).map_err(storage_error)?;
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, bytes).map_err(storage_error)?;
+        fs::rename(&tmp, &self.path).map_err(storage_error)
+    }
+
+    /// Stores `accounts`, restoring the previous versions if they cannot be
+    /// persisted.
+    fn store(&self, state: &mut FileState, accounts: Vec<Account>) -> Result<(), RepoError> {
+        let previous: Vec<(AccountId, Option<Account>)> = accounts
+            .into_iter()
+            .map(|account| {
+                state.last_id = state.last_id.max(account.id);
+                state.index(&account);
+                (account.id, state.accounts.insert(account.id, account))
+            })
+            .collect();
+
+        self.persist(state).inspect_err(|_| {
+            for (id, account) in previous {
+                match account {
+                    Some(account) => state.accounts.insert(id, account),
+                    None => state.accounts.remove(&id),
+                };
+            }
+        })
+    }
+}
+
+fn storage_error(err: impl std::fmt::Display) -> RepoError {
+    RepoError::Storage(err.to_string())
+}
+
+impl AccountRepository for FileRepo {
+    fn next_id(&self) -> Result<AccountId, RepoError> {
+        let mut state = self.lock()?;
+        state.last_id += 1;
+        self.persist(&state).inspect_err(|_| state.last_id -= 1)?;
+        Ok(state.last_id)
+    }
+    fn create(&self, tenant: &TenantId, account: Account) -> Result<(), RepoError> {
+        let mut state = self.lock()?;
+        ensure_tenant(tenant, &account, state.accounts.get(&account.id))?;
+        self.store(&mut state, vec![account])
+    }
+    fn get(&self, tenant: &TenantId, id: AccountId) -> Result<Option<Account>, RepoError> {
+        let state = self.lock()?;
+        Ok(state
+            .accounts
+            .get(&id)
+            .filter(|account| account.tenant == *tenant)
+            .cloned())
+    }
+    fn update(&self, tenant: &TenantId, account: Account) -> Result<(), RepoError> {
+        self.create(tenant, account)
+    }
+    fn list(
+        &self,
+        tenant: &TenantId,
+        query: &AccountQuery,
+        page: PageRequest,
+    ) -> Result<Page<Account>, RepoError> {
+        let state = self.lock()?;
+        let start = match page.after {
+            Some(cursor) => Bound::Excluded(cursor.after()),
+            None => Bound::Unbounded,
+        };
+        let accounts = state
+            .accounts
+            .range((start, Bound::Unbounded))
+            .map(|(_, account)| account)
+            .filter(|account| account.tenant == *tenant);
+        Ok(Page::from_ordered(accounts, query, page))
+    }
+
+    fn get_by_number(
+        &self,
+        tenant: &TenantId,
+        number: &AccountNumber,
+    ) -> Result<Option<Account>, RepoError> {
+        let state = self.lock()?;
+        let id = state.numbers.get(&(tenant.clone(), number.clone()));
+        Ok(id
+            .and_then(|id| state.accounts.get(id))
+            .filter(|account| account.tenant == *tenant && account.number.as_ref() == Some(number))
+            .cloned())
+    }
+
+    fn create_checked<E>(
+        &self,
+        tenant: &TenantId,
+        account: Account,
+        check: impl FnOnce(&[Account]) -> Result<(), E>,
+    ) -> Result<(), E>
+    where
+        E: From<RepoError>,
+    {
+        let mut state = self.lock()?;
+        ensure_tenant(tenant, &account, state.accounts.get(&account.id))?;
+        let owned: Vec<Account> = state
+            .owners
+            .get(&(tenant.clone(), account.owner))
+            .into_iter()
+            .flatten()
+            .filter_map(|id| state.accounts.get(id))
+            .cloned()
+            .collect();
+
+        check(&owned)?;
+        Ok(self.store(&mut state, vec![account])?)
+    }
+
+    fn modify<T, E>(
+        &self,
+        tenant: &TenantId,
+        ids: &[AccountId],
+        f: impl FnOnce(&mut [Account]) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        E: From<RepoError>,
+    {
+        let mut state = self.lock()?;
+
+        for (index, id) in ids.iter().enumerate() {
+            if ids[..index].contains(id) {
+                return Err(RepoError::DuplicateId(*id).into());
+            }
+        }
+
+        let mut accounts = ids
+            .iter()
+            .map(|id| {
+                let account = state
+                    .accounts
+                    .get(id)
+                    .filter(|account| account.tenant == *tenant);
+                account.cloned().ok_or(RepoError::NotFound(*id))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let value = f(&mut accounts)?;
+        for account in &accounts {
+            ensure_tenant(tenant, account, None)?;
+        }
+        self.store(&mut state, accounts)?;
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{fs, path::PathBuf};
+
+    use bank_core::{
+        account::{Account, AccountRepository, Money},
+        account_number::AccountNumbering,
+        errors::RepoError,
+        tenant::TenantId,
+    };
+
+    use crate::file::FileRepo;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("bank-{name}-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_file_repo_will_persist_accounts_and_sequence_across_reopen() {
+        let path = temp_path("reopen");
+        let tenant = TenantId::default();
+
+        let repo = FileRepo::open(&path).unwrap();
+        let first = repo.next_id().unwrap();
+        let account = Account::builder(first, 1).balance(Money(10.into())).build();
+        repo.create(&tenant, account).unwrap();
+        let second = repo.next_id().unwrap();
+        drop(repo);
+
+        let repo = FileRepo::open(&path).unwrap();
+        assert_eq!(
+            repo.get(&tenant, first).unwrap().unwrap().balance,
+            Money(10.into())
+        );
+        let third = repo.next_id().unwrap();
+
+        assert_eq!((first, second, third), (1, 2, 3));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_repo_will_not_reuse_ids_of_accounts_created_directly() {
+        let path = temp_path("direct");
+
+        let repo = FileRepo::open(&path).unwrap();
+        repo.create(&TenantId::default(), Account::builder(41, 1).build())
+            .unwrap();
+        drop(repo);
+
+        let repo = FileRepo::open(&path).unwrap();
+        assert_eq!(repo.next_id().unwrap(), 42);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_repo_will_find_accounts_by_number_after_reopen() {
+        let path = temp_path("numbers");
+        let tenant = TenantId::default();
+        let number = AccountNumbering::new("GB", "NWBK")
+            .unwrap()
+            .issue(5)
+            .unwrap();
+
+        let repo = FileRepo::open(&path).unwrap();
+        let account = Account::builder(5, 1).number(number.clone()).build();
+        repo.create(&tenant, account.clone()).unwrap();
+        assert_eq!(
+            repo.get_by_number(&tenant, &number).unwrap(),
+            Some(account.clone())
+        );
+        drop(repo);
+
+        let repo = FileRepo::open(&path).unwrap();
+        assert_eq!(repo.get_by_number(&tenant, &number).unwrap(), Some(account));
+        assert_eq!(
+            repo.get_by_number(&TenantId::new("acme"), &number).unwrap(),
+            None
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_repo_will_reject_corrupt_file() {
+        let path = temp_path("corrupt");
+        fs::write(&path, "not json").unwrap();
+
+        assert!(FileRepo::open(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_repo_will_check_new_accounts_against_owner_accounts_after_reopen() {
+        let path = temp_path("owners");
+        let tenant = TenantId::default();
+
+        let repo = FileRepo::open(&path).unwrap();
+        repo.create(&tenant, Account::builder(1, 7).build())
+            .unwrap();
+        repo.create(&tenant, Account::builder(2, 8).build())
+            .unwrap();
+        drop(repo);
+
+        let repo = FileRepo::open(&path).unwrap();
+        let mut seen = Vec::new();
+        repo.create_checked(&tenant, Account::builder(3, 7).build(), |owned| {
+            seen = owned.iter().map(|account| account.id).collect();
+            Err(RepoError::Storage("limit".into()))
+        })
+        .unwrap_err();
+
+        assert_eq!(seen, vec![1]);
+        assert_eq!(repo.get(&tenant, 3).unwrap(), None);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+use std::{sync::Arc, time::Instant};
+
+use bank_core::{
+    account::{Account, AccountId, AccountRepository},
+    account_number::AccountNumber,
+    errors::RepoError,
+    query::{AccountQuery, Page, PageRequest},
+    tenant::TenantId,
+};
+use tracing::{debug_span, field};
+
+use crate::metrics::{Metrics, outcome};
+
+/// Wraps a repository so every call runs in a `repo.*` tracing span and is
+/// counted and timed in [`Metrics`].
+pub struct InstrumentedRepo<R> {
+    inner: R,
+    metrics: Arc<Metrics>,
+}
+
+impl<R: AccountRepository> InstrumentedRepo<R> {
+    pub fn new(inner: R, metrics: Arc<Metrics>) -> Self {
+        Self { inner, metrics }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn metrics(&self) -> &Arc<Metrics> {
+        &self.metrics
+    }
+
+    fn observe<T, E>(
+        &self,
+        call: &str,
+        span: tracing::Span,
+        f: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        let _entered = span.enter();
+        let started = Instant::now();
+
+        let result = f();
+
+        let outcome = outcome(&result);
+        span.record("outcome", outcome);
+        self.metrics
+            .record_repo_call(call, outcome, started.elapsed());
+        result
+    }
+}
+
+impl<R: AccountRepository> AccountRepository for InstrumentedRepo<R> {
+    fn next_id(&self) -> Result<AccountId, RepoError> {
+        let span = debug_span!("repo.next_id", outcome = field::Empty);
+        self.observe("next_id", span, || self.inner.next_id())
+    }
+    fn create(&self, tenant: &TenantId, account: Account) -> Result<(), RepoError> {
+        let span = debug_span!(
+            "repo.create",
+            account_id = account.id,
+            outcome = field::Empty
+        );
+        self.observe("create", span, || self.inner.create(tenant, account))
+    }
+    fn get(&self, tenant: &TenantId, id: AccountId) -> Result<Option<Account>, RepoError> {
+        let span = debug_span!("repo.get", account_id = id, outcome = field::Empty);
+        self.observe("get", span, || self.inner.get(tenant, id))
+    }
+    fn update(&self, tenant: &TenantId, account: Account) -> Result<(), RepoError> {
+        let span = debug_span!(
+            "repo.update",
+            account_id = account.id,
+            outcome = field::Empty
+        );
+        self.observe("update", span, || self.inner.update(tenant, account))
+    }
+    fn list(
+        &self,
+        tenant: &TenantId,
+        query: &AccountQuery,
+        page: PageRequest,
+    ) -> Result<Page<Account>, RepoError> {
+        let span = debug_span!("repo.list", limit = page.limit, outcome = field::Empty);
+        self.observe("list", span, || self.inner.list(tenant, query, page))
+    }
+
+    fn get_by_number(
+        &self,
+        tenant: &TenantId,
+        number: &AccountNumber,
+    ) -> Result<Option<Account>, RepoError> {
+        let span = debug_span!(
+            "repo.get_by_number",
+            account_number = number.as_str(),
+            outcome = field::Empty
+        );
+        self.observe("get_by_number", span, || {
+            self.inner.get_by_number(tenant, number)
+        })
+    }
+
+    fn create_checked<E>(
+        &self,
+        tenant: &TenantId,
+        account: Account,
+        check: impl FnOnce(&[Account]) -> Result<(), E>,
+    ) -> Result<(), E>
+    where
+        E: From<RepoError>,
+    {
+        let span = debug_span!(
+            "repo.create_checked",
+            account_id = account.id,
+            outcome = field::Empty
+        );
+        self.observe("create_checked", span, || {
+            self.inner.create_checked(tenant, account, check)
+        })
+    }
+
+    fn modify<T, E>(
+        &self,
+        tenant: &TenantId,
+        ids: &[AccountId],
+        f: impl FnOnce(&mut [Account]) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        E: From<RepoError>,
+    {
+        let span = debug_span!("repo.modify", account_ids = ?ids, outcome = field::Empty);
+        self.observe("modify", span, || self.inner.modify(tenant, ids, f))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use bank_core::{
+        account::{Account, AccountRepository},
+        errors::RepoError,
+        tenant::TenantId,
+    };
+
+    use crate::{instrumented::InstrumentedRepo, metrics::Metrics, storage::InMemoryRepo};
+
+    #[test]
+    fn test_instrumented_repo_will_count_calls_by_outcome() {
+        let metrics = Arc::new(Metrics::new());
+        let repo = InstrumentedRepo::new(InMemoryRepo::new(), Arc::clone(&metrics));
+
+        let tenant = TenantId::default();
+
+        repo.create(&tenant, Account::builder(1, 1).build())
+            .unwrap();
+        repo.get(&tenant, 1).unwrap();
+        repo.modify(&tenant, &[1], |_| Ok::<_, RepoError>(()))
+            .unwrap();
+        repo.modify(&tenant, &[2], |_| Ok::<_, RepoError>(()))
+            .unwrap_err();
+
+        assert_eq!(metrics.repo_call_count("create", "ok"), 1);
+        assert_eq!(metrics.repo_call_count("get", "ok"), 1);
+        assert_eq!(metrics.repo_call_count("modify", "ok"), 1);
+        assert_eq!(metrics.repo_call_count("modify", "error"), 1);
+        assert!(
+            metrics
+                .render()
+                .contains(r#"bank_repository_call_duration_seconds_count{call="modify"} 2"#)
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use bank_core::{account::Money, interbank::IncomingPayment};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::iso20022::{
+    CashAccount, CurrencyAmount, DateChoice, Iso20022Error, PartyIdentification,
+    RemittanceInformation, Validator, from_xml, to_xml,
+};
+
+pub const NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:camt.053.001.08";
+
+/// A bank-to-customer statement (camt.053.001.08) sent to us by a
+/// correspondent, listing what was booked on our account with them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "Document")]
+pub struct Camt053Document {
+    #[serde(rename = "@xmlns")]
+    pub xmlns: String,
+    #[serde(rename = "BkToCstmrStmt")]
+    pub statement: BankToCustomerStatement,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BankToCustomerStatement {
+    #[serde(rename = "GrpHdr")]
+    pub header: GroupHeader,
+    #[serde(rename = "Stmt", default)]
+    pub statements: Vec<Statement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupHeader {
+    #[serde(rename = "MsgId")]
+    pub message_id: String,
+    #[serde(rename = "CreDtTm")]
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Statement {
+    #[serde(rename = "Id")]
+    pub id: String,
+    #[serde(rename = "CreDtTm")]
+    pub created_at: String,
+    #[serde(rename = "Acct")]
+    pub account: CashAccount,
+    #[serde(rename = "Ntry", default)]
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    #[serde(rename = "Amt")]
+    pub amount: CurrencyAmount,
+    /// `CRDT` or `DBIT`.
+    #[serde(rename = "CdtDbtInd")]
+    pub credit_debit: String,
+    #[serde(rename = "Sts")]
+    pub status: EntryStatus,
+    #[serde(rename = "BookgDt", skip_serializing_if = "Option::is_none")]
+    pub booking_date: Option<DateChoice>,
+    #[serde(rename = "ValDt", skip_serializing_if = "Option::is_none")]
+    pub value_date: Option<DateChoice>,
+    #[serde(rename = "AcctSvcrRef", skip_serializing_if = "Option::is_none")]
+    pub servicer_reference: Option<String>,
+    #[serde(rename = "NtryDtls", skip_serializing_if = "Option::is_none")]
+    pub details: Option<EntryDetails>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryStatus {
+    /// `BOOK`, `PDNG` or `INFO`.
+    #[serde(rename = "Cd")]
+    pub code: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryDetails {
+    #[serde(rename = "TxDtls", default)]
+    pub transactions: Vec<TransactionDetails>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionDetails {
+    #[serde(rename = "Refs", skip_serializing_if = "Option::is_none")]
+    pub references: Option<TransactionReferences>,
+    #[serde(rename = "RltdPties", skip_serializing_if = "Option::is_none")]
+    pub parties: Option<RelatedParties>,
+    #[serde(rename = "RmtInf", skip_serializing_if = "Option::is_none")]
+    pub remittance: Option<RemittanceInformation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionReferences {
+    #[serde(rename = "EndToEndId", skip_serializing_if = "Option::is_none")]
+    pub end_to_end_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedParties {
+    #[serde(rename = "Dbtr", skip_serializing_if = "Option::is_none")]
+    pub debtor: Option<Party>,
+    #[serde(rename = "CdtrAcct", skip_serializing_if = "Option::is_none")]
+    pub creditor_account: Option<CashAccount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Party {
+    #[serde(rename = "Pty")]
+    pub party: PartyIdentification,
+}
+
+impl Entry {
+    fn is_booked_credit(&self) -> bool {
+        self.credit_debit == "CRDT" && self.status.code == "BOOK"
+    }
+
+    fn transaction(&self) -> Option<&TransactionDetails> {
+        self.details.as_ref()?.transactions.first()
+    }
+
+    fn creditor_iban(&self) -> Option<&str> {
+        let account = self
+            .transaction()?
+            .parties
+            .as_ref()?
+            .creditor_account
+            .as_ref()?;
+        Some(&account.id.iban)
+    }
+}
+
+impl Camt053Document {
+    /// Parses and validates a camt.053 file.
+    pub fn from_xml(xml: &str) -> Result<Self, Iso20022Error> {
+        let document: Self = from_xml(xml)?;
+        document.validate()?;
+        Ok(document)
+    }
+
+    /// Validates and serializes the document.
+    pub fn to_xml(&self) -> Result<String, Iso20022Error> {
+        self.validate()?;
+        to_xml(self)
+    }
+
+    /// Schema checks, plus what importing needs from booked credits: each
+    /// must carry a servicer reference and exactly one transaction naming
+    /// the credited account.
+    pub fn validate(&self) -> Result<(), Iso20022Error> {
+        let mut v = Validator::default();
+        v.namespace(&self.xmlns, NAMESPACE);
+
+        let header = &self.statement.header;
+        v.text("BkToCstmrStmt/GrpHdr/MsgId", &header.message_id, 35);
+        v.date_time("BkToCstmrStmt/GrpHdr/CreDtTm", &header.created_at);
+
+        if self.statement.statements.is_empty() {
+            v.error("BkToCstmrStmt/Stmt", "at least one is required");
+        }
+
+        for (index, statement) in self.statement.statements.iter().enumerate() {
+            let path = format!("BkToCstmrStmt/Stmt[{}]", index + 1);
+            v.text(&format!("{path}/Id"), &statement.id, 35);
+            v.date_time(&format!("{path}/CreDtTm"), &statement.created_at);
+            v.iban(&format!("{path}/Acct/Id/IBAN"), &statement.account.id.iban);
+
+            for (index, entry) in statement.entries.iter().enumerate() {
+                let path = format!("{path}/Ntry[{}]", index + 1);
+                v.amount(&format!("{path}/Amt"), &entry.amount);
+                if !matches!(entry.credit_debit.as_str(), "CRDT" | "DBIT") {
+                    let found = &entry.credit_debit;
+                    v.error(
+                        &format!("{path}/CdtDbtInd"),
+                        format!("{found:?} is not CRDT or DBIT"),
+                    );
+                }
+                if !matches!(entry.status.code.as_str(), "BOOK" | "PDNG" | "INFO") {
+                    let found = &entry.status.code;
+                    v.error(
+                        &format!("{path}/Sts/Cd"),
+                        format!("{found:?} is not an entry status"),
+                    );
+                }
+                if let Some(date) = &entry.booking_date {
+                    v.date(&format!("{path}/BookgDt/Dt"), &date.date);
+                }
+                if let Some(date) = &entry.value_date {
+                    v.date(&format!("{path}/ValDt/Dt"), &date.date);
+                }
+                v.optional_text(
+                    &format!("{path}/AcctSvcrRef"),
+                    entry.servicer_reference.as_deref(),
+                    35,
+                );
+
+                if !entry.is_booked_credit() {
+                    continue;
+                }
+                if entry.booking_date.is_none() {
+                    v.error(&format!("{path}/BookgDt"), "is required on booked entries");
+                }
+                if entry.servicer_reference.is_none() {
+                    v.error(
+                        &format!("{path}/AcctSvcrRef"),
+                        "is required on booked credits",
+                    );
+                }
+                let transactions = entry.details.as_ref().map_or(0, |d| d.transactions.len());
+                if transactions != 1 {
+                    let path = format!("{path}/NtryDtls/TxDtls");
+                    v.error(
+                        &path,
+                        format!("expected one transaction, found {transactions}"),
+                    );
+                }
+                match entry.creditor_iban() {
+                    Some(iban) => {
+                        v.iban(
+                            &format!("{path}/NtryDtls/TxDtls/RltdPties/CdtrAcct/Id/IBAN"),
+                            iban,
+                        );
+                    }
+                    None => v.error(
+                        &format!("{path}/NtryDtls/TxDtls/RltdPties/CdtrAcct"),
+                        "is required on booked credits",
+                    ),
+                }
+            }
+        }
+
+        v.finish()
+    }
+
+    /// The booked credits on every statement, in file order. Debits and
+    /// pending or informational entries are not payments to us and are left
+    /// out. Call on a validated document.
+    pub fn incoming_payments(&self) -> Result<Vec<IncomingPayment>, Iso20022Error> {
+        self.validate()?;
+
+        let mut payments = Vec::new();
+        for entry in self.statement.statements.iter().flat_map(|s| &s.entries) {
+            if !entry.is_booked_credit() {
+                continue;
+            }
+
+            let transaction = entry.transaction();
+            let parties = transaction.and_then(|txn| txn.parties.as_ref());
+            let invalid = |reason: &str| Iso20022Error::Invalid(vec![reason.to_string()]);
+
+            payments.push(IncomingPayment {
+                reference: entry.servicer_reference.clone().unwrap_or_default(),
+                creditor_account: entry
+                    .creditor_iban()
+                    .and_then(|iban| iban.parse().ok())
+                    .ok_or_else(|| invalid("credit without a creditor account"))?,
+                debtor_name: parties
+                    .and_then(|parties| parties.debtor.as_ref())
+                    .and_then(|debtor| debtor.party.name.clone()),
+                amount: Money(
+                    entry
+                        .amount
+                        .value
+                        .parse()
+                        .map_err(|_| invalid("bad amount"))?,
+                ),
+                currency: entry.amount.currency.clone(),
+                booking_date: entry
+                    .booking_date
+                    .as_ref()
+                    .and_then(|date| NaiveDate::parse_from_str(&date.date, "%Y-%m-%d").ok())
+                    .ok_or_else(|| invalid("credit without a booking date"))?,
+                remittance: transaction
+                    .and_then(|txn| txn.remittance.as_ref())
+                    .map(|remittance| remittance.unstructured.join(" ")),
+            });
+        }
+
+        Ok(payments)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use bank_core::account::Money;
+    use rust_decimal::dec;
+
+    use crate::iso20022::{Iso20022Error, camt053::Camt053Document};
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/iso20022/camt.053.xml");
+    const INVALID: &str = include_str!("../../tests/fixtures/iso20022/camt.053.invalid.xml");
+
+    #[test]
+    fn test_camt053_will_read_booked_credits_from_fixture() {
+        let document = Camt053Document::from_xml(FIXTURE).unwrap();
+
+        let payments = document.incoming_payments().unwrap();
+
+        assert_eq!(payments.len(), 2);
+        assert_eq!(payments[0].reference, "CORR-20260302-0001");
+        assert_eq!(
+            payments[0].creditor_account.as_str(),
+            "GB82WEST12345698765432"
+        );
+        assert_eq!(payments[0].amount, Money(dec!(1500.00)));
+        assert_eq!(payments[0].currency, "EUR");
+        assert_eq!(payments[0].debtor_name.as_deref(), Some("Charles Babbage"));
+        assert_eq!(payments[0].remittance.as_deref(), Some("Rent March"));
+        assert_eq!(payments[1].amount, Money(dec!(42.10)));
+    }
+
+    #[test]
+    fn test_camt053_will_round_trip_through_fixture() {
+        let document = Camt053Document::from_xml(FIXTURE).unwrap();
+
+        let xml = document.to_xml().unwrap();
+
+        assert_eq!(xml, FIXTURE);
+        assert_eq!(Camt053Document::from_xml(&xml), Ok(document));
+    }
+
+    #[test]
+    fn test_camt053_will_report_every_schema_violation() {
+        let Err(Iso20022Error::Invalid(errors)) = Camt053Document::from_xml(INVALID) else {
+            panic!("expected validation errors");
+        };
+
+        assert_eq!(errors.len(), 5, "{errors:#?}");
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("BkToCstmrStmt/Stmt[1]/Acct/Id/IBAN:"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.ends_with("Ntry[1]/Amt/@Ccy: \"eur\" is not a currency code"))
+        );
+        assert!(errors.iter().any(|e| e.contains("Ntry[1]/CdtDbtInd:")));
+        assert!(errors.iter().any(|e| e.contains("Ntry[2]/BookgDt/Dt:")));
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("Ntry[2]/AcctSvcrRef: is required"))
+        );
+    }
+}
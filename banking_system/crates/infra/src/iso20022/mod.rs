@@ -0,0 +1,269 @@
+//! ISO 20022 payment messages exchanged with other institutions: pain.001
+//! credit transfer initiations going out and camt.053 statements coming in.
+//!
+//! Only the elements the bank produces or needs are modelled. Validation
+//! covers what the XML schemas would reject for those elements: required
+//! fields, text lengths, identifier patterns, amount and date formats, and
+//! the counts and control sums in the headers.
+
+pub mod camt053;
+pub mod pain001;
+
+use bank_core::account_number::AccountNumber;
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+const DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Iso20022Error {
+    #[error("malformed XML: {0}")]
+    Xml(String),
+    #[error("message fails schema validation:\n  - {}", .0.join("\n  - "))]
+    Invalid(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartyIdentification {
+    #[serde(rename = "Nm", skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CashAccount {
+    #[serde(rename = "Id")]
+    pub id: AccountIdentification,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountIdentification {
+    #[serde(rename = "IBAN")]
+    pub iban: String,
+}
+
+impl CashAccount {
+    pub fn iban(number: &AccountNumber) -> Self {
+        Self {
+            id: AccountIdentification {
+                iban: number.as_str().into(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinancialInstitution {
+    #[serde(rename = "FinInstnId")]
+    pub id: FinancialInstitutionIdentification,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinancialInstitutionIdentification {
+    #[serde(rename = "BICFI", skip_serializing_if = "Option::is_none")]
+    pub bic: Option<String>,
+}
+
+impl FinancialInstitution {
+    pub fn bic(bic: Option<&str>) -> Self {
+        Self {
+            id: FinancialInstitutionIdentification {
+                bic: bic.map(Into::into),
+            },
+        }
+    }
+}
+
+/// An amount with its currency in the `Ccy` attribute.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyAmount {
+    #[serde(rename = "@Ccy")]
+    pub currency: String,
+    #[serde(rename = "$text")]
+    pub value: String,
+}
+
+impl CurrencyAmount {
+    pub fn new(currency: &str, value: Decimal) -> Self {
+        let mut value = value;
+        value.rescale(2);
+        Self {
+            currency: currency.into(),
+            value: value.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DateChoice {
+    #[serde(rename = "Dt")]
+    pub date: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemittanceInformation {
+    #[serde(rename = "Ustrd", default)]
+    pub unstructured: Vec<String>,
+}
+
+fn to_xml<T: Serialize>(document: &T) -> Result<String, Iso20022Error> {
+    let mut body = String::new();
+    let mut serializer = quick_xml::se::Serializer::new(&mut body);
+    serializer.indent(' ', 2);
+    document
+        .serialize(serializer)
+        .map_err(|err| Iso20022Error::Xml(err.to_string()))?;
+    Ok(format!("{XML_DECLARATION}\n{body}\n"))
+}
+
+fn from_xml<T: for<'de> Deserialize<'de>>(xml: &str) -> Result<T, Iso20022Error> {
+    quick_xml::de::from_str(xml).map_err(|err| Iso20022Error::Xml(err.to_string()))
+}
+
+fn format_date_time(at: NaiveDateTime) -> String {
+    at.format(DATE_TIME_FORMAT).to_string()
+}
+
+/// Collects every schema violation in a message, each prefixed with the
+/// path of the offending element.
+#[derive(Debug, Default)]
+struct Validator {
+    errors: Vec<String>,
+}
+
+impl Validator {
+    fn error(&mut self, path: &str, reason: impl std::fmt::Display) {
+        self.errors.push(format!("{path}: {reason}"));
+    }
+
+    fn finish(self) -> Result<(), Iso20022Error> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Iso20022Error::Invalid(self.errors))
+        }
+    }
+
+    fn namespace(&mut self, actual: &str, expected: &str) {
+        if actual != expected {
+            self.error(
+                "Document/@xmlns",
+                format!("expected {expected}, found {actual:?}"),
+            );
+        }
+    }
+
+    /// `Max<max>Text`: between 1 and `max` characters.
+    fn text(&mut self, path: &str, value: &str, max: usize) {
+        let length = value.chars().count();
+        if length == 0 || length > max {
+            self.error(
+                path,
+                format!("must be 1 to {max} characters, found {length}"),
+            );
+        }
+    }
+
+    fn optional_text(&mut self, path: &str, value: Option<&str>, max: usize) {
+        if let Some(value) = value {
+            self.text(path, value, max);
+        }
+    }
+
+    fn iban(&mut self, path: &str, value: &str) -> Option<AccountNumber> {
+        let valid_characters = value
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+        match AccountNumber::parse(value) {
+            Ok(number) if valid_characters => Some(number),
+            _ => {
+                self.error(path, format!("{value:?} is not a valid IBAN"));
+                None
+            }
+        }
+    }
+
+    /// `BICFIIdentifier`: four letters of institution, two of country, then
+    /// two alphanumeric location characters and an optional three of branch.
+    fn bic(&mut self, path: &str, value: Option<&str>) {
+        let Some(value) = value else { return };
+        let bytes = value.as_bytes();
+        let valid = matches!(bytes.len(), 8 | 11)
+            && bytes[..6].iter().all(u8::is_ascii_uppercase)
+            && bytes[6..]
+                .iter()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit());
+        if !valid {
+            self.error(path, format!("{value:?} is not a valid BIC"));
+        }
+    }
+
+    /// `ActiveOrHistoricCurrencyAndAmount`: a three-letter currency and a
+    /// non-negative amount with at most 18 digits, 5 of them fractional.
+    fn amount(&mut self, path: &str, amount: &CurrencyAmount) -> Option<Decimal> {
+        if amount.currency.len() != 3 || !amount.currency.chars().all(|c| c.is_ascii_uppercase()) {
+            self.error(
+                &format!("{path}/@Ccy"),
+                format!("{:?} is not a currency code", amount.currency),
+            );
+        }
+        self.decimal(path, &amount.value)
+    }
+
+    fn decimal(&mut self, path: &str, value: &str) -> Option<Decimal> {
+        let well_formed = !value.is_empty()
+            && value.chars().all(|c| c.is_ascii_digit() || c == '.')
+            && value.matches('.').count() <= 1;
+        let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+
+        match value.parse::<Decimal>() {
+            Ok(parsed)
+                if well_formed && integer.len() + fraction.len() <= 18 && fraction.len() <= 5 =>
+            {
+                Some(parsed)
+            }
+            _ => {
+                self.error(path, format!("{value:?} is not a valid amount"));
+                None
+            }
+        }
+    }
+
+    fn date(&mut self, path: &str, value: &str) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| self.error(path, format!("{value:?} is not an ISO date")))
+            .ok()
+    }
+
+    fn date_time(&mut self, path: &str, value: &str) {
+        if NaiveDateTime::parse_from_str(value, DATE_TIME_FORMAT).is_err() {
+            self.error(path, format!("{value:?} is not an ISO date and time"));
+        }
+    }
+
+    /// Checks a declared number of transactions against the actual count.
+    fn count(&mut self, path: &str, declared: &str, actual: usize) {
+        match declared.parse::<usize>() {
+            Ok(declared) if declared == actual => {}
+            Ok(declared) => self.error(
+                path,
+                format!("declares {declared} transactions, found {actual}"),
+            ),
+            Err(_) => self.error(path, format!("{declared:?} is not a number")),
+        }
+    }
+
+    fn control_sum(&mut self, path: &str, declared: Option<&str>, actual: Decimal) {
+        let Some(declared) = declared else { return };
+        if let Some(declared) = self.decimal(path, declared) {
+            if declared != actual {
+                self.error(
+                    path,
+                    format!("declares {declared}, amounts add up to {actual}"),
+                );
+            }
+        }
+    }
+}
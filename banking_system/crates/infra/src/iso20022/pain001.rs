@@ -0,0 +1,475 @@
+use std::collections::BTreeMap;
+
+use bank_core::interbank::OutgoingPayment;
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::iso20022::{
+    CashAccount, CurrencyAmount, DateChoice, FinancialInstitution, Iso20022Error,
+    PartyIdentification, RemittanceInformation, Validator, format_date_time, from_xml, to_xml,
+};
+
+pub const NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:pain.001.001.09";
+
+/// Identifies the file and the bank sending it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentFileHeader {
+    pub message_id: String,
+    pub created_at: NaiveDateTime,
+    pub initiating_party: String,
+    /// BIC of the sending bank, used as the debtor agent.
+    pub debtor_agent: String,
+    pub currency: String,
+}
+
+/// A customer credit transfer initiation (pain.001.001.09).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "Document")]
+pub struct Pain001Document {
+    #[serde(rename = "@xmlns")]
+    pub xmlns: String,
+    #[serde(rename = "CstmrCdtTrfInitn")]
+    pub initiation: CustomerCreditTransferInitiation,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomerCreditTransferInitiation {
+    #[serde(rename = "GrpHdr")]
+    pub header: GroupHeader,
+    #[serde(rename = "PmtInf", default)]
+    pub payment_information: Vec<PaymentInformation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupHeader {
+    #[serde(rename = "MsgId")]
+    pub message_id: String,
+    #[serde(rename = "CreDtTm")]
+    pub created_at: String,
+    #[serde(rename = "NbOfTxs")]
+    pub number_of_transactions: String,
+    #[serde(rename = "CtrlSum", skip_serializing_if = "Option::is_none")]
+    pub control_sum: Option<String>,
+    #[serde(rename = "InitgPty")]
+    pub initiating_party: PartyIdentification,
+}
+
+/// The transfers debited from one account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaymentInformation {
+    #[serde(rename = "PmtInfId")]
+    pub id: String,
+    #[serde(rename = "PmtMtd")]
+    pub method: String,
+    #[serde(rename = "NbOfTxs", skip_serializing_if = "Option::is_none")]
+    pub number_of_transactions: Option<String>,
+    #[serde(rename = "CtrlSum", skip_serializing_if = "Option::is_none")]
+    pub control_sum: Option<String>,
+    #[serde(rename = "ReqdExctnDt")]
+    pub requested_execution: DateChoice,
+    #[serde(rename = "Dbtr")]
+    pub debtor: PartyIdentification,
+    #[serde(rename = "DbtrAcct")]
+    pub debtor_account: CashAccount,
+    #[serde(rename = "DbtrAgt")]
+    pub debtor_agent: FinancialInstitution,
+    #[serde(rename = "CdtTrfTxInf", default)]
+    pub transactions: Vec<CreditTransferTransaction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreditTransferTransaction {
+    #[serde(rename = "PmtId")]
+    pub payment_id: PaymentIdentification,
+    #[serde(rename = "Amt")]
+    pub amount: AmountType,
+    #[serde(rename = "CdtrAgt", skip_serializing_if = "Option::is_none")]
+    pub creditor_agent: Option<FinancialInstitution>,
+    #[serde(rename = "Cdtr")]
+    pub creditor: PartyIdentification,
+    #[serde(rename = "CdtrAcct")]
+    pub creditor_account: CashAccount,
+    #[serde(rename = "RmtInf", skip_serializing_if = "Option::is_none")]
+    pub remittance: Option<RemittanceInformation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaymentIdentification {
+    #[serde(rename = "EndToEndId")]
+    pub end_to_end_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmountType {
+    #[serde(rename = "InstdAmt")]
+    pub instructed: CurrencyAmount,
+}
+
+impl Pain001Document {
+    /// Builds one payment information block per debtor account and
+    /// execution date, in the order the payments were given.
+    pub fn from_payments(header: &PaymentFileHeader, payments: &[OutgoingPayment]) -> Self {
+        let mut groups: BTreeMap<(String, String), Vec<&OutgoingPayment>> = BTreeMap::new();
+        let mut order = Vec::new();
+        for payment in payments {
+            let key = (
+                payment.debtor_number.as_str().to_string(),
+                payment.requested_execution.to_string(),
+            );
+            if !groups.contains_key(&key) {
+                order.push(key.clone());
+            }
+            groups.entry(key).or_default().push(payment);
+        }
+
+        let payment_information = order
+            .into_iter()
+            .enumerate()
+            .map(|(index, key)| {
+                let group = &groups[&key];
+                let first = group[0];
+                PaymentInformation {
+                    id: format!("{}-{}", header.message_id, index + 1),
+                    method: "TRF".into(),
+                    number_of_transactions: Some(group.len().to_string()),
+                    control_sum: Some(control_sum(group.iter().copied())),
+                    requested_execution: DateChoice {
+                        date: first.requested_execution.to_string(),
+                    },
+                    debtor: PartyIdentification {
+                        name: Some(first.debtor_name.clone()),
+                    },
+                    debtor_account: CashAccount::iban(&first.debtor_number),
+                    debtor_agent: FinancialInstitution::bic(Some(&header.debtor_agent)),
+                    transactions: group
+                        .iter()
+                        .map(|payment| transaction(&header.currency, payment))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Pain001Document {
+            xmlns: NAMESPACE.into(),
+            initiation: CustomerCreditTransferInitiation {
+                header: GroupHeader {
+                    message_id: header.message_id.clone(),
+                    created_at: format_date_time(header.created_at),
+                    number_of_transactions: payments.len().to_string(),
+                    control_sum: Some(control_sum(payments.iter())),
+                    initiating_party: PartyIdentification {
+                        name: Some(header.initiating_party.clone()),
+                    },
+                },
+                payment_information,
+            },
+        }
+    }
+
+    /// Parses and validates a pain.001 file.
+    pub fn from_xml(xml: &str) -> Result<Self, Iso20022Error> {
+        let document: Self = from_xml(xml)?;
+        document.validate()?;
+        Ok(document)
+    }
+
+    /// Validates and serializes the document.
+    pub fn to_xml(&self) -> Result<String, Iso20022Error> {
+        self.validate()?;
+        to_xml(self)
+    }
+
+    pub fn validate(&self) -> Result<(), Iso20022Error> {
+        let mut v = Validator::default();
+        v.namespace(&self.xmlns, NAMESPACE);
+
+        let header = &self.initiation.header;
+        let path = "CstmrCdtTrfInitn/GrpHdr";
+        v.text(&format!("{path}/MsgId"), &header.message_id, 35);
+        v.date_time(&format!("{path}/CreDtTm"), &header.created_at);
+        v.optional_text(
+            &format!("{path}/InitgPty/Nm"),
+            header.initiating_party.name.as_deref(),
+            140,
+        );
+
+        if self.initiation.payment_information.is_empty() {
+            v.error("CstmrCdtTrfInitn/PmtInf", "at least one is required");
+        }
+
+        let mut total_count = 0;
+        let mut total_sum = Decimal::ZERO;
+        for (index, info) in self.initiation.payment_information.iter().enumerate() {
+            let path = format!("CstmrCdtTrfInitn/PmtInf[{}]", index + 1);
+            v.text(&format!("{path}/PmtInfId"), &info.id, 35);
+            if !matches!(info.method.as_str(), "TRF" | "CHK" | "TRA") {
+                v.error(
+                    &format!("{path}/PmtMtd"),
+                    format!("{:?} is not a payment method", info.method),
+                );
+            }
+            v.date(
+                &format!("{path}/ReqdExctnDt/Dt"),
+                &info.requested_execution.date,
+            );
+            v.optional_text(&format!("{path}/Dbtr/Nm"), info.debtor.name.as_deref(), 140);
+            v.iban(
+                &format!("{path}/DbtrAcct/Id/IBAN"),
+                &info.debtor_account.id.iban,
+            );
+            v.bic(
+                &format!("{path}/DbtrAgt/FinInstnId/BICFI"),
+                info.debtor_agent.id.bic.as_deref(),
+            );
+
+            if info.transactions.is_empty() {
+                v.error(&format!("{path}/CdtTrfTxInf"), "at least one is required");
+            }
+
+            let mut sum = Decimal::ZERO;
+            for (index, txn) in info.transactions.iter().enumerate() {
+                let path = format!("{path}/CdtTrfTxInf[{}]", index + 1);
+                v.text(
+                    &format!("{path}/PmtId/EndToEndId"),
+                    &txn.payment_id.end_to_end_id,
+                    35,
+                );
+                if let Some(amount) =
+                    v.amount(&format!("{path}/Amt/InstdAmt"), &txn.amount.instructed)
+                {
+                    if amount <= Decimal::ZERO {
+                        v.error(&format!("{path}/Amt/InstdAmt"), "must be greater than zero");
+                    }
+                    sum += amount;
+                }
+                if let Some(agent) = &txn.creditor_agent {
+                    v.bic(
+                        &format!("{path}/CdtrAgt/FinInstnId/BICFI"),
+                        agent.id.bic.as_deref(),
+                    );
+                }
+                match txn.creditor.name.as_deref() {
+                    Some(name) => v.text(&format!("{path}/Cdtr/Nm"), name, 140),
+                    None => v.error(&format!("{path}/Cdtr/Nm"), "is required"),
+                }
+                v.iban(
+                    &format!("{path}/CdtrAcct/Id/IBAN"),
+                    &txn.creditor_account.id.iban,
+                );
+                if let Some(remittance) = &txn.remittance {
+                    for line in &remittance.unstructured {
+                        v.text(&format!("{path}/RmtInf/Ustrd"), line, 140);
+                    }
+                }
+            }
+
+            if let Some(declared) = &info.number_of_transactions {
+                v.count(
+                    &format!("{path}/NbOfTxs"),
+                    declared,
+                    info.transactions.len(),
+                );
+            }
+            v.control_sum(&format!("{path}/CtrlSum"), info.control_sum.as_deref(), sum);
+            total_count += info.transactions.len();
+            total_sum += sum;
+        }
+
+        v.count(
+            &format!("{path}/NbOfTxs"),
+            &header.number_of_transactions,
+            total_count,
+        );
+        v.control_sum(
+            &format!("{path}/CtrlSum"),
+            header.control_sum.as_deref(),
+            total_sum,
+        );
+        v.finish()
+    }
+}
+
+fn transaction(currency: &str, payment: &OutgoingPayment) -> CreditTransferTransaction {
+    let transfer = &payment.transfer;
+    CreditTransferTransaction {
+        payment_id: PaymentIdentification {
+            end_to_end_id: payment.end_to_end_id.clone(),
+        },
+        amount: AmountType {
+            instructed: CurrencyAmount::new(currency, transfer.amount.0),
+        },
+        creditor_agent: transfer
+            .creditor_agent
+            .as_deref()
+            .map(|bic| FinancialInstitution::bic(Some(bic))),
+        creditor: PartyIdentification {
+            name: Some(transfer.creditor_name.clone()),
+        },
+        creditor_account: CashAccount::iban(&transfer.creditor_account),
+        remittance: transfer
+            .remittance
+            .as_ref()
+            .map(|text| RemittanceInformation {
+                unstructured: vec![text.clone()],
+            }),
+    }
+}
+
+fn control_sum<'a>(payments: impl Iterator<Item = &'a OutgoingPayment>) -> String {
+    let mut sum: Decimal = payments.map(|payment| payment.transfer.amount.0).sum();
+    sum.rescale(2);
+    sum.to_string()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use bank_core::{
+        account::Money,
+        account_number::AccountNumber,
+        interbank::{ExternalTransfer, OutgoingPayment},
+    };
+    use chrono::NaiveDate;
+    use rust_decimal::dec;
+
+    use crate::iso20022::{
+        Iso20022Error,
+        pain001::{Pain001Document, PaymentFileHeader},
+    };
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/iso20022/pain.001.xml");
+
+    fn header() -> PaymentFileHeader {
+        PaymentFileHeader {
+            message_id: "PRORUST-20260302-1".into(),
+            created_at: NaiveDate::from_ymd_opt(2026, 3, 2)
+                .unwrap()
+                .and_hms_opt(9, 30, 0)
+                .unwrap(),
+            initiating_party: "Pro Rust Bank".into(),
+            debtor_agent: "PRORGB2L".into(),
+            currency: "EUR".into(),
+        }
+    }
+
+    fn payment(
+        id: &str,
+        creditor: &str,
+        amount: Money,
+        remittance: Option<&str>,
+    ) -> OutgoingPayment {
+        OutgoingPayment {
+            end_to_end_id: id.into(),
+            debtor_account: 1,
+            debtor_name: "Ada Lovelace".into(),
+            debtor_number: AccountNumber::parse("GB82WEST12345698765432").unwrap(),
+            transfer: ExternalTransfer {
+                creditor_name: creditor.into(),
+                creditor_account: AccountNumber::parse("DE89370400440532013000").unwrap(),
+                creditor_agent: Some("COBADEFFXXX".into()),
+                amount,
+                remittance: remittance.map(Into::into),
+            },
+            requested_execution: NaiveDate::from_ymd_opt(2026, 3, 3).unwrap(),
+        }
+    }
+
+    fn payments() -> Vec<OutgoingPayment> {
+        vec![
+            payment(
+                "E2E000000000001",
+                "Charles Babbage",
+                Money(dec!(250)),
+                Some("Invoice 42"),
+            ),
+            payment("E2E000000000002", "Grace Hopper", Money(dec!(19.5)), None),
+        ]
+    }
+
+    #[test]
+    fn test_pain001_will_export_payments_matching_fixture() {
+        let xml = Pain001Document::from_payments(&header(), &payments())
+            .to_xml()
+            .unwrap();
+
+        assert_eq!(xml, FIXTURE);
+    }
+
+    #[test]
+    fn test_pain001_will_round_trip_through_fixture() {
+        let parsed = Pain001Document::from_xml(FIXTURE).unwrap();
+
+        assert_eq!(
+            parsed,
+            Pain001Document::from_payments(&header(), &payments())
+        );
+        assert_eq!(
+            Pain001Document::from_xml(&parsed.to_xml().unwrap()),
+            Ok(parsed)
+        );
+    }
+
+    #[test]
+    fn test_pain001_will_reject_schema_violations() {
+        let mut document = Pain001Document::from_payments(&header(), &payments());
+        document.initiation.header.number_of_transactions = "3".into();
+        document.initiation.header.message_id = "X".repeat(36);
+        let info = &mut document.initiation.payment_information[0];
+        info.transactions[0].amount.instructed.value = "12.345678".into();
+        info.transactions[1].creditor_account.id.iban = "DE00370400440532013000".into();
+        info.debtor_agent.id.bic = Some("prorgb2l".into());
+
+        let Err(Iso20022Error::Invalid(errors)) = document.validate() else {
+            panic!("expected validation errors");
+        };
+
+        // The unreadable amount also leaves both control sums short.
+        assert_eq!(errors.len(), 7, "{errors:#?}");
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("CstmrCdtTrfInitn/GrpHdr/MsgId:"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("CstmrCdtTrfInitn/GrpHdr/NbOfTxs:"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("CdtTrfTxInf[1]/Amt/InstdAmt:"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("CdtTrfTxInf[2]/CdtrAcct/Id/IBAN:"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("DbtrAgt/FinInstnId/BICFI:"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("CstmrCdtTrfInitn/GrpHdr/CtrlSum:"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("CstmrCdtTrfInitn/PmtInf[1]/CtrlSum:"))
+        );
+        assert!(document.to_xml().is_err());
+
+        let wrong_namespace = FIXTURE.replace("pain.001.001.09", "pain.001.001.03");
+        assert!(matches!(
+            Pain001Document::from_xml(&wrong_namespace),
+            Err(Iso20022Error::Invalid(_))
+        ));
+        assert!(matches!(
+            Pain001Document::from_xml("<Document>"),
+            Err(Iso20022Error::Xml(_))
+        ));
+    }
+}
@@ -1 +1,8 @@
+pub mod clearing;
+pub mod file;
+pub mod instrumented;
+pub mod iso20022;
+pub mod metrics;
+pub mod notify;
+pub mod snapshot;
 pub mod storage;
@@ -0,0 +1,159 @@
+use std::time::Duration;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+
+/// Latency buckets in seconds, from 10µs to about 2.6s: in-memory operations
+/// take microseconds while file-backed ones take milliseconds.
+const LATENCY_BUCKETS: [f64; 10] = [
+    0.000_01, 0.000_04, 0.000_16, 0.000_64, 0.002_56, 0.010_24, 0.040_96, 0.163_84, 0.655_36,
+    2.621_44,
+];
+
+/// Counters and latency histograms for bank operations and the repository
+/// calls they make, kept in their own registry so that each bank (and each
+/// test) sees only its own numbers.
+pub struct Metrics {
+    registry: Registry,
+    operations: IntCounterVec,
+    operation_seconds: HistogramVec,
+    repo_calls: IntCounterVec,
+    repo_call_seconds: HistogramVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let operations = IntCounterVec::new(
+            Opts::new("bank_operations_total", "Bank operations by outcome."),
+            &["operation", "outcome"],
+        )
+        .unwrap();
+        let operation_seconds = HistogramVec::new(
+            HistogramOpts::new("bank_operation_duration_seconds", "Bank operation latency.")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+            &["operation"],
+        )
+        .unwrap();
+        let repo_calls = IntCounterVec::new(
+            Opts::new(
+                "bank_repository_calls_total",
+                "Repository calls by outcome.",
+            ),
+            &["call", "outcome"],
+        )
+        .unwrap();
+        let repo_call_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "bank_repository_call_duration_seconds",
+                "Repository call latency.",
+            )
+            .buckets(LATENCY_BUCKETS.to_vec()),
+            &["call"],
+        )
+        .unwrap();
+
+        registry.register(Box::new(operations.clone())).unwrap();
+        registry
+            .register(Box::new(operation_seconds.clone()))
+            .unwrap();
+        registry.register(Box::new(repo_calls.clone())).unwrap();
+        registry
+            .register(Box::new(repo_call_seconds.clone()))
+            .unwrap();
+
+        Self {
+            registry,
+            operations,
+            operation_seconds,
+            repo_calls,
+            repo_call_seconds,
+        }
+    }
+
+    pub fn record_operation(&self, operation: &str, outcome: &str, elapsed: Duration) {
+        self.operations
+            .with_label_values(&[operation, outcome])
+            .inc();
+        self.operation_seconds
+            .with_label_values(&[operation])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn record_repo_call(&self, call: &str, outcome: &str, elapsed: Duration) {
+        self.repo_calls.with_label_values(&[call, outcome]).inc();
+        self.repo_call_seconds
+            .with_label_values(&[call])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn operation_count(&self, operation: &str, outcome: &str) -> u64 {
+        self.operations
+            .with_label_values(&[operation, outcome])
+            .get()
+    }
+
+    pub fn repo_call_count(&self, call: &str, outcome: &str) -> u64 {
+        self.repo_calls.with_label_values(&[call, outcome]).get()
+    }
+
+    /// The registry, for callers that want to add metrics of their own.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Everything recorded so far in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Label for the outcome of a call: `ok`, or `error` when it failed.
+pub fn outcome<T, E>(result: &Result<T, E>) -> &'static str {
+    match result {
+        Ok(_) => "ok",
+        Err(_) => "error",
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::time::Duration;
+
+    use crate::metrics::Metrics;
+
+    #[test]
+    fn test_metrics_will_render_counters_and_histograms_as_prometheus_text() {
+        let metrics = Metrics::new();
+
+        metrics.record_operation("process", "ok", Duration::from_micros(20));
+        metrics.record_operation("process", "ok", Duration::from_micros(30));
+        metrics.record_operation("process", "error", Duration::from_millis(1));
+        metrics.record_repo_call("modify", "ok", Duration::from_micros(5));
+
+        let text = metrics.render();
+
+        assert!(text.contains("# TYPE bank_operations_total counter"));
+        assert!(text.contains(r#"bank_operations_total{operation="process",outcome="ok"} 2"#));
+        assert!(text.contains(r#"bank_operations_total{operation="process",outcome="error"} 1"#));
+        assert!(text.contains("# TYPE bank_operation_duration_seconds histogram"));
+        assert!(text.contains(
+            r#"bank_operation_duration_seconds_bucket{operation="process",le="0.00004"} 2"#
+        ));
+        assert!(text.contains(r#"bank_operation_duration_seconds_count{operation="process"} 3"#));
+        assert!(text.contains(r#"bank_repository_calls_total{call="modify",outcome="ok"} 1"#));
+        assert_eq!(metrics.operation_count("process", "ok"), 2);
+    }
+}
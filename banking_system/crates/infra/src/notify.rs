@@ -0,0 +1,181 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use bank_core::{
+    errors::NotifyError,
+    notification::{Notification, Notifier},
+};
+
+/// Appends every notification to a file as one JSON object per line, for a
+/// separate process to pick up and deliver.
+pub struct OutboxNotifier {
+    path: PathBuf,
+    /// Serializes appends so lines from concurrent operations never mix.
+    lock: Mutex<()>,
+}
+
+impl OutboxNotifier {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Notifier for OutboxNotifier {
+    fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let mut line = serde_json::to_string(notification).map_err(delivery_error)?;
+        line.push('\n');
+
+        let _guard = self.lock.lock().unwrap();
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(delivery_error)
+    }
+}
+
+fn delivery_error(err: impl std::fmt::Display) -> NotifyError {
+    NotifyError::Delivery(err.to_string())
+}
+
+/// An email as it would have been handed to the mail relay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Stands in for an SMTP relay in tests: renders notifications to email
+/// and keeps them in memory instead of sending them.
+#[derive(Debug, Default)]
+pub struct LocalMailServer {
+    state: Mutex<MailState>,
+}
+
+#[derive(Debug, Default)]
+struct MailState {
+    unavailable: bool,
+    sent: Vec<Email>,
+}
+
+impl LocalMailServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Simulates the relay refusing connections until it is lifted.
+    pub fn set_available(&self, available: bool) {
+        self.state.lock().unwrap().unavailable = !available;
+    }
+
+    /// Every email accepted so far.
+    pub fn sent(&self) -> Vec<Email> {
+        self.state.lock().unwrap().sent.clone()
+    }
+}
+
+impl Notifier for LocalMailServer {
+    fn notify(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let to = notification
+            .recipient
+            .clone()
+            .ok_or(NotifyError::NoRecipient(notification.customer))?;
+
+        let mut state = self.state.lock().unwrap();
+        if state.unavailable {
+            return Err(NotifyError::Delivery("connection refused".into()));
+        }
+        state.sent.push(Email {
+            to,
+            subject: notification.subject(),
+            body: notification.body(),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{fs, time::SystemTime};
+
+    use bank_core::{
+        account::Money,
+        errors::NotifyError,
+        notification::{Alert, Notification, Notifier},
+    };
+    use rust_decimal::dec;
+
+    use crate::notify::{LocalMailServer, OutboxNotifier};
+
+    fn notification(recipient: Option<&str>) -> Notification {
+        Notification {
+            rule_id: 1,
+            customer: 1,
+            recipient: recipient.map(Into::into),
+            account_id: 3,
+            alert: Alert::LowBalance {
+                balance: Money(dec!(40)),
+                threshold: Money(dec!(50)),
+            },
+            at: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn test_outbox_will_append_one_json_line_per_notification() {
+        let path = std::env::temp_dir().join(format!("bank-outbox-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let outbox = OutboxNotifier::new(&path);
+
+        outbox.notify(&notification(None)).unwrap();
+        outbox
+            .notify(&notification(Some("ada@example.com")))
+            .unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let lines: Vec<Notification> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].recipient.as_deref(), Some("ada@example.com"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_local_mail_server_will_render_and_keep_emails() {
+        let server = LocalMailServer::new();
+
+        server
+            .notify(&notification(Some("ada@example.com")))
+            .unwrap();
+        assert_eq!(
+            server.notify(&notification(None)),
+            Err(NotifyError::NoRecipient(1))
+        );
+        server.set_available(false);
+        assert!(matches!(
+            server.notify(&notification(Some("ada@example.com"))),
+            Err(NotifyError::Delivery(_))
+        ));
+
+        let sent = server.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "ada@example.com");
+        assert_eq!(sent[0].subject, "Low balance on account 3");
+        assert!(sent[0].body.contains("40"));
+    }
+}
@@ -0,0 +1,91 @@
+use std::{fs, path::Path};
+
+use bank_core::{errors::SnapshotError, snapshot::BankSnapshot};
+
+/// Writes `snapshot` to `path` as JSON. Like [`crate::file::FileRepo`], it
+/// goes through a sibling temporary file so an interrupted write never
+/// leaves a truncated snapshot behind.
+pub fn write_snapshot(
+    path: impl AsRef<Path>,
+    snapshot: &BankSnapshot,
+) -> Result<(), SnapshotError> {
+    let path = path.as_ref();
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, snapshot.to_json()?).map_err(io_error)?;
+    fs::rename(&tmp, path).map_err(io_error)
+}
+
+pub fn read_snapshot(path: impl AsRef<Path>) -> Result<BankSnapshot, SnapshotError> {
+    let json = fs::read_to_string(path).map_err(io_error)?;
+    BankSnapshot::from_json(&json)
+}
+
+fn io_error(err: std::io::Error) -> SnapshotError {
+    SnapshotError::Io(err.to_string())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{fs, time::SystemTime};
+
+    use bank_core::{
+        account::{Account, Money},
+        errors::SnapshotError,
+        snapshot::{BankSnapshot, SNAPSHOT_VERSION},
+    };
+
+    use crate::snapshot::{read_snapshot, write_snapshot};
+
+    fn snapshot() -> BankSnapshot {
+        BankSnapshot {
+            version: SNAPSHOT_VERSION,
+            taken_at: SystemTime::now(),
+            accounts: vec![Account::builder(1, 1).balance(Money(10.into())).build()],
+            customers: vec![],
+            next_approval_id: 0,
+            pending_approvals: vec![],
+            next_loan_id: 0,
+            loans: vec![],
+            next_term_deposit_id: 0,
+            term_deposits: vec![],
+            next_drawer_id: 0,
+            drawers: vec![],
+            next_payment_id: 0,
+            external_payments: vec![],
+            clearing_backlog: vec![],
+            clearing_dead_letters: vec![],
+            imported: vec!["STMT-1".into()],
+            next_alert_rule_id: 0,
+            alert_rules: vec![],
+            audit: vec![],
+        }
+    }
+
+    #[test]
+    fn test_snapshot_will_round_trip_through_file_and_refuse_other_versions() {
+        let path = std::env::temp_dir().join(format!("bank-snapshot-{}.json", std::process::id()));
+        let snapshot = snapshot();
+
+        write_snapshot(&path, &snapshot).unwrap();
+        assert_eq!(read_snapshot(&path).unwrap(), snapshot);
+
+        let newer = fs::read_to_string(&path).unwrap().replace(
+            &format!("\"version\": {SNAPSHOT_VERSION}"),
+            "\"version\": 99",
+        );
+        fs::write(&path, newer).unwrap();
+        assert_eq!(
+            read_snapshot(&path),
+            Err(SnapshotError::UnsupportedVersion(99))
+        );
+
+        fs::write(&path, "{\"version\": 1").unwrap();
+        assert!(matches!(
+            read_snapshot(&path),
+            Err(SnapshotError::Malformed(_))
+        ));
+
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(read_snapshot(&path), Err(SnapshotError::Io(_))));
+    }
+}
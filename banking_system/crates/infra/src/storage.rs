@@ -1,39 +1,312 @@
 use std::{
-    collections::HashMap,
-    sync::{Arc, RwLock},
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::Bound,
+    sync::{
+        RwLock, RwLockWriteGuard,
+        atomic::{AtomicU64, Ordering},
+    },
 };
 
 use bank_core::{
-    account::{Account, AccountId, AccountRepository},
+    account::{Account, AccountId, AccountRepository, ensure_tenant},
+    account_number::AccountNumber,
+    async_repo::AsyncAccountRepository,
+    customer::{Customer, CustomerId, CustomerStore},
     errors::RepoError,
+    query::{AccountQuery, Page, PageRequest},
+    tenant::TenantId,
 };
 
+pub const DEFAULT_SHARDS: usize = 32;
+
+type Shard = RwLock<BTreeMap<AccountId, Account>>;
+
+/// Account ids by external number, per tenant.
+type NumberIndex = HashMap<TenantId, HashMap<AccountNumber, AccountId>>;
+
+/// Account ids by tenant and primary owner.
+type OwnerIndex = HashMap<(TenantId, CustomerId), BTreeSet<AccountId>>;
+
+/// Accounts are striped over a fixed number of independently locked shards,
+/// so operations on accounts in different shards never wait on each other.
 pub struct InMemoryRepo {
-    pub store: Arc<RwLock<HashMap<AccountId, Account>>>,
+    shards: Box<[Shard]>,
+    numbers: RwLock<NumberIndex>,
+    /// Held for writing while an account is created, so
+    /// [`AccountRepository::create_checked`] sees every account of the owner.
+    owners: RwLock<OwnerIndex>,
+    last_id: AtomicU64,
 }
 
 impl InMemoryRepo {
     pub fn new() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+
+    pub fn with_shards(shards: usize) -> Self {
         Self {
-            store: Arc::new(RwLock::new(HashMap::new())),
+            shards: (0..shards.max(1)).map(|_| RwLock::default()).collect(),
+            numbers: RwLock::default(),
+            owners: RwLock::default(),
+            last_id: AtomicU64::new(0),
         }
     }
+
+    fn shard_index(&self, id: AccountId) -> usize {
+        id as usize % self.shards.len()
+    }
+
+    fn shard(&self, id: AccountId) -> &Shard {
+        &self.shards[self.shard_index(id)]
+    }
+
+    /// Stores `account` while the caller holds the owner index.
+    fn insert(
+        &self,
+        tenant: &TenantId,
+        account: Account,
+        owners: &mut OwnerIndex,
+    ) -> Result<(), RepoError> {
+        let mut shard = self
+            .shard(account.id)
+            .write()
+            .map_err(|_| RepoError::LockPoisened)?;
+        ensure_tenant(tenant, &account, shard.get(&account.id))?;
+        self.last_id.fetch_max(account.id, Ordering::Relaxed);
+        self.index_numbers([&account])?;
+        owners
+            .entry((account.tenant.clone(), account.owner))
+            .or_default()
+            .insert(account.id);
+        shard.insert(account.id, account);
+        Ok(())
+    }
+
+    /// Records the numbers of `accounts` for [`AccountRepository::get_by_number`].
+    /// Called while their shards are still locked, so lookups never see an
+    /// account before its number.
+    fn index_numbers<'a>(
+        &self,
+        accounts: impl IntoIterator<Item = &'a Account>,
+    ) -> Result<(), RepoError> {
+        let mut numbers = self.numbers.write().map_err(|_| RepoError::LockPoisened)?;
+        for account in accounts {
+            if let Some(number) = &account.number {
+                numbers
+                    .entry(account.tenant.clone())
+                    .or_default()
+                    .insert(number.clone(), account.id);
+            }
+        }
+        Ok(())
+    }
 }
 
 impl AccountRepository for InMemoryRepo {
-    fn create(&self, account: Account) -> Result<(), RepoError> {
-        let mut store = self.store.write().map_err(|_| RepoError::LockPoisened)?;
-        store.insert(account.id, account);
-        Ok(())
+    fn next_id(&self) -> Result<AccountId, RepoError> {
+        Ok(self.last_id.fetch_add(1, Ordering::Relaxed) + 1)
     }
-    fn get(&self, id: AccountId) -> Result<Option<Account>, RepoError> {
-        let store = self.store.read().map_err(|_| RepoError::LockPoisened)?;
-        Ok(store.get(&id).cloned())
+    fn create(&self, tenant: &TenantId, account: Account) -> Result<(), RepoError> {
+        let mut owners = self.owners.write().map_err(|_| RepoError::LockPoisened)?;
+        self.insert(tenant, account, &mut owners)
     }
-    fn update(&self, account: Account) -> Result<(), RepoError> {
-        let mut store = self.store.write().map_err(|_| RepoError::LockPoisened)?;
-        store.insert(account.id, account);
-        Ok(())
+    fn get(&self, tenant: &TenantId, id: AccountId) -> Result<Option<Account>, RepoError> {
+        let shard = self.shard(id).read().map_err(|_| RepoError::LockPoisened)?;
+        Ok(shard
+            .get(&id)
+            .filter(|account| account.tenant == *tenant)
+            .cloned())
+    }
+    fn update(&self, tenant: &TenantId, account: Account) -> Result<(), RepoError> {
+        AccountRepository::create(self, tenant, account)
+    }
+    fn list(
+        &self,
+        tenant: &TenantId,
+        query: &AccountQuery,
+        page: PageRequest,
+    ) -> Result<Page<Account>, RepoError> {
+        let start = match page.after {
+            Some(cursor) => Bound::Excluded(cursor.after()),
+            None => Bound::Unbounded,
+        };
+
+        // Each shard is ordered by id, so its first `limit + 1` matches past
+        // the cursor are the only ones that can make the page.
+        let mut candidates = Vec::new();
+        for shard in self.shards.iter() {
+            let shard = shard.read().map_err(|_| RepoError::LockPoisened)?;
+            candidates.extend(
+                shard
+                    .range((start, Bound::Unbounded))
+                    .map(|(_, account)| account)
+                    .filter(|account| account.tenant == *tenant && query.matches(account))
+                    .take(page.limit + 1)
+                    .cloned(),
+            );
+        }
+        candidates.sort_by_key(|account| account.id);
+        Ok(Page::from_ordered(&candidates, query, page))
+    }
+
+    fn get_by_number(
+        &self,
+        tenant: &TenantId,
+        number: &AccountNumber,
+    ) -> Result<Option<Account>, RepoError> {
+        let id = self
+            .numbers
+            .read()
+            .map_err(|_| RepoError::LockPoisened)?
+            .get(tenant)
+            .and_then(|numbers| numbers.get(number))
+            .copied();
+        let Some(id) = id else {
+            return Ok(None);
+        };
+        Ok(AccountRepository::get(self, tenant, id)?
+            .filter(|account| account.number.as_ref() == Some(number)))
+    }
+
+    /// Holds the owner index while the owner's accounts are loaded, checked
+    /// and the new one stored, so concurrent openings for one owner take
+    /// turns.
+    fn create_checked<E>(
+        &self,
+        tenant: &TenantId,
+        account: Account,
+        check: impl FnOnce(&[Account]) -> Result<(), E>,
+    ) -> Result<(), E>
+    where
+        E: From<RepoError>,
+    {
+        let mut owners = self.owners.write().map_err(|_| RepoError::LockPoisened)?;
+        let owned = owners
+            .get(&(tenant.clone(), account.owner))
+            .into_iter()
+            .flatten()
+            .filter_map(|id| AccountRepository::get(self, tenant, *id).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        check(&owned)?;
+        Ok(self.insert(tenant, account, &mut owners)?)
+    }
+
+    /// Write-locks every shard the accounts live in, in ascending shard
+    /// order whatever order the ids were requested in, so two transfers
+    /// between the same pair of accounts in opposite directions cannot
+    /// deadlock.
+    fn modify<T, E>(
+        &self,
+        tenant: &TenantId,
+        ids: &[AccountId],
+        f: impl FnOnce(&mut [Account]) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        E: From<RepoError>,
+    {
+        for (index, id) in ids.iter().enumerate() {
+            if ids[..index].contains(id) {
+                return Err(RepoError::DuplicateId(*id).into());
+            }
+        }
+
+        let mut shard_indexes: Vec<usize> = ids.iter().map(|id| self.shard_index(*id)).collect();
+        shard_indexes.sort_unstable();
+        shard_indexes.dedup();
+
+        let mut guards: Vec<(usize, RwLockWriteGuard<'_, BTreeMap<AccountId, Account>>)> =
+            Vec::with_capacity(shard_indexes.len());
+        for index in shard_indexes {
+            let guard = self.shards[index]
+                .write()
+                .map_err(|_| RepoError::LockPoisened)?;
+            guards.push((index, guard));
+        }
+
+        let mut accounts = Vec::with_capacity(ids.len());
+        for id in ids {
+            let index = self.shard_index(*id);
+            let (_, shard) = guards.iter().find(|(i, _)| *i == index).unwrap();
+            let account = shard.get(id).filter(|account| account.tenant == *tenant);
+            accounts.push(account.cloned().ok_or(RepoError::NotFound(*id))?);
+        }
+
+        let value = f(&mut accounts)?;
+
+        for account in &accounts {
+            ensure_tenant(tenant, account, None)?;
+        }
+        self.index_numbers(&accounts)?;
+        for account in accounts {
+            let index = self.shard_index(account.id);
+            let (_, shard) = guards.iter_mut().find(|(i, _)| *i == index).unwrap();
+            shard.insert(account.id, account);
+        }
+
+        Ok(value)
+    }
+}
+
+/// Critical sections never await, so the blocking locks are held only briefly
+/// and the async methods simply defer to the synchronous ones.
+impl AsyncAccountRepository for InMemoryRepo {
+    async fn next_id(&self) -> Result<AccountId, RepoError> {
+        AccountRepository::next_id(self)
+    }
+
+    async fn create(&self, tenant: &TenantId, account: Account) -> Result<(), RepoError> {
+        AccountRepository::create(self, tenant, account)
+    }
+
+    async fn get(&self, tenant: &TenantId, id: AccountId) -> Result<Option<Account>, RepoError> {
+        AccountRepository::get(self, tenant, id)
+    }
+
+    async fn update(&self, tenant: &TenantId, account: Account) -> Result<(), RepoError> {
+        AccountRepository::update(self, tenant, account)
+    }
+
+    async fn list(
+        &self,
+        tenant: &TenantId,
+        query: &AccountQuery,
+        page: PageRequest,
+    ) -> Result<Page<Account>, RepoError> {
+        AccountRepository::list(self, tenant, query, page)
+    }
+
+    async fn get_by_number(
+        &self,
+        tenant: &TenantId,
+        number: &AccountNumber,
+    ) -> Result<Option<Account>, RepoError> {
+        AccountRepository::get_by_number(self, tenant, number)
+    }
+
+    async fn create_checked<E>(
+        &self,
+        tenant: &TenantId,
+        account: Account,
+        check: impl FnOnce(&[Account]) -> Result<(), E> + Send,
+    ) -> Result<(), E>
+    where
+        E: From<RepoError> + Send,
+    {
+        AccountRepository::create_checked(self, tenant, account, check)
+    }
+
+    async fn modify<T, E>(
+        &self,
+        tenant: &TenantId,
+        ids: &[AccountId],
+        f: impl FnOnce(&mut [Account]) -> Result<T, E> + Send,
+    ) -> Result<T, E>
+    where
+        T: Send,
+        E: From<RepoError> + Send,
+    {
+        AccountRepository::modify(self, tenant, ids, f)
     }
 }
 
@@ -43,21 +316,70 @@ impl Default for InMemoryRepo {
     }
 }
 
-#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct InMemoryCustomerStore {
+    customers: RwLock<HashMap<(TenantId, CustomerId), Customer>>,
+}
+
+impl InMemoryCustomerStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CustomerStore for InMemoryCustomerStore {
+    fn get(&self, tenant: &TenantId, id: CustomerId) -> Result<Option<Customer>, RepoError> {
+        let customers = self.customers.read().map_err(|_| RepoError::LockPoisened)?;
+        Ok(customers.get(&(tenant.clone(), id)).cloned())
+    }
 
+    fn save(&self, tenant: &TenantId, customer: Customer) -> Result<(), RepoError> {
+        let mut customers = self
+            .customers
+            .write()
+            .map_err(|_| RepoError::LockPoisened)?;
+        customers.insert((tenant.clone(), customer.id), customer);
+        Ok(())
+    }
+
+    fn list(&self, tenant: &TenantId) -> Result<Vec<Customer>, RepoError> {
+        let customers = self.customers.read().map_err(|_| RepoError::LockPoisened)?;
+        let mut customers: Vec<Customer> = customers
+            .iter()
+            .filter(|((owner, _), _)| owner == tenant)
+            .map(|(_, customer)| customer.clone())
+            .collect();
+        customers.sort_by_key(|customer| customer.id);
+        Ok(customers)
+    }
+}
+
+#[cfg(test)]
 pub mod tests {
-    use bank_core::{account::{Account, AccountRepository}, customer::Customer};
+    use std::{sync::Arc, thread};
 
-    use crate::storage::InMemoryRepo;
+    use bank_core::{
+        account::{Account, AccountRepository, AccountStatus, Money},
+        account_number::AccountNumbering,
+        customer::Customer,
+        customer::CustomerStore,
+        errors::RepoError,
+        query::{AccountQuery, PageRequest},
+        tenant::TenantId,
+    };
+    use rust_decimal::Decimal;
+
+    use crate::storage::{InMemoryCustomerStore, InMemoryRepo};
 
     #[test]
     fn test_will_create_account_and_store_to_in_memory_repo_successfully() {
         let customer: Customer = Customer::builder(1).build();
         let account: Account = Account::builder(1, customer.id).build();
         let repo: InMemoryRepo = InMemoryRepo::new();
-        let _ = repo.create(account.clone());
+        let tenant = TenantId::default();
+        let _ = repo.create(&tenant, account.clone());
 
-        let response = repo.get(account.id);
+        let response = repo.get(&tenant, account.id);
         assert!(response.is_ok());
         assert!(matches!(response, Ok(Some(_))));
         assert_eq!(response.ok().unwrap(), Some(account))
@@ -68,20 +390,233 @@ pub mod tests {
         let customer: Customer = Customer::builder(1).build();
         let mut account: Account = Account::builder(1, customer.id).build();
         let repo: InMemoryRepo = InMemoryRepo::new();
-        let _ = repo.create(account.clone());
+        let tenant = TenantId::default();
+        let _ = repo.create(&tenant, account.clone());
 
-        let response = repo.get(account.id);
+        let response = repo.get(&tenant, account.id);
         assert!(response.is_ok());
         assert!(matches!(response, Ok(Some(_))));
         assert_eq!(response.ok().unwrap(), Some(account.clone()));
-        
+
         let customer: Customer = Customer::builder(2).build();
         account.id = customer.id;
-        let _ = repo.update(account.clone());
+        let _ = repo.update(&tenant, account.clone());
 
-        let response = repo.get(account.id);
+        let response = repo.get(&tenant, account.id);
         assert!(response.is_ok());
         assert!(matches!(response, Ok(Some(_))));
         assert_eq!(response.ok().unwrap(), Some(account.clone()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_will_modify_accounts_in_requested_order_and_store_them() {
+        let repo = InMemoryRepo::with_shards(4);
+        let tenant = TenantId::default();
+        repo.create(
+            &tenant,
+            Account::builder(1, 1).balance(Money(10.into())).build(),
+        )
+        .unwrap();
+        repo.create(
+            &tenant,
+            Account::builder(2, 1).balance(Money(20.into())).build(),
+        )
+        .unwrap();
+
+        let result: Result<(), RepoError> = repo.modify(&tenant, &[2, 1], |accounts| {
+            assert_eq!(accounts[0].id, 2);
+            assert_eq!(accounts[1].id, 1);
+            accounts[0].balance = Money(5.into());
+            accounts[1].balance = Money(25.into());
+            Ok(())
+        });
+
+        assert!(result.is_ok());
+        assert_eq!(
+            repo.get(&tenant, 1).unwrap().unwrap().balance,
+            Money(25.into())
+        );
+        assert_eq!(
+            repo.get(&tenant, 2).unwrap().unwrap().balance,
+            Money(5.into())
+        );
+    }
+
+    #[test]
+    fn test_will_not_store_modifications_when_closure_fails() {
+        let repo = InMemoryRepo::new();
+        let tenant = TenantId::default();
+        repo.create(
+            &tenant,
+            Account::builder(1, 1).balance(Money(10.into())).build(),
+        )
+        .unwrap();
+
+        let result: Result<(), RepoError> = repo.modify(&tenant, &[1], |accounts| {
+            accounts[0].balance = Money(0.into());
+            Err(RepoError::LockPoisened)
+        });
+
+        assert_eq!(result, Err(RepoError::LockPoisened));
+        assert_eq!(
+            repo.get(&tenant, 1).unwrap().unwrap().balance,
+            Money(10.into())
+        );
+    }
+
+    #[test]
+    fn test_will_reject_missing_and_duplicate_ids_in_modify() {
+        let repo = InMemoryRepo::new();
+        let tenant = TenantId::default();
+        repo.create(&tenant, Account::builder(1, 1).build())
+            .unwrap();
+
+        let missing: Result<(), RepoError> = repo.modify(&tenant, &[1, 2], |_| Ok(()));
+        assert_eq!(missing, Err(RepoError::NotFound(2)));
+
+        let duplicate: Result<(), RepoError> = repo.modify(&tenant, &[1, 1], |_| Ok(()));
+        assert_eq!(duplicate, Err(RepoError::DuplicateId(1)));
+    }
+
+    #[test]
+    fn test_will_not_lose_updates_under_concurrent_opposing_transfers() {
+        let repo = Arc::new(InMemoryRepo::with_shards(2));
+        let tenant = TenantId::default();
+        repo.create(
+            &tenant,
+            Account::builder(1, 1).balance(Money(1000.into())).build(),
+        )
+        .unwrap();
+        repo.create(
+            &tenant,
+            Account::builder(2, 1).balance(Money(1000.into())).build(),
+        )
+        .unwrap();
+
+        let handles: Vec<_> = (0..8)
+            .map(|thread_index| {
+                let repo = Arc::clone(&repo);
+                let tenant = TenantId::default();
+                thread::spawn(move || {
+                    let ids = if thread_index % 2 == 0 {
+                        [1, 2]
+                    } else {
+                        [2, 1]
+                    };
+                    for _ in 0..500 {
+                        let result: Result<(), RepoError> =
+                            repo.modify(&tenant, &ids, |accounts| {
+                                accounts[0].balance.0 -= Decimal::ONE;
+                                accounts[1].balance.0 += Decimal::ONE;
+                                Ok(())
+                            });
+                        result.unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(
+            repo.get(&tenant, 1).unwrap().unwrap().balance,
+            Money(1000.into())
+        );
+        assert_eq!(
+            repo.get(&tenant, 2).unwrap().unwrap().balance,
+            Money(1000.into())
+        );
+    }
+
+    #[test]
+    fn test_will_list_accounts_across_shards_in_id_order() {
+        let repo = InMemoryRepo::with_shards(3);
+        let tenant = TenantId::default();
+        for id in (1..=10).rev() {
+            let status = if id % 2 == 0 {
+                AccountStatus::Frozen
+            } else {
+                AccountStatus::Active
+            };
+            repo.create(&tenant, Account::builder(id, id % 3).status(status).build())
+                .unwrap();
+        }
+
+        let frozen = AccountQuery::default().status(AccountStatus::Frozen);
+        let first = repo.list(&tenant, &frozen, PageRequest::first(3)).unwrap();
+        let ids: Vec<_> = first.items.iter().map(|account| account.id).collect();
+        assert_eq!(ids, vec![2, 4, 6]);
+
+        let next = PageRequest::next(first.next.unwrap(), 3);
+        let second = repo.list(&tenant, &frozen, next).unwrap();
+        let ids: Vec<_> = second.items.iter().map(|account| account.id).collect();
+        assert_eq!(ids, vec![8, 10]);
+        assert_eq!(second.next, None);
+    }
+
+    #[test]
+    fn test_will_find_accounts_by_number_through_the_index() {
+        let repo = InMemoryRepo::new();
+        let (acme, globex) = (TenantId::new("acme"), TenantId::new("globex"));
+        let numbering = AccountNumbering::new("GB", "NWBK").unwrap();
+        let number = numbering.issue(7).unwrap();
+        let account = Account::builder(7, 1)
+            .tenant(acme.clone())
+            .number(number.clone())
+            .build();
+        repo.create(&acme, account.clone()).unwrap();
+        repo.create(&acme, Account::builder(8, 1).tenant(acme.clone()).build())
+            .unwrap();
+
+        assert_eq!(repo.get_by_number(&acme, &number).unwrap(), Some(account));
+        assert_eq!(repo.get_by_number(&globex, &number).unwrap(), None);
+        let unknown = numbering.issue(8).unwrap();
+        assert_eq!(repo.get_by_number(&acme, &unknown).unwrap(), None);
+
+        let result: Result<(), RepoError> = repo.modify(&acme, &[8], |accounts| {
+            accounts[0].number = Some(unknown.clone());
+            Ok(())
+        });
+        result.unwrap();
+        assert_eq!(repo.get_by_number(&acme, &unknown).unwrap().unwrap().id, 8);
+    }
+
+    #[test]
+    fn test_will_keep_accounts_and_customers_of_each_tenant_apart() {
+        let repo = InMemoryRepo::new();
+        let (acme, globex) = (TenantId::new("acme"), TenantId::new("globex"));
+        let account = Account::builder(1, 1).tenant(acme.clone()).build();
+        repo.create(&acme, account.clone()).unwrap();
+
+        assert_eq!(repo.get(&acme, 1).unwrap(), Some(account.clone()));
+        assert_eq!(repo.get(&globex, 1).unwrap(), None);
+        let all = repo
+            .list(&globex, &AccountQuery::default(), PageRequest::first(10))
+            .unwrap();
+        assert!(all.items.is_empty());
+
+        let stolen: Result<(), RepoError> = repo.modify(&globex, &[1], |_| Ok(()));
+        assert_eq!(stolen, Err(RepoError::NotFound(1)));
+        let mismatch = || RepoError::TenantMismatch {
+            account_id: 1,
+            tenant: globex.clone(),
+        };
+        let overwrite = Account::builder(1, 1).tenant(globex.clone()).build();
+        assert_eq!(repo.update(&globex, overwrite), Err(mismatch()));
+        assert_eq!(repo.create(&globex, account.clone()), Err(mismatch()));
+        assert_eq!(repo.get(&acme, 1).unwrap(), Some(account));
+
+        let customers = InMemoryCustomerStore::new();
+        customers
+            .save(&acme, Customer::builder(1).name("Ada").build())
+            .unwrap();
+        customers
+            .save(&globex, Customer::builder(1).name("Grace").build())
+            .unwrap();
+        assert_eq!(customers.get(&acme, 1).unwrap().unwrap().name, "Ada");
+        assert_eq!(customers.list(&globex).unwrap().len(), 1);
+        assert_eq!(customers.list(&TenantId::default()).unwrap(), vec![]);
+    }
+}
@@ -0,0 +1,710 @@
+use std::{str::FromStr, time::SystemTime};
+
+use bank_core::{
+    account::{Account, AccountHolder, AccountStatus, HolderRole, Money, Transaction},
+    account_number::AccountNumber,
+    approval::PendingApproval,
+    audit::LedgerEntry,
+    cash::{
+        CashBalance, CashCount, CashDirection, CashDrawer, CashMovement, Denomination,
+        DrawerStatus, ShiftReport,
+    },
+    context::{Actor, RequestContext},
+    customer::Customer,
+    errors::{AppError, ErrorResponse},
+    interbank::{
+        ClearingEvent, ClearingOutcome, ExternalPayment, ExternalPaymentStatus, ExternalTransfer,
+        OutgoingPayment,
+    },
+    loan::{
+        AmortizationMethod, Installment, Loan, LoanStatus, LoanTerms, PayoffQuote, RepaymentSplit,
+    },
+    notification::{AlertCondition, AlertRule},
+    term_deposit::{
+        MaturityInstruction, MaturityOutcome, TermDeposit, TermDepositPayout, TermDepositStatus,
+        TermDepositTerms,
+    },
+};
+use bank_infra::iso20022::pain001::PaymentFileHeader;
+use bank_services::bank::ClearingResults;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use tonic::{
+    Code, Request, Status,
+    metadata::{MetadataKey, MetadataMap, MetadataValue},
+};
+
+use crate::pb;
+
+/// Metadata entry naming the caller, as `customer:<id>`, `teller:<id>` or
+/// `system:<name>`.
+pub const ACTOR_HEADER: &str = "x-actor";
+/// Metadata entry carrying the id the call is audited under.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+/// Metadata entry carrying [`bank_core::errors::DomainError::code`] on errors.
+pub const ERROR_CODE_HEADER: &str = "x-error-code";
+
+/// The caller and request id from the request metadata.
+///
+/// This is a trust boundary: the caller is whoever [`ACTOR_HEADER`] says,
+/// with nothing to prove it. The server must only be reachable over an
+/// authenticated channel by services that have authenticated the end user
+/// themselves.
+pub fn context<T>(request: &Request<T>) -> Result<RequestContext, Status> {
+    let metadata = request.metadata();
+    let text = |key| metadata.get(key).and_then(|value| value.to_str().ok());
+
+    let actor = text(ACTOR_HEADER)
+        .ok_or_else(|| Status::unauthenticated(format!("{ACTOR_HEADER} metadata is required")))
+        .and_then(parse_actor)?;
+    let request_id = text(REQUEST_ID_HEADER).unwrap_or("rpc");
+    Ok(RequestContext::new(actor, request_id))
+}
+
+pub fn parse_actor(value: &str) -> Result<Actor, Status> {
+    let invalid = || Status::unauthenticated(format!("{ACTOR_HEADER}: {value:?} is not a caller"));
+    let (kind, id) = value.split_once(':').ok_or_else(invalid)?;
+    match kind {
+        "customer" => id.parse().map(Actor::Customer).map_err(|_| invalid()),
+        "teller" => id.parse().map(Actor::Teller).map_err(|_| invalid()),
+        "system" if !id.is_empty() => Ok(Actor::System(id.into())),
+        _ => Err(invalid()),
+    }
+}
+
+/// Reports `err` with the status matching its HTTP status, the error code
+/// in [`ERROR_CODE_HEADER`] and each detail in an `x-error-<name>` entry.
+pub fn status(err: AppError) -> Status {
+    let response = ErrorResponse::from(&err);
+    let code = match response.status {
+        404 => Code::NotFound,
+        403 => Code::PermissionDenied,
+        202 | 409 => Code::FailedPrecondition,
+        422 => Code::InvalidArgument,
+        501 => Code::Unimplemented,
+        503 => Code::Unavailable,
+        _ => Code::Internal,
+    };
+
+    let mut metadata = MetadataMap::new();
+    metadata.insert(ERROR_CODE_HEADER, MetadataValue::from_static(response.code));
+    for (name, value) in &response.details {
+        let key = format!("x-error-{}", name.replace('_', "-"));
+        if let (Ok(key), Ok(value)) = (MetadataKey::from_bytes(key.as_bytes()), value.parse()) {
+            metadata.insert(key, value);
+        }
+    }
+    Status::with_metadata(code, response.message, metadata)
+}
+
+/// `err` as an item of a batch result, coded as [`status`] would code it.
+pub fn failure(err: &AppError) -> pb::Failure {
+    let response = ErrorResponse::from(err);
+    pb::Failure {
+        code: response.code.into(),
+        message: response.message,
+    }
+}
+
+pub fn parse_money(field: &str, value: &str) -> Result<Money, Status> {
+    Decimal::from_str(value)
+        .map(Money)
+        .map_err(|_| Status::invalid_argument(format!("{field}: {value:?} is not an amount")))
+}
+
+pub fn parse_rate(field: &str, value: &str) -> Result<Decimal, Status> {
+    Decimal::from_str(value)
+        .map_err(|_| Status::invalid_argument(format!("{field}: {value:?} is not a rate")))
+}
+
+pub fn parse_date(field: &str, value: &str) -> Result<NaiveDate, Status> {
+    NaiveDate::from_str(value)
+        .map_err(|_| Status::invalid_argument(format!("{field}: {value:?} is not a date")))
+}
+
+pub fn transaction(txn: Option<pb::Transaction>) -> Result<Transaction, Status> {
+    match txn.and_then(|txn| txn.kind) {
+        Some(pb::transaction::Kind::Deposit(amount)) => {
+            Ok(Transaction::Deposit(parse_money("deposit", &amount)?))
+        }
+        Some(pb::transaction::Kind::Withdraw(amount)) => {
+            Ok(Transaction::Withdraw(parse_money("withdraw", &amount)?))
+        }
+        Some(pb::transaction::Kind::Transfer(transfer)) => Ok(Transaction::Transfer {
+            to: transfer.to,
+            amount: parse_money("transfer.amount", &transfer.amount)?,
+        }),
+        None => Err(Status::invalid_argument("transaction is required")),
+    }
+}
+
+pub fn holder_role(role: i32) -> Result<HolderRole, Status> {
+    match pb::HolderRole::try_from(role) {
+        Ok(pb::HolderRole::Owner) => Ok(HolderRole::Owner),
+        Ok(pb::HolderRole::Signatory) => Ok(HolderRole::Signatory),
+        Ok(pb::HolderRole::Viewer) => Ok(HolderRole::Viewer),
+        _ => Err(Status::invalid_argument(format!(
+            "role: {role} is not a holder role"
+        ))),
+    }
+}
+
+pub fn account_status(status: i32) -> Result<AccountStatus, Status> {
+    match pb::AccountStatus::try_from(status) {
+        Ok(pb::AccountStatus::Active) => Ok(AccountStatus::Active),
+        Ok(pb::AccountStatus::Frozen) => Ok(AccountStatus::Frozen),
+        Ok(pb::AccountStatus::Closed) => Ok(AccountStatus::Closed),
+        _ => Err(Status::invalid_argument(format!(
+            "status: {status} is not an account status"
+        ))),
+    }
+}
+
+pub fn loan_terms(terms: Option<pb::LoanTerms>) -> Result<LoanTerms, Status> {
+    let terms = terms.ok_or_else(|| Status::invalid_argument("terms is required"))?;
+    let method = match pb::AmortizationMethod::try_from(terms.method) {
+        Ok(pb::AmortizationMethod::Annuity) => AmortizationMethod::Annuity,
+        Ok(pb::AmortizationMethod::EqualPrincipal) => AmortizationMethod::EqualPrincipal,
+        _ => {
+            return Err(Status::invalid_argument(format!(
+                "terms.method: {} is not an amortization method",
+                terms.method
+            )));
+        }
+    };
+
+    Ok(LoanTerms {
+        principal: parse_money("terms.principal", &terms.principal)?,
+        annual_rate: parse_rate("terms.annual_rate", &terms.annual_rate)?,
+        term_months: terms.term_months,
+        method,
+        start: parse_date("terms.start", &terms.start)?,
+        late_fee: parse_money("terms.late_fee", &terms.late_fee)?,
+    })
+}
+
+pub fn term_deposit_terms(terms: Option<pb::TermDepositTerms>) -> Result<TermDepositTerms, Status> {
+    let terms = terms.ok_or_else(|| Status::invalid_argument("terms is required"))?;
+    let instruction = match pb::MaturityInstruction::try_from(terms.instruction) {
+        Ok(pb::MaturityInstruction::Payout) => MaturityInstruction::Payout,
+        Ok(pb::MaturityInstruction::RolloverPrincipal) => MaturityInstruction::RolloverPrincipal,
+        Ok(pb::MaturityInstruction::RolloverAll) => MaturityInstruction::RolloverAll,
+        _ => {
+            return Err(Status::invalid_argument(format!(
+                "terms.instruction: {} is not a maturity instruction",
+                terms.instruction
+            )));
+        }
+    };
+
+    Ok(TermDepositTerms {
+        principal: parse_money("terms.principal", &terms.principal)?,
+        annual_rate: parse_rate("terms.annual_rate", &terms.annual_rate)?,
+        term_months: terms.term_months,
+        penalty_days: terms.penalty_days,
+        instruction,
+    })
+}
+
+/// The notes and coins in `pieces`, adding up repeated denominations.
+pub fn cash_count(field: &str, pieces: Vec<pb::CashPieces>) -> Result<CashCount, Status> {
+    let mut count = CashCount::new();
+    for entry in pieces {
+        let denomination = Denomination(parse_money(field, &entry.denomination)?.0);
+        *count.entry(denomination).or_default() += entry.pieces;
+    }
+    Ok(count)
+}
+
+pub fn cash_pieces(count: &CashCount) -> Vec<pb::CashPieces> {
+    count
+        .iter()
+        .map(|(denomination, pieces)| pb::CashPieces {
+            denomination: denomination.to_string(),
+            pieces: *pieces,
+        })
+        .collect()
+}
+
+pub fn alert_condition(condition: Option<pb::AlertCondition>) -> Result<AlertCondition, Status> {
+    match condition.and_then(|condition| condition.kind) {
+        Some(pb::alert_condition::Kind::LargeWithdrawal(amount)) => Ok(
+            AlertCondition::LargeWithdrawal(parse_money("large_withdrawal", &amount)?),
+        ),
+        Some(pb::alert_condition::Kind::LowBalance(amount)) => Ok(AlertCondition::LowBalance(
+            parse_money("low_balance", &amount)?,
+        )),
+        None => Err(Status::invalid_argument("condition is required")),
+    }
+}
+
+pub fn external_transfer(
+    transfer: Option<pb::ExternalTransfer>,
+) -> Result<ExternalTransfer, Status> {
+    let transfer = transfer.ok_or_else(|| Status::invalid_argument("transfer is required"))?;
+    Ok(ExternalTransfer {
+        creditor_name: transfer.creditor_name,
+        creditor_account: AccountNumber::parse(&transfer.creditor_account)
+            .map_err(|err| status(err.into()))?,
+        creditor_agent: transfer.creditor_agent,
+        amount: parse_money("transfer.amount", &transfer.amount)?,
+        remittance: transfer.remittance,
+    })
+}
+
+pub fn clearing_event(event: pb::ClearingEvent) -> Result<ClearingEvent, Status> {
+    let outcome = match event.outcome {
+        Some(pb::clearing_event::Outcome::Settled(pb::Ack {})) => ClearingOutcome::Settled,
+        Some(pb::clearing_event::Outcome::Rejected(reason)) => ClearingOutcome::Rejected(reason),
+        Some(pb::clearing_event::Outcome::Returned(reason)) => ClearingOutcome::Returned(reason),
+        None => return Err(Status::invalid_argument("outcome is required")),
+    };
+    Ok(ClearingEvent {
+        end_to_end_id: event.end_to_end_id,
+        outcome,
+    })
+}
+
+pub fn payment_file_header(
+    request: pb::ExportPaymentFileRequest,
+) -> Result<PaymentFileHeader, Status> {
+    let created_at = system_time("created_at", request.created_at)?;
+    Ok(PaymentFileHeader {
+        message_id: request.message_id,
+        created_at: DateTime::<Utc>::from(created_at).naive_utc(),
+        initiating_party: request.initiating_party,
+        debtor_agent: request.debtor_agent,
+        currency: request.currency,
+    })
+}
+
+pub fn clearing_results(results: &ClearingResults) -> pb::ClearingResults {
+    let dead_lettered = results
+        .dead_lettered
+        .iter()
+        .map(|letter| pb::ClearingResult {
+            end_to_end_id: letter.event.end_to_end_id.clone(),
+            result: Some(pb::clearing_result::Result::Failure(pb::Failure {
+                code: letter.code.clone(),
+                message: letter.message.clone(),
+            })),
+        })
+        .collect();
+    let results = results
+        .results
+        .iter()
+        .map(|(end_to_end_id, result)| {
+            let result = match result {
+                Ok(status) => pb::clearing_result::Result::Status(status.into()),
+                Err(err) => pb::clearing_result::Result::Failure(failure(err)),
+            };
+            pb::ClearingResult {
+                end_to_end_id: end_to_end_id.clone(),
+                result: Some(result),
+            }
+        })
+        .collect();
+    pb::ClearingResults {
+        results,
+        dead_lettered,
+    }
+}
+
+pub fn timestamp(at: SystemTime) -> prost_types::Timestamp {
+    at.into()
+}
+
+pub fn system_time(field: &str, at: Option<prost_types::Timestamp>) -> Result<SystemTime, Status> {
+    at.ok_or_else(|| Status::invalid_argument(format!("{field} is required")))?
+        .try_into()
+        .map_err(|_| Status::invalid_argument(format!("{field} is out of range")))
+}
+
+impl From<&Account> for pb::Account {
+    fn from(account: &Account) -> Self {
+        pb::Account {
+            id: account.id,
+            tenant: account.tenant.to_string(),
+            number: account.number.as_ref().map(|number| number.as_str().into()),
+            owner: account.owner,
+            balance: account.balance.to_string(),
+            status: pb::AccountStatus::from(&account.status).into(),
+            holders: account.holders.iter().map(Into::into).collect(),
+            approval_threshold: account
+                .approval_threshold
+                .map(|threshold| threshold.to_string()),
+            product: account.product.clone(),
+        }
+    }
+}
+
+impl From<&AccountStatus> for pb::AccountStatus {
+    fn from(status: &AccountStatus) -> Self {
+        match status {
+            AccountStatus::Active => pb::AccountStatus::Active,
+            AccountStatus::Frozen => pb::AccountStatus::Frozen,
+            AccountStatus::Closed => pb::AccountStatus::Closed,
+        }
+    }
+}
+
+impl From<&AccountHolder> for pb::AccountHolder {
+    fn from(holder: &AccountHolder) -> Self {
+        let role = match holder.role {
+            HolderRole::Owner => pb::HolderRole::Owner,
+            HolderRole::Signatory => pb::HolderRole::Signatory,
+            HolderRole::Viewer => pb::HolderRole::Viewer,
+        };
+        pb::AccountHolder {
+            customer: holder.customer,
+            role: role.into(),
+        }
+    }
+}
+
+impl From<&Transaction> for pb::Transaction {
+    fn from(txn: &Transaction) -> Self {
+        let kind = match txn {
+            Transaction::Deposit(amount) => pb::transaction::Kind::Deposit(amount.to_string()),
+            Transaction::Withdraw(amount) => pb::transaction::Kind::Withdraw(amount.to_string()),
+            Transaction::Transfer { to, amount } => pb::transaction::Kind::Transfer(pb::Transfer {
+                to: *to,
+                amount: amount.to_string(),
+            }),
+        };
+        pb::Transaction { kind: Some(kind) }
+    }
+}
+
+impl From<&Actor> for pb::Actor {
+    fn from(actor: &Actor) -> Self {
+        let kind = match actor {
+            Actor::Customer(id) => pb::actor::Kind::Customer(*id),
+            Actor::Teller(id) => pb::actor::Kind::Teller(*id),
+            Actor::System(name) => pb::actor::Kind::System(name.clone()),
+        };
+        pb::Actor { kind: Some(kind) }
+    }
+}
+
+impl From<&PendingApproval> for pb::PendingApproval {
+    fn from(approval: &PendingApproval) -> Self {
+        pb::PendingApproval {
+            id: approval.id,
+            account_id: approval.account_id,
+            requested_by: Some((&approval.requested_by).into()),
+            transaction: Some((&approval.txn).into()),
+        }
+    }
+}
+
+impl From<&Customer> for pb::Customer {
+    fn from(customer: &Customer) -> Self {
+        pb::Customer {
+            id: customer.id,
+            name: customer.name.clone(),
+            email: customer.email.clone(),
+        }
+    }
+}
+
+impl From<pb::Customer> for Customer {
+    fn from(customer: pb::Customer) -> Self {
+        Customer {
+            id: customer.id,
+            name: customer.name,
+            email: customer.email,
+            erased_on: None,
+        }
+    }
+}
+
+impl From<&LedgerEntry> for pb::LedgerEvent {
+    fn from(entry: &LedgerEntry) -> Self {
+        pb::LedgerEvent {
+            seq: entry.seq,
+            at: Some(timestamp(entry.at)),
+            account_id: entry.account_id,
+            action: entry.action.clone(),
+            amount: entry.amount.to_string(),
+            balance: entry.balance.to_string(),
+        }
+    }
+}
+
+impl From<&LoanTerms> for pb::LoanTerms {
+    fn from(terms: &LoanTerms) -> Self {
+        let method = match terms.method {
+            AmortizationMethod::Annuity => pb::AmortizationMethod::Annuity,
+            AmortizationMethod::EqualPrincipal => pb::AmortizationMethod::EqualPrincipal,
+        };
+        pb::LoanTerms {
+            principal: terms.principal.to_string(),
+            annual_rate: terms.annual_rate.to_string(),
+            term_months: terms.term_months,
+            method: method.into(),
+            start: terms.start.to_string(),
+            late_fee: terms.late_fee.to_string(),
+        }
+    }
+}
+
+impl From<&Installment> for pb::Installment {
+    fn from(installment: &Installment) -> Self {
+        pb::Installment {
+            number: installment.number,
+            due: installment.due.to_string(),
+            payment: installment.payment.to_string(),
+            interest: installment.interest.to_string(),
+            principal: installment.principal.to_string(),
+            balance_after: installment.balance_after.to_string(),
+        }
+    }
+}
+
+impl From<&Loan> for pb::Loan {
+    fn from(loan: &Loan) -> Self {
+        let status = match loan.status {
+            LoanStatus::Approved => pb::LoanStatus::Approved,
+            LoanStatus::Active => pb::LoanStatus::Active,
+            LoanStatus::PaidOff => pb::LoanStatus::PaidOff,
+        };
+        pb::Loan {
+            id: loan.id,
+            borrower: loan.borrower,
+            account_id: loan.account_id,
+            terms: Some((&loan.terms).into()),
+            schedule: loan.schedule.iter().map(Into::into).collect(),
+            status: status.into(),
+            principal_paid: loan.principal_paid.to_string(),
+            principal_prepaid: loan.principal_prepaid.to_string(),
+            interest_paid: loan.interest_paid.to_string(),
+            fees_charged: loan.fees_charged.to_string(),
+            fees_paid: loan.fees_paid.to_string(),
+            late_installments: loan.late_installments.clone(),
+        }
+    }
+}
+
+impl From<&RepaymentSplit> for pb::RepaymentSplit {
+    fn from(split: &RepaymentSplit) -> Self {
+        pb::RepaymentSplit {
+            fees: split.fees.to_string(),
+            interest: split.interest.to_string(),
+            principal: split.principal.to_string(),
+        }
+    }
+}
+
+impl From<&PayoffQuote> for pb::PayoffQuote {
+    fn from(quote: &PayoffQuote) -> Self {
+        pb::PayoffQuote {
+            on: quote.on.to_string(),
+            principal: quote.principal.to_string(),
+            interest: quote.interest.to_string(),
+            fees: quote.fees.to_string(),
+            total: quote.total().to_string(),
+        }
+    }
+}
+
+impl From<&TermDepositTerms> for pb::TermDepositTerms {
+    fn from(terms: &TermDepositTerms) -> Self {
+        let instruction = match terms.instruction {
+            MaturityInstruction::Payout => pb::MaturityInstruction::Payout,
+            MaturityInstruction::RolloverPrincipal => pb::MaturityInstruction::RolloverPrincipal,
+            MaturityInstruction::RolloverAll => pb::MaturityInstruction::RolloverAll,
+        };
+        pb::TermDepositTerms {
+            principal: terms.principal.to_string(),
+            annual_rate: terms.annual_rate.to_string(),
+            term_months: terms.term_months,
+            penalty_days: terms.penalty_days,
+            instruction: instruction.into(),
+        }
+    }
+}
+
+impl From<&TermDepositStatus> for pb::TermDepositStatus {
+    fn from(status: &TermDepositStatus) -> Self {
+        let kind = match status {
+            TermDepositStatus::Active => pb::term_deposit_status::Kind::Active(pb::Ack {}),
+            TermDepositStatus::Matured => pb::term_deposit_status::Kind::Matured(pb::Ack {}),
+            TermDepositStatus::Withdrawn => pb::term_deposit_status::Kind::Withdrawn(pb::Ack {}),
+            TermDepositStatus::RolledOver(deposit_id) => {
+                pb::term_deposit_status::Kind::RolledOver(*deposit_id)
+            }
+        };
+        pb::TermDepositStatus { kind: Some(kind) }
+    }
+}
+
+impl From<&TermDeposit> for pb::TermDeposit {
+    fn from(deposit: &TermDeposit) -> Self {
+        pb::TermDeposit {
+            id: deposit.id,
+            owner: deposit.owner,
+            source_account: deposit.source_account,
+            terms: Some((&deposit.terms).into()),
+            start: deposit.start.to_string(),
+            maturity: deposit.maturity.to_string(),
+            status: Some((&deposit.status).into()),
+        }
+    }
+}
+
+impl From<&TermDepositPayout> for pb::TermDepositPayout {
+    fn from(payout: &TermDepositPayout) -> Self {
+        pb::TermDepositPayout {
+            principal: payout.principal.to_string(),
+            interest: payout.interest.to_string(),
+            penalty: payout.penalty.to_string(),
+            total: payout.total().to_string(),
+        }
+    }
+}
+
+impl From<&MaturityOutcome> for pb::MaturityOutcome {
+    fn from(outcome: &MaturityOutcome) -> Self {
+        pb::MaturityOutcome {
+            payout: Some((&outcome.payout).into()),
+            paid_out: outcome.paid_out.to_string(),
+            rolled_into: outcome.rolled_into,
+        }
+    }
+}
+
+impl From<&CashMovement> for pb::CashMovement {
+    fn from(movement: &CashMovement) -> Self {
+        let direction = match movement.direction {
+            CashDirection::In => pb::CashDirection::In,
+            CashDirection::Out => pb::CashDirection::Out,
+        };
+        pb::CashMovement {
+            teller: movement.teller,
+            account_id: movement.account_id,
+            direction: direction.into(),
+            notes: cash_pieces(&movement.notes),
+        }
+    }
+}
+
+impl From<&ShiftReport> for pb::ShiftReport {
+    fn from(report: &ShiftReport) -> Self {
+        let balance = match report.balance {
+            CashBalance::Balanced => pb::shift_report::Balance::Balanced(pb::Ack {}),
+            CashBalance::Over(amount) => pb::shift_report::Balance::Over(amount.to_string()),
+            CashBalance::Short(amount) => pb::shift_report::Balance::Short(amount.to_string()),
+        };
+        pb::ShiftReport {
+            drawer_id: report.drawer_id,
+            teller: report.teller,
+            opening: report.opening.to_string(),
+            deposits: report.deposits.to_string(),
+            withdrawals: report.withdrawals.to_string(),
+            expected: report.expected.to_string(),
+            counted: report.counted.to_string(),
+            variances: report
+                .variances
+                .iter()
+                .map(|(denomination, pieces)| pb::PieceVariance {
+                    denomination: denomination.to_string(),
+                    pieces: *pieces,
+                })
+                .collect(),
+            balance: Some(balance),
+        }
+    }
+}
+
+impl From<&CashDrawer> for pb::CashDrawer {
+    fn from(drawer: &CashDrawer) -> Self {
+        let status = match drawer.status {
+            DrawerStatus::Open => pb::DrawerStatus::Open,
+            DrawerStatus::Closed => pb::DrawerStatus::Closed,
+        };
+        pb::CashDrawer {
+            id: drawer.id,
+            teller: drawer.teller,
+            opened_on: drawer.opened_on.to_string(),
+            status: status.into(),
+            opening: cash_pieces(&drawer.opening),
+            expected: cash_pieces(&drawer.expected),
+            movements: drawer.movements.iter().map(Into::into).collect(),
+            shifts: drawer.shifts.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<&AlertRule> for pb::AlertRule {
+    fn from(rule: &AlertRule) -> Self {
+        let kind = match rule.condition {
+            AlertCondition::LargeWithdrawal(amount) => {
+                pb::alert_condition::Kind::LargeWithdrawal(amount.to_string())
+            }
+            AlertCondition::LowBalance(amount) => {
+                pb::alert_condition::Kind::LowBalance(amount.to_string())
+            }
+        };
+        pb::AlertRule {
+            id: rule.id,
+            customer: rule.customer,
+            account_id: rule.account_id,
+            condition: Some(pb::AlertCondition { kind: Some(kind) }),
+        }
+    }
+}
+
+impl From<&ExternalTransfer> for pb::ExternalTransfer {
+    fn from(transfer: &ExternalTransfer) -> Self {
+        pb::ExternalTransfer {
+            creditor_name: transfer.creditor_name.clone(),
+            creditor_account: transfer.creditor_account.as_str().into(),
+            creditor_agent: transfer.creditor_agent.clone(),
+            amount: transfer.amount.to_string(),
+            remittance: transfer.remittance.clone(),
+        }
+    }
+}
+
+impl From<&OutgoingPayment> for pb::OutgoingPayment {
+    fn from(payment: &OutgoingPayment) -> Self {
+        pb::OutgoingPayment {
+            end_to_end_id: payment.end_to_end_id.clone(),
+            debtor_account: payment.debtor_account,
+            debtor_name: payment.debtor_name.clone(),
+            debtor_number: payment.debtor_number.as_str().into(),
+            transfer: Some((&payment.transfer).into()),
+            requested_execution: payment.requested_execution.to_string(),
+        }
+    }
+}
+
+impl From<&ExternalPaymentStatus> for pb::PaymentStatus {
+    fn from(status: &ExternalPaymentStatus) -> Self {
+        let (state, reason) = match status {
+            ExternalPaymentStatus::Pending => (pb::PaymentState::Pending, None),
+            ExternalPaymentStatus::Submitted => (pb::PaymentState::Submitted, None),
+            ExternalPaymentStatus::Settled => (pb::PaymentState::Settled, None),
+            ExternalPaymentStatus::Rejected(reason) => {
+                (pb::PaymentState::Rejected, Some(reason.clone()))
+            }
+            ExternalPaymentStatus::Returned(reason) => {
+                (pb::PaymentState::Returned, Some(reason.clone()))
+            }
+        };
+        pb::PaymentStatus {
+            state: state.into(),
+            reason,
+        }
+    }
+}
+
+impl From<&ExternalPayment> for pb::ExternalPayment {
+    fn from(external: &ExternalPayment) -> Self {
+        pb::ExternalPayment {
+            payment: Some((&external.payment).into()),
+            status: Some((&external.status).into()),
+        }
+    }
+}
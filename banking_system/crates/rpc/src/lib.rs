@@ -0,0 +1,11 @@
+//! Binary RPC interface to [`bank_services::bank::Bank`] for internal
+//! services, described by `proto/bank/v1/bank.proto`.
+
+pub mod convert;
+pub mod server;
+
+/// Messages and stubs generated from the protobuf schema.
+pub mod pb {
+    #![allow(clippy::all)]
+    tonic::include_proto!("bank.v1");
+}
@@ -0,0 +1,1272 @@
+use std::{sync::Arc, time::Duration};
+
+use bank_core::{
+    account::{AccountRepository, Money},
+    account_number::{AccountNumber, AccountRef},
+    context::{Actor, RequestContext},
+    customer::CustomerId,
+    errors::{AppError, DomainError},
+    query::{AccountQuery, Cursor, PageRequest},
+    snapshot::BankSnapshot,
+};
+use bank_infra::iso20022::camt053::Camt053Document;
+use bank_services::bank::Bank;
+use tokio::{net::TcpListener, sync::mpsc};
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tonic::{Request, Response, Status, transport::Server};
+
+use crate::{
+    convert::{self, status},
+    pb::{
+        self,
+        bank_server::{self, BankServer},
+    },
+};
+
+/// How often a ledger tail looks for new movements once it has caught up.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Serves a [`Bank`] over gRPC. Calls run on the blocking pool, since bank
+/// operations take locks and may touch storage.
+pub struct BankService<R: AccountRepository> {
+    pub bank: Arc<Bank<R>>,
+    pub poll_interval: Duration,
+}
+
+impl<R: AccountRepository + Send + Sync + 'static> BankService<R> {
+    pub fn new(bank: Arc<Bank<R>>) -> Self {
+        Self {
+            bank,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    async fn call<T, F>(&self, f: F) -> Result<T, Status>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Bank<R>) -> Result<T, AppError> + Send + 'static,
+    {
+        let bank = Arc::clone(&self.bank);
+        tokio::task::spawn_blocking(move || f(&bank))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .map_err(status)
+    }
+}
+
+/// Serves `bank` on `listener` until the listener fails. Callers are not
+/// authenticated (see [`convert::context`]), so the listener must only
+/// accept connections from trusted internal services.
+pub async fn serve<R>(
+    listener: TcpListener,
+    bank: Arc<Bank<R>>,
+) -> Result<(), tonic::transport::Error>
+where
+    R: AccountRepository + Send + Sync + 'static,
+{
+    Server::builder()
+        .add_service(BankServer::new(BankService::new(bank)))
+        .serve_with_incoming(TcpListenerStream::new(listener))
+        .await
+}
+
+/// Batch jobs and back-office operations are for staff and the bank's own
+/// services, never customers.
+fn ensure_staff(ctx: &RequestContext) -> Result<(), Status> {
+    match ctx.actor {
+        Actor::Customer(_) => Err(Status::permission_denied(
+            "customers may not make this call",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Customers may only act on their own profile and account listings.
+fn ensure_self(ctx: &RequestContext, customer: CustomerId) -> Result<(), AppError> {
+    match ctx.actor {
+        Actor::Customer(caller) if caller != customer => {
+            Err(DomainError::CustomerAccessDenied { customer, caller }.into())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn money(field: &str, value: Option<String>) -> Result<Option<Money>, Status> {
+    value
+        .map(|value| convert::parse_money(field, &value))
+        .transpose()
+}
+
+#[tonic::async_trait]
+impl<R: AccountRepository + Send + Sync + 'static> bank_server::Bank for BankService<R> {
+    async fn create_account(
+        &self,
+        request: Request<pb::CreateAccountRequest>,
+    ) -> Result<Response<pb::CreateAccountResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        let pb::CreateAccountRequest { owner, product } = request.into_inner();
+
+        let account_id = self
+            .call(move |bank| bank.open_account(&ctx, owner, product.as_deref()))
+            .await?;
+        Ok(Response::new(pb::CreateAccountResponse { account_id }))
+    }
+
+    async fn get_account(
+        &self,
+        request: Request<pb::AccountRequest>,
+    ) -> Result<Response<pb::Account>, Status> {
+        let ctx = convert::context(&request)?;
+        let account_id = request.into_inner().account_id;
+
+        let account = self
+            .call(move |bank| bank.account_as(&ctx, account_id))
+            .await?;
+        Ok(Response::new((&account).into()))
+    }
+
+    async fn find_account(
+        &self,
+        request: Request<pb::FindAccountRequest>,
+    ) -> Result<Response<pb::Account>, Status> {
+        let ctx = convert::context(&request)?;
+        let number =
+            AccountNumber::parse(&request.into_inner().number).map_err(|err| status(err.into()))?;
+
+        let account = self
+            .call(move |bank| {
+                let account = bank.find_account(&AccountRef::Number(number))?;
+                bank.account_as(&ctx, account.id)
+            })
+            .await?;
+        Ok(Response::new((&account).into()))
+    }
+
+    async fn list_accounts(
+        &self,
+        request: Request<pb::ListAccountsRequest>,
+    ) -> Result<Response<pb::ListAccountsResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+
+        let owner = match (&ctx.actor, request.owner) {
+            (Actor::Customer(caller), None) => Some(*caller),
+            (_, owner) => owner,
+        };
+        let mut query = AccountQuery::default();
+        if let Some(owner) = owner {
+            ensure_self(&ctx, owner).map_err(status)?;
+            query = query.owner(owner);
+        }
+        if let Some(account_status) = request.status {
+            query = query.status(convert::account_status(account_status)?);
+        }
+        if let Some(min) = money("min_balance", request.min_balance)? {
+            query = query.min_balance(min);
+        }
+        if let Some(max) = money("max_balance", request.max_balance)? {
+            query = query.max_balance(max);
+        }
+
+        let limit = match request.limit {
+            0 => PageRequest::default().limit,
+            limit => limit as usize,
+        };
+        let page = match request.cursor {
+            Some(cursor) => PageRequest::next(
+                cursor.parse::<Cursor>().map_err(Status::invalid_argument)?,
+                limit,
+            ),
+            None => PageRequest::first(limit),
+        };
+
+        let page = self
+            .call(move |bank| bank.list_accounts(&query, page))
+            .await?;
+        Ok(Response::new(pb::ListAccountsResponse {
+            accounts: page.items.iter().map(Into::into).collect(),
+            next_cursor: page.next.map(|cursor| cursor.to_string()),
+        }))
+    }
+
+    async fn get_account_at(
+        &self,
+        request: Request<pb::AccountAtRequest>,
+    ) -> Result<Response<pb::Account>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let at = convert::system_time("at", request.at)?;
+
+        let account = self
+            .call(move |bank| {
+                bank.account_as(&ctx, request.account_id)?;
+                bank.account_at(request.account_id, at)
+            })
+            .await?;
+        Ok(Response::new((&account).into()))
+    }
+
+    async fn process(
+        &self,
+        request: Request<pb::ProcessRequest>,
+    ) -> Result<Response<pb::BalanceResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let txn = convert::transaction(request.transaction)?;
+
+        let balance = self
+            .call(move |bank| bank.process_as(&ctx, request.account_id, txn))
+            .await?;
+        Ok(Response::new(pb::BalanceResponse {
+            balance: balance.to_string(),
+        }))
+    }
+
+    async fn approve(
+        &self,
+        request: Request<pb::ApproveRequest>,
+    ) -> Result<Response<pb::BalanceResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        let approval_id = request.into_inner().approval_id;
+
+        let balance = self
+            .call(move |bank| bank.approve(&ctx, approval_id))
+            .await?;
+        Ok(Response::new(pb::BalanceResponse {
+            balance: balance.to_string(),
+        }))
+    }
+
+    async fn list_pending_approvals(
+        &self,
+        request: Request<pb::AccountRequest>,
+    ) -> Result<Response<pb::ListPendingApprovalsResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        let account_id = request.into_inner().account_id;
+
+        let approvals = self
+            .call(move |bank| {
+                bank.account_as(&ctx, account_id)?;
+                Ok(bank.pending_approvals(account_id))
+            })
+            .await?;
+        Ok(Response::new(pb::ListPendingApprovalsResponse {
+            approvals: approvals.iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn freeze(
+        &self,
+        request: Request<pb::AccountRequest>,
+    ) -> Result<Response<pb::Ack>, Status> {
+        let ctx = convert::context(&request)?;
+        let account_id = request.into_inner().account_id;
+
+        self.call(move |bank| bank.freeze_as(&ctx, account_id))
+            .await?;
+        Ok(Response::new(pb::Ack {}))
+    }
+
+    async fn close(
+        &self,
+        request: Request<pb::AccountRequest>,
+    ) -> Result<Response<pb::Ack>, Status> {
+        let ctx = convert::context(&request)?;
+        let account_id = request.into_inner().account_id;
+
+        self.call(move |bank| bank.close_as(&ctx, account_id))
+            .await?;
+        Ok(Response::new(pb::Ack {}))
+    }
+
+    async fn add_holder(
+        &self,
+        request: Request<pb::AddHolderRequest>,
+    ) -> Result<Response<pb::Ack>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let role = convert::holder_role(request.role)?;
+
+        self.call(move |bank| bank.add_holder(&ctx, request.account_id, request.customer, role))
+            .await?;
+        Ok(Response::new(pb::Ack {}))
+    }
+
+    async fn remove_holder(
+        &self,
+        request: Request<pb::RemoveHolderRequest>,
+    ) -> Result<Response<pb::Ack>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+
+        self.call(move |bank| bank.remove_holder(&ctx, request.account_id, request.customer))
+            .await?;
+        Ok(Response::new(pb::Ack {}))
+    }
+
+    async fn set_approval_threshold(
+        &self,
+        request: Request<pb::SetApprovalThresholdRequest>,
+    ) -> Result<Response<pb::Ack>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let threshold = money("threshold", request.threshold)?;
+
+        self.call(move |bank| bank.set_approval_threshold(&ctx, request.account_id, threshold))
+            .await?;
+        Ok(Response::new(pb::Ack {}))
+    }
+
+    async fn register_customer(
+        &self,
+        request: Request<pb::Customer>,
+    ) -> Result<Response<pb::Ack>, Status> {
+        let ctx = convert::context(&request)?;
+        let customer = request.into_inner();
+
+        self.call(move |bank| {
+            ensure_self(&ctx, customer.id)?;
+            bank.register_customer(&ctx, customer.into())
+        })
+        .await?;
+        Ok(Response::new(pb::Ack {}))
+    }
+
+    async fn get_customer(
+        &self,
+        request: Request<pb::CustomerRequest>,
+    ) -> Result<Response<pb::Customer>, Status> {
+        let ctx = convert::context(&request)?;
+        let customer_id = request.into_inner().customer_id;
+
+        let customer = self
+            .call(move |bank| {
+                ensure_self(&ctx, customer_id)?;
+                bank.customer(customer_id)
+            })
+            .await?;
+        Ok(Response::new((&customer).into()))
+    }
+
+    type TailLedgerStream = ReceiverStream<Result<pb::LedgerEvent, Status>>;
+
+    /// Customers must name an account they can see; tellers and the bank
+    /// itself may follow every account.
+    async fn tail_ledger(
+        &self,
+        request: Request<pb::TailLedgerRequest>,
+    ) -> Result<Response<Self::TailLedgerStream>, Status> {
+        let ctx = convert::context(&request)?;
+        let pb::TailLedgerRequest {
+            account_id,
+            after_seq,
+        } = request.into_inner();
+
+        match (&ctx.actor, account_id) {
+            (Actor::Customer(_), None) => {
+                return Err(Status::permission_denied(
+                    "customers must name an account to tail",
+                ));
+            }
+            (_, Some(account_id)) => {
+                self.call(move |bank| bank.account_as(&ctx, account_id))
+                    .await?;
+            }
+            (_, None) => {}
+        }
+
+        let (tx, rx) = mpsc::channel(64);
+        let bank = Arc::clone(&self.bank);
+        let poll_interval = self.poll_interval;
+        tokio::spawn(async move {
+            let mut seq = after_seq;
+            while !tx.is_closed() {
+                let entries = match bank.audit.ledger_after(seq) {
+                    Ok(entries) => entries,
+                    Err(err) => {
+                        let _ = tx.send(Err(status(err.into()))).await;
+                        return;
+                    }
+                };
+
+                for entry in entries {
+                    seq = entry.seq;
+                    if account_id.is_some_and(|id| id != entry.account_id) {
+                        continue;
+                    }
+                    if tx.send(Ok((&entry).into())).await.is_err() {
+                        return;
+                    }
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn originate_loan(
+        &self,
+        request: Request<pb::OriginateLoanRequest>,
+    ) -> Result<Response<pb::OriginateLoanResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let terms = convert::loan_terms(request.terms)?;
+
+        let loan_id = self
+            .call(move |bank| bank.originate_loan(&ctx, request.account_id, terms))
+            .await?;
+        Ok(Response::new(pb::OriginateLoanResponse { loan_id }))
+    }
+
+    async fn disburse_loan(
+        &self,
+        request: Request<pb::LoanRequest>,
+    ) -> Result<Response<pb::BalanceResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        let loan_id = request.into_inner().loan_id;
+
+        let balance = self
+            .call(move |bank| bank.disburse_loan(&ctx, loan_id))
+            .await?;
+        Ok(Response::new(pb::BalanceResponse {
+            balance: balance.to_string(),
+        }))
+    }
+
+    async fn repay_loan(
+        &self,
+        request: Request<pb::RepayLoanRequest>,
+    ) -> Result<Response<pb::RepaymentSplit>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let amount = convert::parse_money("amount", &request.amount)?;
+        let on = convert::parse_date("on", &request.on)?;
+
+        let split = self
+            .call(move |bank| bank.repay_loan(&ctx, request.loan_id, amount, on))
+            .await?;
+        Ok(Response::new((&split).into()))
+    }
+
+    async fn assess_late_fees(
+        &self,
+        request: Request<pb::LoanDateRequest>,
+    ) -> Result<Response<pb::AmountResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        ensure_staff(&ctx)?;
+        let request = request.into_inner();
+        let on = convert::parse_date("on", &request.on)?;
+
+        let fees = self
+            .call(move |bank| bank.assess_late_fees(&ctx, request.loan_id, on))
+            .await?;
+        Ok(Response::new(pb::AmountResponse {
+            amount: fees.to_string(),
+        }))
+    }
+
+    async fn get_loan(
+        &self,
+        request: Request<pb::LoanRequest>,
+    ) -> Result<Response<pb::Loan>, Status> {
+        let ctx = convert::context(&request)?;
+        let loan_id = request.into_inner().loan_id;
+
+        let loan = self
+            .call(move |bank| {
+                let loan = bank.loan(loan_id)?;
+                bank.account_as(&ctx, loan.account_id)?;
+                Ok(loan)
+            })
+            .await?;
+        Ok(Response::new((&loan).into()))
+    }
+
+    async fn get_payoff_quote(
+        &self,
+        request: Request<pb::LoanDateRequest>,
+    ) -> Result<Response<pb::PayoffQuote>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let on = convert::parse_date("on", &request.on)?;
+
+        let quote = self
+            .call(move |bank| {
+                let loan = bank.loan(request.loan_id)?;
+                bank.account_as(&ctx, loan.account_id)?;
+                Ok(loan.payoff_quote(on))
+            })
+            .await?;
+        Ok(Response::new((&quote).into()))
+    }
+
+    async fn open_term_deposit(
+        &self,
+        request: Request<pb::OpenTermDepositRequest>,
+    ) -> Result<Response<pb::OpenTermDepositResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let terms = convert::term_deposit_terms(request.terms)?;
+
+        let deposit_id = self
+            .call(move |bank| bank.open_term_deposit(&ctx, request.source_account, terms))
+            .await?;
+        Ok(Response::new(pb::OpenTermDepositResponse { deposit_id }))
+    }
+
+    async fn withdraw_term_deposit(
+        &self,
+        request: Request<pb::TermDepositRequest>,
+    ) -> Result<Response<pb::TermDepositPayout>, Status> {
+        let ctx = convert::context(&request)?;
+        let deposit_id = request.into_inner().deposit_id;
+
+        let payout = self
+            .call(move |bank| bank.withdraw_term_deposit(&ctx, deposit_id))
+            .await?;
+        Ok(Response::new((&payout).into()))
+    }
+
+    async fn get_term_deposit(
+        &self,
+        request: Request<pb::TermDepositRequest>,
+    ) -> Result<Response<pb::TermDeposit>, Status> {
+        let ctx = convert::context(&request)?;
+        let deposit_id = request.into_inner().deposit_id;
+
+        let deposit = self
+            .call(move |bank| {
+                let deposit = bank.term_deposit(deposit_id)?;
+                bank.account_as(&ctx, deposit.source_account)?;
+                Ok(deposit)
+            })
+            .await?;
+        Ok(Response::new((&deposit).into()))
+    }
+
+    async fn process_maturities(
+        &self,
+        request: Request<pb::ProcessMaturitiesRequest>,
+    ) -> Result<Response<pb::ProcessMaturitiesResponse>, Status> {
+        ensure_staff(&convert::context(&request)?)?;
+
+        let results = self.call(|bank| Ok(bank.process_maturities())).await?;
+        let results = results
+            .iter()
+            .map(|(deposit_id, result)| {
+                let result = match result {
+                    Ok(outcome) => pb::maturity_result::Result::Outcome(outcome.into()),
+                    Err(err) => pb::maturity_result::Result::Failure(convert::failure(err)),
+                };
+                pb::MaturityResult {
+                    deposit_id: *deposit_id,
+                    result: Some(result),
+                }
+            })
+            .collect();
+        Ok(Response::new(pb::ProcessMaturitiesResponse { results }))
+    }
+
+    async fn open_drawer(
+        &self,
+        request: Request<pb::OpenDrawerRequest>,
+    ) -> Result<Response<pb::OpenDrawerResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        let float = convert::cash_count("float", request.into_inner().float)?;
+
+        let drawer_id = self.call(move |bank| bank.open_drawer(&ctx, float)).await?;
+        Ok(Response::new(pb::OpenDrawerResponse { drawer_id }))
+    }
+
+    async fn process_cash(
+        &self,
+        request: Request<pb::ProcessCashRequest>,
+    ) -> Result<Response<pb::BalanceResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let txn = convert::transaction(request.transaction)?;
+        let notes = convert::cash_count("notes", request.notes)?;
+
+        let balance = self
+            .call(move |bank| {
+                bank.process_cash(&ctx, request.drawer_id, request.account_id, txn, notes)
+            })
+            .await?;
+        Ok(Response::new(pb::BalanceResponse {
+            balance: balance.to_string(),
+        }))
+    }
+
+    async fn hand_off_drawer(
+        &self,
+        request: Request<pb::HandOffDrawerRequest>,
+    ) -> Result<Response<pb::ShiftReport>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let counted = convert::cash_count("counted", request.counted)?;
+
+        let report = self
+            .call(move |bank| bank.hand_off_drawer(&ctx, request.drawer_id, request.to, counted))
+            .await?;
+        Ok(Response::new((&report).into()))
+    }
+
+    async fn close_drawer(
+        &self,
+        request: Request<pb::CloseDrawerRequest>,
+    ) -> Result<Response<pb::ShiftReport>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let counted = convert::cash_count("counted", request.counted)?;
+
+        let report = self
+            .call(move |bank| bank.close_drawer(&ctx, request.drawer_id, counted))
+            .await?;
+        Ok(Response::new((&report).into()))
+    }
+
+    async fn get_cash_drawer(
+        &self,
+        request: Request<pb::DrawerRequest>,
+    ) -> Result<Response<pb::CashDrawer>, Status> {
+        ensure_staff(&convert::context(&request)?)?;
+        let drawer_id = request.into_inner().drawer_id;
+
+        let drawer = self.call(move |bank| bank.cash_drawer(drawer_id)).await?;
+        Ok(Response::new((&drawer).into()))
+    }
+
+    async fn add_alert_rule(
+        &self,
+        request: Request<pb::AddAlertRuleRequest>,
+    ) -> Result<Response<pb::AddAlertRuleResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let condition = convert::alert_condition(request.condition)?;
+
+        let rule_id = self
+            .call(move |bank| {
+                bank.add_alert_rule(&ctx, request.customer, request.account_id, condition)
+            })
+            .await?;
+        Ok(Response::new(pb::AddAlertRuleResponse { rule_id }))
+    }
+
+    async fn remove_alert_rule(
+        &self,
+        request: Request<pb::AlertRuleRequest>,
+    ) -> Result<Response<pb::Ack>, Status> {
+        let ctx = convert::context(&request)?;
+        let rule_id = request.into_inner().rule_id;
+
+        self.call(move |bank| bank.remove_alert_rule(&ctx, rule_id))
+            .await?;
+        Ok(Response::new(pb::Ack {}))
+    }
+
+    async fn list_alert_rules(
+        &self,
+        request: Request<pb::CustomerRequest>,
+    ) -> Result<Response<pb::ListAlertRulesResponse>, Status> {
+        let ctx = convert::context(&request)?;
+        let customer_id = request.into_inner().customer_id;
+
+        let rules = self
+            .call(move |bank| {
+                ensure_self(&ctx, customer_id)?;
+                Ok(bank.alert_rules(customer_id))
+            })
+            .await?;
+        Ok(Response::new(pb::ListAlertRulesResponse {
+            rules: rules.iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn transfer_external(
+        &self,
+        request: Request<pb::TransferExternalRequest>,
+    ) -> Result<Response<pb::OutgoingPayment>, Status> {
+        let ctx = convert::context(&request)?;
+        let request = request.into_inner();
+        let transfer = convert::external_transfer(request.transfer)?;
+
+        let payment = self
+            .call(move |bank| {
+                bank.transfer_external(&ctx, request.account_id, &request.debtor_name, transfer)
+            })
+            .await?;
+        Ok(Response::new((&payment).into()))
+    }
+
+    async fn get_external_payment(
+        &self,
+        request: Request<pb::ExternalPaymentRequest>,
+    ) -> Result<Response<pb::ExternalPayment>, Status> {
+        let ctx = convert::context(&request)?;
+        let end_to_end_id = request.into_inner().end_to_end_id;
+
+        let external = self
+            .call(move |bank| {
+                let external = bank.external_payment(&end_to_end_id)?;
+                bank.account_as(&ctx, external.payment.debtor_account)?;
+                Ok(external)
+            })
+            .await?;
+        Ok(Response::new((&external).into()))
+    }
+
+    async fn list_outgoing_payments(
+        &self,
+        request: Request<pb::ListOutgoingPaymentsRequest>,
+    ) -> Result<Response<pb::ListOutgoingPaymentsResponse>, Status> {
+        ensure_staff(&convert::context(&request)?)?;
+
+        let payments = self.call(|bank| Ok(bank.outgoing_payments())).await?;
+        Ok(Response::new(pb::ListOutgoingPaymentsResponse {
+            payments: payments.iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn export_payment_file(
+        &self,
+        request: Request<pb::ExportPaymentFileRequest>,
+    ) -> Result<Response<pb::PaymentFile>, Status> {
+        ensure_staff(&convert::context(&request)?)?;
+        let header = convert::payment_file_header(request.into_inner())?;
+
+        let xml = self
+            .call(move |bank| Ok(bank.export_payment_file(&header)))
+            .await?
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        Ok(Response::new(pb::PaymentFile { xml }))
+    }
+
+    async fn submit_external_payments(
+        &self,
+        request: Request<pb::SubmitExternalPaymentsRequest>,
+    ) -> Result<Response<pb::ClearingResults>, Status> {
+        ensure_staff(&convert::context(&request)?)?;
+
+        let results = self.call(|bank| bank.submit_external_payments()).await?;
+        Ok(Response::new(convert::clearing_results(&results)))
+    }
+
+    async fn process_clearing_events(
+        &self,
+        request: Request<pb::ProcessClearingEventsRequest>,
+    ) -> Result<Response<pb::ClearingResults>, Status> {
+        ensure_staff(&convert::context(&request)?)?;
+
+        let results = self.call(|bank| bank.process_clearing_events()).await?;
+        Ok(Response::new(convert::clearing_results(&results)))
+    }
+
+    async fn record_clearing_event(
+        &self,
+        request: Request<pb::ClearingEvent>,
+    ) -> Result<Response<pb::PaymentStatus>, Status> {
+        let ctx = convert::context(&request)?;
+        ensure_staff(&ctx)?;
+        let event = convert::clearing_event(request.into_inner())?;
+
+        let payment_status = self
+            .call(move |bank| bank.record_clearing_event(&ctx, &event))
+            .await?;
+        Ok(Response::new((&payment_status).into()))
+    }
+
+    async fn import_statement(
+        &self,
+        request: Request<pb::ImportStatementRequest>,
+    ) -> Result<Response<pb::ImportStatementResponse>, Status> {
+        ensure_staff(&convert::context(&request)?)?;
+        let request = request.into_inner();
+        let payments = Camt053Document::from_xml(&request.xml)
+            .and_then(|statement| statement.incoming_payments())
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+
+        let results = self
+            .call(move |bank| Ok(bank.import_statement(&payments, &request.currency)))
+            .await?;
+        let results = results
+            .into_iter()
+            .map(|(reference, result)| {
+                let result = match result {
+                    Ok(balance) => pb::statement_entry_result::Result::Balance(balance.to_string()),
+                    Err(err) => pb::statement_entry_result::Result::Failure(convert::failure(&err)),
+                };
+                pb::StatementEntryResult {
+                    reference,
+                    result: Some(result),
+                }
+            })
+            .collect();
+        Ok(Response::new(pb::ImportStatementResponse { results }))
+    }
+
+    async fn export_customer_data(
+        &self,
+        request: Request<pb::CustomerRequest>,
+    ) -> Result<Response<pb::CustomerDataExport>, Status> {
+        let ctx = convert::context(&request)?;
+        let customer_id = request.into_inner().customer_id;
+
+        let export = self
+            .call(move |bank| bank.export_customer_data(&ctx, customer_id))
+            .await?;
+        let json = export
+            .to_json()
+            .map_err(|err| Status::internal(err.to_string()))?;
+        Ok(Response::new(pb::CustomerDataExport { json }))
+    }
+
+    async fn erase_customer(
+        &self,
+        request: Request<pb::CustomerRequest>,
+    ) -> Result<Response<pb::Customer>, Status> {
+        let ctx = convert::context(&request)?;
+        let customer_id = request.into_inner().customer_id;
+
+        let customer = self
+            .call(move |bank| bank.erase_customer(&ctx, customer_id))
+            .await?;
+        Ok(Response::new((&customer).into()))
+    }
+
+    async fn take_snapshot(
+        &self,
+        request: Request<pb::TakeSnapshotRequest>,
+    ) -> Result<Response<pb::Snapshot>, Status> {
+        ensure_staff(&convert::context(&request)?)?;
+
+        let json = self
+            .call(|bank| Ok(bank.take_snapshot()?.to_json()?))
+            .await?;
+        Ok(Response::new(pb::Snapshot { json }))
+    }
+
+    async fn restore_snapshot(
+        &self,
+        request: Request<pb::Snapshot>,
+    ) -> Result<Response<pb::Ack>, Status> {
+        ensure_staff(&convert::context(&request)?)?;
+        let snapshot = BankSnapshot::from_json(&request.into_inner().json)
+            .map_err(|err| status(err.into()))?;
+
+        self.call(move |bank| bank.restore_snapshot(snapshot))
+            .await?;
+        Ok(Response::new(pb::Ack {}))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{net::SocketAddr, sync::Arc};
+
+    use bank_core::{
+        account::{HolderRole, Money, Transaction},
+        account_number::AccountRef,
+        context::RequestContext,
+    };
+    use bank_infra::storage::InMemoryRepo;
+    use bank_services::bank::Bank;
+    use tokio::net::TcpListener;
+    use tonic::{Code, Request, transport::Channel};
+
+    use crate::{
+        convert::{ACTOR_HEADER, ERROR_CODE_HEADER},
+        pb::{self, bank_client::BankClient},
+        server::serve,
+    };
+
+    async fn start(bank: Bank<InMemoryRepo>) -> (Arc<Bank<InMemoryRepo>>, BankClient<Channel>) {
+        let bank = Arc::new(bank);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr: SocketAddr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, Arc::clone(&bank)));
+
+        let client = BankClient::connect(format!("http://{addr}")).await.unwrap();
+        (bank, client)
+    }
+
+    fn as_actor<T>(actor: &str, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request
+            .metadata_mut()
+            .insert(ACTOR_HEADER, actor.parse().unwrap());
+        request
+    }
+
+    fn deposit(account_id: u64, amount: &str) -> pb::ProcessRequest {
+        pb::ProcessRequest {
+            account_id,
+            transaction: Some(pb::Transaction {
+                kind: Some(pb::transaction::Kind::Deposit(amount.into())),
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rpc_server_will_serve_bank_operations_over_a_socket() {
+        let (bank, mut client) = start(Bank::new(Arc::new(InMemoryRepo::new()))).await;
+
+        let create = |owner| pb::CreateAccountRequest {
+            owner,
+            product: None,
+        };
+        let from = client
+            .create_account(as_actor("customer:1", create(1)))
+            .await
+            .unwrap();
+        let from = from.into_inner().account_id;
+        let to = client
+            .create_account(as_actor("customer:2", create(2)))
+            .await
+            .unwrap();
+        let to = to.into_inner().account_id;
+
+        let balance = client
+            .process(as_actor("customer:1", deposit(from, "100.50")))
+            .await;
+        assert_eq!(balance.unwrap().into_inner().balance, "100.50");
+
+        let transfer = pb::ProcessRequest {
+            account_id: from,
+            transaction: Some(pb::Transaction {
+                kind: Some(pb::transaction::Kind::Transfer(pb::Transfer {
+                    to,
+                    amount: "40.25".into(),
+                })),
+            }),
+        };
+        let balance = client
+            .process(as_actor("customer:1", transfer))
+            .await
+            .unwrap();
+        assert_eq!(balance.into_inner().balance, "60.25");
+
+        let account = client
+            .get_account(as_actor("teller:9", pb::AccountRequest { account_id: to }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(account.balance, "40.25");
+        assert_eq!(account.owner, 2);
+        assert_eq!(account.status(), pb::AccountStatus::Active);
+
+        let listed = client
+            .list_accounts(as_actor("customer:1", pb::ListAccountsRequest::default()))
+            .await
+            .unwrap()
+            .into_inner();
+        let ids: Vec<u64> = listed.accounts.iter().map(|account| account.id).collect();
+        assert_eq!(ids, vec![from]);
+
+        client
+            .add_holder(as_actor(
+                "customer:1",
+                pb::AddHolderRequest {
+                    account_id: from,
+                    customer: 2,
+                    role: pb::HolderRole::Signatory.into(),
+                },
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            bank.find_account(&AccountRef::Id(from)).unwrap().holders[0].role,
+            HolderRole::Signatory
+        );
+        assert_eq!(
+            bank.find_account(&AccountRef::Id(to)).unwrap().balance,
+            Money("40.25".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rpc_server_will_report_bank_errors_with_their_codes() {
+        let (bank, mut client) = start(Bank::new(Arc::new(InMemoryRepo::new()))).await;
+        let account_id = bank.create_account_as(&RequestContext::system(), 1);
+        let account_id = account_id.unwrap();
+
+        let missing = client
+            .process(Request::new(deposit(account_id, "1")))
+            .await
+            .unwrap_err();
+        assert_eq!(missing.code(), Code::Unauthenticated);
+
+        let err = client
+            .get_account(as_actor("customer:2", pb::AccountRequest { account_id }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::PermissionDenied);
+
+        let err = client
+            .get_account(as_actor("teller:9", pb::AccountRequest { account_id: 99 }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::NotFound);
+        assert_eq!(
+            err.metadata().get(ERROR_CODE_HEADER).unwrap(),
+            "account_not_found"
+        );
+
+        let err = client
+            .process(as_actor("customer:1", deposit(account_id, "lots")))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        let withdraw = pb::ProcessRequest {
+            account_id,
+            transaction: Some(pb::Transaction {
+                kind: Some(pb::transaction::Kind::Withdraw("5".into())),
+            }),
+        };
+        let err = client
+            .process(as_actor("customer:1", withdraw))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+        assert_eq!(
+            err.metadata().get(ERROR_CODE_HEADER).unwrap(),
+            "insufficient_funds"
+        );
+        assert_eq!(err.metadata().get("x-error-available").unwrap(), "0");
+    }
+
+    #[tokio::test]
+    async fn test_rpc_server_will_stream_ledger_events_as_they_commit() {
+        let (bank, mut client) = start(Bank::new(Arc::new(InMemoryRepo::new()))).await;
+        let first = bank
+            .create_account_as(&RequestContext::system(), 1)
+            .unwrap();
+        let second = bank
+            .create_account_as(&RequestContext::system(), 2)
+            .unwrap();
+        bank.process(first, Transaction::Deposit(Money(10.into())))
+            .unwrap();
+        bank.process(second, Transaction::Deposit(Money(99.into())))
+            .unwrap();
+
+        let tail = pb::TailLedgerRequest {
+            account_id: Some(first),
+            after_seq: 0,
+        };
+        let err = client
+            .tail_ledger(as_actor("customer:1", pb::TailLedgerRequest::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::PermissionDenied);
+        let mut stream = client
+            .tail_ledger(as_actor("customer:1", tail))
+            .await
+            .unwrap();
+        let stream = stream.get_mut();
+
+        let event = stream.message().await.unwrap().unwrap();
+        assert_eq!((event.account_id, event.amount.as_str()), (first, "10"));
+
+        bank.process(first, Transaction::Withdraw(Money(4.into())))
+            .unwrap();
+        let event = stream.message().await.unwrap().unwrap();
+        assert_eq!(event.action, "process");
+        assert_eq!((event.amount.as_str(), event.balance.as_str()), ("-4", "6"));
+        assert!(event.at.is_some());
+
+        let resumed = pb::TailLedgerRequest {
+            account_id: None,
+            after_seq: event.seq - 1,
+        };
+        let mut stream = client
+            .tail_ledger(as_actor("teller:9", resumed))
+            .await
+            .unwrap();
+        let event = stream.get_mut().message().await.unwrap().unwrap();
+        assert_eq!(event.amount, "-4");
+    }
+
+    #[tokio::test]
+    async fn test_rpc_server_will_serve_loans_alerts_and_customer_data() {
+        let (bank, mut client) = start(Bank::new(Arc::new(InMemoryRepo::new()))).await;
+        let account_id = bank
+            .create_account_as(&RequestContext::system(), 1)
+            .unwrap();
+        let ada = pb::Customer {
+            id: 1,
+            name: "Ada Lovelace".into(),
+            email: Some("ada@example.com".into()),
+        };
+        client
+            .register_customer(as_actor("customer:1", ada))
+            .await
+            .unwrap();
+
+        let terms = pb::LoanTerms {
+            principal: "1200".into(),
+            annual_rate: "0.12".into(),
+            term_months: 12,
+            method: pb::AmortizationMethod::EqualPrincipal.into(),
+            start: "2026-01-15".into(),
+            late_fee: "25".into(),
+        };
+        let originate = pb::OriginateLoanRequest {
+            account_id,
+            terms: Some(terms),
+        };
+        let loan_id = client
+            .originate_loan(as_actor("customer:1", originate))
+            .await
+            .unwrap()
+            .into_inner()
+            .loan_id;
+        let disbursed = client
+            .disburse_loan(as_actor("teller:9", pb::LoanRequest { loan_id }))
+            .await
+            .unwrap();
+        assert_eq!(disbursed.into_inner().balance, "1200");
+        let repay = pb::RepayLoanRequest {
+            loan_id,
+            amount: "112".into(),
+            on: "2026-02-15".into(),
+        };
+        let split = client
+            .repay_loan(as_actor("customer:1", repay))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            (split.interest.as_str(), split.principal.as_str()),
+            ("12.00", "100.00")
+        );
+
+        let loan = client
+            .get_loan(as_actor("customer:1", pb::LoanRequest { loan_id }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(loan.status(), pb::LoanStatus::Active);
+        assert_eq!(loan.schedule.len(), 12);
+        let err = client
+            .get_loan(as_actor("customer:2", pb::LoanRequest { loan_id }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::PermissionDenied);
+
+        let rule = pb::AddAlertRuleRequest {
+            customer: 1,
+            account_id,
+            condition: Some(pb::AlertCondition {
+                kind: Some(pb::alert_condition::Kind::LowBalance("100".into())),
+            }),
+        };
+        client
+            .add_alert_rule(as_actor("customer:1", rule))
+            .await
+            .unwrap();
+        let rules = client
+            .list_alert_rules(as_actor(
+                "customer:1",
+                pb::CustomerRequest { customer_id: 1 },
+            ))
+            .await
+            .unwrap()
+            .into_inner()
+            .rules;
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].account_id, account_id);
+
+        let export = client
+            .export_customer_data(as_actor(
+                "customer:1",
+                pb::CustomerRequest { customer_id: 1 },
+            ))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(export.json.contains("ada@example.com"));
+        let err = client
+            .erase_customer(as_actor(
+                "customer:1",
+                pb::CustomerRequest { customer_id: 1 },
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.metadata().get(ERROR_CODE_HEADER).unwrap(),
+            "loan_status_conflict"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rpc_server_will_keep_back_office_calls_for_staff() {
+        let (bank, mut client) = start(Bank::new(Arc::new(InMemoryRepo::new()))).await;
+        let account_id = bank
+            .create_account_as(&RequestContext::system(), 1)
+            .unwrap();
+        bank.process(account_id, Transaction::Deposit(Money(10.into())))
+            .unwrap();
+
+        let err = client
+            .take_snapshot(as_actor("customer:1", pb::TakeSnapshotRequest {}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::PermissionDenied);
+        let err = client
+            .process_maturities(as_actor("customer:1", pb::ProcessMaturitiesRequest {}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::PermissionDenied);
+
+        let snapshot = client
+            .take_snapshot(as_actor("system:backup", pb::TakeSnapshotRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        let (restored, mut standby) = start(Bank::new(Arc::new(InMemoryRepo::new()))).await;
+        standby
+            .restore_snapshot(as_actor("system:backup", snapshot))
+            .await
+            .unwrap();
+        assert_eq!(
+            restored
+                .find_account(&AccountRef::Id(account_id))
+                .unwrap()
+                .balance,
+            Money(10.into())
+        );
+
+        let err = client
+            .submit_external_payments(as_actor("teller:9", pb::SubmitExternalPaymentsRequest {}))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.metadata().get(ERROR_CODE_HEADER).unwrap(),
+            "not_configured"
+        );
+
+        let float = vec![pb::CashPieces {
+            denomination: "20".into(),
+            pieces: 5,
+        }];
+        let drawer_id = client
+            .open_drawer(as_actor("teller:9", pb::OpenDrawerRequest { float }))
+            .await
+            .unwrap()
+            .into_inner()
+            .drawer_id;
+        let drawer = client
+            .get_cash_drawer(as_actor("teller:9", pb::DrawerRequest { drawer_id }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(drawer.status(), pb::DrawerStatus::Open);
+        assert_eq!(drawer.expected[0].pieces, 5);
+    }
+}
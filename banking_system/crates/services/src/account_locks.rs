@@ -0,0 +1,74 @@
+use std::sync::{Mutex, MutexGuard};
+
+use bank_core::account::AccountId;
+
+/// Number of locks accounts are spread over. Operations on accounts that
+/// share a stripe wait for each other even though they need not.
+const STRIPES: usize = 64;
+
+/// Serializes audited operations touching the same accounts, so the before
+/// and after snapshots of an operation and its place in the audit log only
+/// ever reflect its own changes.
+#[derive(Debug)]
+pub struct AccountLocks {
+    stripes: Vec<Mutex<()>>,
+}
+
+impl Default for AccountLocks {
+    fn default() -> Self {
+        AccountLocks {
+            stripes: (0..STRIPES).map(|_| Mutex::new(())).collect(),
+        }
+    }
+}
+
+impl AccountLocks {
+    /// Holds `accounts` until the guards are dropped. Stripes are taken in
+    /// ascending order, so callers locking overlapping sets cannot deadlock.
+    pub fn lock(&self, accounts: &[AccountId]) -> Vec<MutexGuard<'_, ()>> {
+        stripes(accounts)
+            .into_iter()
+            .map(|stripe| {
+                self.stripes[stripe]
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+            })
+            .collect()
+    }
+}
+
+/// [`AccountLocks`] for async operations, whose guards are held across
+/// awaits.
+#[derive(Debug)]
+pub struct AsyncAccountLocks {
+    stripes: Vec<tokio::sync::Mutex<()>>,
+}
+
+impl Default for AsyncAccountLocks {
+    fn default() -> Self {
+        AsyncAccountLocks {
+            stripes: (0..STRIPES).map(|_| tokio::sync::Mutex::new(())).collect(),
+        }
+    }
+}
+
+impl AsyncAccountLocks {
+    /// See [`AccountLocks::lock`].
+    pub async fn lock(&self, accounts: &[AccountId]) -> Vec<tokio::sync::MutexGuard<'_, ()>> {
+        let mut guards = Vec::new();
+        for stripe in stripes(accounts) {
+            guards.push(self.stripes[stripe].lock().await);
+        }
+        guards
+    }
+}
+
+fn stripes(accounts: &[AccountId]) -> Vec<usize> {
+    let mut stripes: Vec<usize> = accounts
+        .iter()
+        .map(|account_id| (*account_id % STRIPES as u64) as usize)
+        .collect();
+    stripes.sort_unstable();
+    stripes.dedup();
+    stripes
+}
@@ -0,0 +1,462 @@
+use std::{fmt::Debug, future::Future, sync::Arc};
+
+use bank_core::{
+    account::{Account, AccountId, HolderRole, Money, Transaction},
+    approval::{ApprovalId, PendingApproval},
+    async_repo::AsyncAccountRepository,
+    audit::{AuditEntry, AuditLog},
+    clock::{Clock, SystemClock},
+    context::RequestContext,
+    customer::CustomerId,
+    errors::{AppError, DomainError},
+    query::{AccountQuery, Page, PageRequest},
+    tenant::Tenant,
+};
+
+use crate::{
+    account_locks::AsyncAccountLocks,
+    authorization::{Approvals, authorize, check_account_limit, check_transaction_limit},
+    bank::{debit, not_found, touched},
+};
+
+/// Async counterpart of [`crate::bank::Bank`] for use on a Tokio (or any
+/// other multi-threaded) runtime. It applies the same holder checks, dual
+/// approval and tenant limits, and audits every operation under the caller
+/// in its [`RequestContext`].
+pub struct AsyncBank<R: AsyncAccountRepository> {
+    pub repo: Arc<R>,
+    /// The tenant whose accounts this bank serves, with its limits.
+    pub tenant: Tenant,
+    /// Dates withdrawals for the daily limits of the tenant's products.
+    pub clock: Arc<dyn Clock>,
+    pub approvals: Approvals,
+    pub audit: AuditLog,
+    /// See [`crate::bank::Bank::account_locks`].
+    pub account_locks: AsyncAccountLocks,
+}
+
+impl<R: AsyncAccountRepository> AsyncBank<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        AsyncBank {
+            repo,
+            tenant: Tenant::default(),
+            clock: Arc::new(SystemClock),
+            approvals: Approvals::default(),
+            audit: AuditLog::new(),
+            account_locks: AsyncAccountLocks::default(),
+        }
+    }
+
+    pub fn with_tenant(mut self, tenant: Tenant) -> Self {
+        self.tenant = tenant;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub async fn create_account(
+        &self,
+        ctx: &RequestContext,
+        owner: CustomerId,
+    ) -> Result<AccountId, AppError> {
+        let account_id = self.repo.next_id().await?;
+
+        let inputs = format!("owner={owner}");
+        self.audited(ctx, "create_account", inputs, vec![account_id], async {
+            let account = Account::builder(account_id, owner)
+                .tenant(self.tenant.id.clone())
+                .build();
+            if self.tenant.limits.max_accounts_per_owner.is_some() {
+                self.repo
+                    .create_checked(&self.tenant.id, account, |owned| {
+                        Ok::<_, AppError>(check_account_limit(&self.tenant, owner, owned)?)
+                    })
+                    .await?;
+            } else {
+                self.repo.create(&self.tenant.id, account).await?;
+            }
+            Ok(account_id)
+        })
+        .await
+    }
+
+    /// Processes `txn` on behalf of the caller in `ctx`, under the same
+    /// rules as [`crate::bank::Bank::process_as`].
+    pub async fn process(
+        &self,
+        ctx: &RequestContext,
+        account_id: AccountId,
+        txn: Transaction,
+    ) -> Result<Money, AppError> {
+        let inputs = format!("account={account_id} {txn:?}");
+        let accounts = touched(account_id, &txn);
+
+        self.audited(ctx, "process", inputs, accounts, async {
+            check_transaction_limit(&self.tenant, account_id, &txn)?;
+            if txn.is_debit() {
+                let account = self.load(account_id).await?;
+                self.approvals.admit_debit(ctx, &account, &txn)?;
+            }
+
+            self.execute(account_id, txn).await
+        })
+        .await
+    }
+
+    /// Releases a held transaction; see [`crate::bank::Bank::approve`].
+    pub async fn approve(
+        &self,
+        ctx: &RequestContext,
+        approval_id: ApprovalId,
+    ) -> Result<Money, AppError> {
+        let accounts = self
+            .approvals
+            .get(approval_id)
+            .map(|approval| touched(approval.account_id, &approval.txn))
+            .unwrap_or_default();
+
+        let inputs = format!("approval={approval_id}");
+        self.audited(ctx, "approve", inputs, accounts, async {
+            let approval = self.approvals.claim(approval_id, &ctx.actor)?;
+            let result = async {
+                let account = self.load(approval.account_id).await?;
+                authorize(&account, &ctx.actor, HolderRole::can_transact)?;
+                self.execute(approval.account_id, approval.txn.clone())
+                    .await
+            }
+            .await;
+            if result.is_err() {
+                self.approvals.release(approval);
+            }
+            result
+        })
+        .await
+    }
+
+    pub fn pending_approvals(&self, account_id: AccountId) -> Vec<PendingApproval> {
+        self.approvals.for_account(account_id)
+    }
+
+    pub async fn freeze(
+        &self,
+        ctx: &RequestContext,
+        account_id: AccountId,
+    ) -> Result<(), AppError> {
+        let inputs = format!("account={account_id}");
+
+        self.audited(ctx, "freeze", inputs, vec![account_id], async {
+            self.mutate(account_id, |account| {
+                authorize(account, &ctx.actor, HolderRole::can_manage)?;
+                Ok(account.freeze()?)
+            })
+            .await
+        })
+        .await
+    }
+
+    pub async fn close(&self, ctx: &RequestContext, account_id: AccountId) -> Result<(), AppError> {
+        let inputs = format!("account={account_id}");
+
+        self.audited(ctx, "close", inputs, vec![account_id], async {
+            self.mutate(account_id, |account| {
+                authorize(account, &ctx.actor, HolderRole::can_manage)?;
+                Ok(account.close()?)
+            })
+            .await
+        })
+        .await
+    }
+
+    /// Returns the account if the caller may see it.
+    pub async fn account(
+        &self,
+        ctx: &RequestContext,
+        account_id: AccountId,
+    ) -> Result<Account, AppError> {
+        let account = self.load(account_id).await?;
+        authorize(&account, &ctx.actor, |_| true)?;
+        Ok(account)
+    }
+
+    pub async fn list_accounts(
+        &self,
+        query: &AccountQuery,
+        page: PageRequest,
+    ) -> Result<Page<Account>, AppError> {
+        Ok(self.repo.list(&self.tenant.id, query, page).await?)
+    }
+
+    async fn load(&self, account_id: AccountId) -> Result<Account, AppError> {
+        Ok(self
+            .repo
+            .get(&self.tenant.id, account_id)
+            .await?
+            .ok_or(DomainError::AccountNotFound(account_id))?)
+    }
+
+    async fn execute(&self, account_id: AccountId, txn: Transaction) -> Result<Money, AppError> {
+        check_transaction_limit(&self.tenant, account_id, &txn)?;
+        match txn {
+            Transaction::Transfer { to, amount } => self.transfer(account_id, to, amount).await,
+            Transaction::Withdraw(amount) => {
+                self.mutate(account_id, |account| {
+                    Ok(debit(&self.tenant, account, amount, self.clock.today())?)
+                })
+                .await
+            }
+            txn => {
+                self.mutate(account_id, |account| Ok(account.apply_transaction(txn)?))
+                    .await
+            }
+        }
+    }
+
+    async fn transfer(
+        &self,
+        from: AccountId,
+        to: AccountId,
+        amount: Money,
+    ) -> Result<Money, AppError> {
+        if from == to {
+            return Err(DomainError::TransferToSelf(from).into());
+        }
+
+        self.repo
+            .modify(&self.tenant.id, &[from, to], |accounts| {
+                let (src, dest) = accounts.split_at_mut(1);
+
+                debit(&self.tenant, &mut src[0], amount, self.clock.today())?;
+                dest[0].deposit(amount)?;
+
+                Ok(src[0].balance)
+            })
+            .await
+            .map_err(not_found)
+    }
+
+    async fn mutate<T: Send>(
+        &self,
+        account_id: AccountId,
+        f: impl FnOnce(&mut Account) -> Result<T, AppError> + Send,
+    ) -> Result<T, AppError> {
+        self.repo
+            .modify(&self.tenant.id, &[account_id], |accounts| {
+                f(&mut accounts[0])
+            })
+            .await
+            .map_err(not_found)
+    }
+
+    /// See [`crate::bank::Bank`]'s `audited`; the accounts stay locked
+    /// until the record is appended.
+    async fn audited<T: Debug>(
+        &self,
+        ctx: &RequestContext,
+        action: &str,
+        inputs: String,
+        accounts: Vec<AccountId>,
+        op: impl Future<Output = Result<T, AppError>>,
+    ) -> Result<T, AppError> {
+        let _held = self.account_locks.lock(&accounts).await;
+        let before = self.snapshot(&accounts).await?;
+        let result = op.await;
+        let after = self.snapshot(&accounts).await?;
+
+        let entry = AuditEntry::from_result(action, inputs, accounts, before, after, &result);
+        self.audit.append(ctx, entry)?;
+
+        result
+    }
+
+    async fn snapshot(&self, accounts: &[AccountId]) -> Result<Vec<Option<Account>>, AppError> {
+        let mut snapshot = Vec::with_capacity(accounts.len());
+        for account_id in accounts {
+            snapshot.push(self.repo.get(&self.tenant.id, *account_id).await?);
+        }
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use bank_core::{
+        account::{AccountStatus, HolderRole, Money, Transaction},
+        async_repo::AsyncAccountRepository,
+        audit::AuditQuery,
+        context::{Actor, RequestContext},
+        errors::{AccountOperation, AppError, DomainError},
+        tenant::Tenant,
+    };
+    use bank_infra::storage::InMemoryRepo;
+    use rust_decimal::Decimal;
+
+    use crate::async_bank::AsyncBank;
+
+    #[tokio::test]
+    async fn test_async_bank_will_process_deposit_and_withdraw() {
+        let system = RequestContext::system();
+        let bank = AsyncBank::new(Arc::new(InMemoryRepo::new()));
+        let account_id = bank.create_account(&system, 1).await.unwrap();
+
+        let balance = bank
+            .process(&system, account_id, Transaction::Deposit(Money(100.into())))
+            .await;
+        assert_eq!(balance, Ok(Money(100.into())));
+
+        let result = bank
+            .process(
+                &system,
+                account_id,
+                Transaction::Withdraw(Money(101.into())),
+            )
+            .await;
+        assert_eq!(
+            result,
+            Err(AppError::Domain(DomainError::InsufficientFunds {
+                account_id,
+                requested: Money(101.into()),
+                available: Money(100.into()),
+            }))
+        );
+
+        bank.freeze(&system, account_id).await.unwrap();
+        let result = bank
+            .process(&system, account_id, Transaction::Withdraw(Money(1.into())))
+            .await;
+        assert_eq!(
+            result,
+            Err(AppError::Domain(DomainError::AccountNotActive {
+                account_id,
+                status: AccountStatus::Frozen,
+                operation: AccountOperation::Withdraw,
+            }))
+        );
+        assert!(bank.audit.verify().is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_async_bank_will_complete_thousands_of_concurrent_transfers() {
+        const ACCOUNTS: u64 = 10;
+        const TASKS: u64 = 5_000;
+
+        let system = RequestContext::system();
+        let bank = Arc::new(AsyncBank::new(Arc::new(InMemoryRepo::with_shards(4))));
+        for owner in 1..=ACCOUNTS {
+            let id = bank.create_account(&system, owner).await.unwrap();
+            bank.process(&system, id, Transaction::Deposit(Money(1_000.into())))
+                .await
+                .unwrap();
+        }
+
+        let handles: Vec<_> = (0..TASKS)
+            .map(|task| {
+                let bank = Arc::clone(&bank);
+                tokio::spawn(async move {
+                    let from = task % ACCOUNTS + 1;
+                    let to = (task * 7 + 3) % ACCOUNTS + 1;
+                    let txn = Transaction::Transfer {
+                        to,
+                        amount: Money(Decimal::ONE),
+                    };
+                    (
+                        from,
+                        to,
+                        bank.process(&RequestContext::system(), from, txn).await,
+                    )
+                })
+            })
+            .collect();
+
+        let mut expected = vec![Decimal::from(1_000); ACCOUNTS as usize + 1];
+        for handle in handles {
+            let (from, to, result) = handle.await.unwrap();
+            match result {
+                Ok(_) => {
+                    expected[from as usize] -= Decimal::ONE;
+                    expected[to as usize] += Decimal::ONE;
+                }
+                Err(err) => assert_eq!(err, AppError::Domain(DomainError::TransferToSelf(from))),
+            }
+        }
+
+        let mut total = Decimal::ZERO;
+        for id in 1..=ACCOUNTS {
+            let balance = bank.account(&system, id).await.unwrap().balance.0;
+            assert_eq!(balance, expected[id as usize]);
+            total += balance;
+        }
+        assert_eq!(total, Decimal::from(1_000 * ACCOUNTS));
+    }
+
+    #[tokio::test]
+    async fn test_async_bank_will_check_holders_approvals_and_limits_as_the_caller() {
+        let system = RequestContext::system();
+        let tenant = Tenant::builder("acme")
+            .max_transaction(Money(1_000.into()))
+            .build();
+        let bank = AsyncBank::new(Arc::new(InMemoryRepo::new())).with_tenant(tenant);
+        let account_id = bank.create_account(&system, 1).await.unwrap();
+        bank.process(&system, account_id, Transaction::Deposit(Money(900.into())))
+            .await
+            .unwrap();
+        bank.repo
+            .modify(&bank.tenant.id, &[account_id], |accounts| {
+                accounts[0].add_holder(2, HolderRole::Signatory)?;
+                accounts[0].approval_threshold = Some(Money(500.into()));
+                Ok::<_, AppError>(())
+            })
+            .await
+            .unwrap();
+
+        let owner = RequestContext::customer(1, "req-1");
+        let signatory = RequestContext::customer(2, "req-2");
+        let stranger = RequestContext::customer(3, "req-3");
+
+        let withdraw = |amount: u32| Transaction::Withdraw(Money(amount.into()));
+        assert_eq!(
+            bank.process(&stranger, account_id, withdraw(10)).await,
+            Err(AppError::Domain(DomainError::Unauthorized {
+                account_id,
+                customer: 3
+            }))
+        );
+        assert!(matches!(
+            bank.process(
+                &owner,
+                account_id,
+                Transaction::Deposit(Money(1_001.into()))
+            )
+            .await,
+            Err(AppError::Domain(
+                DomainError::TransactionLimitExceeded { .. }
+            ))
+        ));
+        assert_eq!(
+            bank.process(&owner, account_id, withdraw(600)).await,
+            Err(AppError::Domain(DomainError::ApprovalRequired(1)))
+        );
+        assert_eq!(
+            bank.approve(&owner, 1).await,
+            Err(AppError::Domain(DomainError::SelfApproval {
+                approval_id: 1
+            }))
+        );
+        assert_eq!(bank.approve(&signatory, 1).await, Ok(Money(300.into())));
+        assert!(bank.pending_approvals(account_id).is_empty());
+        assert!(bank.freeze(&signatory, account_id).await.is_err());
+
+        let records = bank
+            .audit
+            .query(&AuditQuery::default().actor(Actor::Customer(2)))
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].request_id, "req-2");
+        assert_eq!(records[0].action, "approve");
+        assert!(bank.audit.verify().is_ok());
+    }
+}
@@ -0,0 +1,178 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use bank_core::{
+    account::{Account, AccountId, AccountStatus, HolderRole, Transaction},
+    approval::{ApprovalId, PendingApproval},
+    context::{Actor, RequestContext},
+    customer::CustomerId,
+    errors::{AppError, DomainError},
+    tenant::Tenant,
+};
+
+/// Lets customers act on an account only in a role `allowed` accepts.
+/// Tellers and the bank itself may act on any account.
+pub fn authorize(
+    account: &Account,
+    actor: &Actor,
+    allowed: impl Fn(&HolderRole) -> bool,
+) -> Result<(), DomainError> {
+    let customer = match actor {
+        Actor::Customer(customer) => *customer,
+        Actor::Teller(_) | Actor::System(_) => return Ok(()),
+    };
+
+    match account.role_of(customer) {
+        Some(role) if allowed(&role) => Ok(()),
+        _ => Err(DomainError::Unauthorized {
+            account_id: account.id,
+            customer,
+        }),
+    }
+}
+
+/// Lets `approver` sign off on a held debit from `account` if they are a
+/// transacting holder of it or a teller the tenant lists as an approver.
+pub fn authorize_approval(
+    tenant: &Tenant,
+    account: &Account,
+    approver: &Actor,
+    approval_id: ApprovalId,
+) -> Result<(), DomainError> {
+    match approver {
+        Actor::Customer(_) => authorize(account, approver, HolderRole::can_transact),
+        Actor::Teller(teller) if tenant.approvers.contains(teller) => Ok(()),
+        Actor::Teller(_) | Actor::System(_) => Err(DomainError::ApproverRequired { approval_id }),
+    }
+}
+
+pub fn check_transaction_limit(
+    tenant: &Tenant,
+    account_id: AccountId,
+    txn: &Transaction,
+) -> Result<(), DomainError> {
+    match tenant.limits.max_transaction {
+        Some(limit) if txn.amount().0 > limit.0 => Err(DomainError::TransactionLimitExceeded {
+            account_id,
+            amount: txn.amount(),
+            limit,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Refuses another account for `owner` once they are the primary owner of
+/// as many open `accounts` as the tenant allows. Meant to run inside
+/// [`bank_core::account::AccountRepository::create_checked`], so that no
+/// other account for `owner` is opened between the check and the write.
+pub fn check_account_limit(
+    tenant: &Tenant,
+    owner: CustomerId,
+    accounts: &[Account],
+) -> Result<(), DomainError> {
+    let Some(limit) = tenant.limits.max_accounts_per_owner else {
+        return Ok(());
+    };
+
+    let owned = accounts
+        .iter()
+        .filter(|account| account.owner == owner)
+        .filter(|account| account.status != AccountStatus::Closed)
+        .count();
+    if owned >= limit {
+        return Err(DomainError::AccountLimitReached { owner, limit });
+    }
+    Ok(())
+}
+
+/// Debits above their account's approval threshold, waiting for a second
+/// holder to sign off on them.
+#[derive(Debug, Default)]
+pub struct Approvals {
+    pub next_id: Mutex<ApprovalId>,
+    pub pending: Mutex<HashMap<ApprovalId, PendingApproval>>,
+}
+
+impl Approvals {
+    /// Checks that the caller in `ctx` may debit `txn` from `account`, and
+    /// holds it with [`DomainError::ApprovalRequired`] if it needs a second
+    /// holder's approval.
+    pub fn admit_debit(
+        &self,
+        ctx: &RequestContext,
+        account: &Account,
+        txn: &Transaction,
+    ) -> Result<(), AppError> {
+        authorize(account, &ctx.actor, HolderRole::can_transact)?;
+        if !account.requires_approval(txn) {
+            return Ok(());
+        }
+
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        let approval = PendingApproval {
+            id: *next_id,
+            account_id: account.id,
+            requested_by: ctx.actor.clone(),
+            txn: txn.clone(),
+        };
+        self.pending.lock().unwrap().insert(approval.id, approval);
+
+        Err(DomainError::ApprovalRequired(*next_id).into())
+    }
+
+    pub fn get(&self, approval_id: ApprovalId) -> Option<PendingApproval> {
+        self.pending.lock().unwrap().get(&approval_id).cloned()
+    }
+
+    /// Takes the approval out of the pending set for `approver` to run.
+    /// Requesters never approve their own debits.
+    pub fn claim(
+        &self,
+        approval_id: ApprovalId,
+        approver: &Actor,
+    ) -> Result<PendingApproval, DomainError> {
+        let mut pending = self.pending.lock().unwrap();
+        match pending.get(&approval_id) {
+            None => Err(DomainError::ApprovalNotFound(approval_id)),
+            Some(approval) if approval.requested_by == *approver => {
+                Err(DomainError::SelfApproval { approval_id })
+            }
+            Some(_) => Ok(pending.remove(&approval_id).unwrap()),
+        }
+    }
+
+    /// Puts back a claimed approval whose transaction did not go through,
+    /// so it can be approved again once the account allows it.
+    pub fn release(&self, approval: PendingApproval) {
+        self.pending.lock().unwrap().insert(approval.id, approval);
+    }
+
+    pub fn for_account(&self, account_id: AccountId) -> Vec<PendingApproval> {
+        let mut approvals: Vec<PendingApproval> = self
+            .pending
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|approval| approval.account_id == account_id)
+            .cloned()
+            .collect();
+        approvals.sort_by_key(|approval| approval.id);
+        approvals
+    }
+
+    /// The last id issued and every pending approval, by id.
+    pub fn all(&self) -> (ApprovalId, Vec<PendingApproval>) {
+        let mut approvals: Vec<PendingApproval> =
+            self.pending.lock().unwrap().values().cloned().collect();
+        approvals.sort_by_key(|approval| approval.id);
+        (*self.next_id.lock().unwrap(), approvals)
+    }
+
+    pub fn restore(&self, next_id: ApprovalId, approvals: Vec<PendingApproval>) {
+        *self.next_id.lock().unwrap() = next_id;
+        *self.pending.lock().unwrap() = approvals
+            .into_iter()
+            .map(|approval| (approval.id, approval))
+            .collect();
+    }
+}
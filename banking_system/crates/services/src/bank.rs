@@ -1,118 +1,3739 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Debug,
+    path::Path,
+    sync::{Arc, Mutex, RwLock},
+    time::{Instant, SystemTime},
+};
 
 use bank_core::{
-    account::{Account, AccountId, AccountRepository, Money, Transaction},
-    customer::CustomerId,
-    errors::{AppError, DomainError},
+    account::{
+        Account, AccountId, AccountRepository, AccountStatus, HolderRole, Money, Transaction,
+    },
+    account_number::{AccountNumbering, AccountRef},
+    approval::{ApprovalId, PendingApproval},
+    audit::{AuditEntry, AuditLog, AuditRecord},
+    cash::{CashCount, CashDirection, CashDrawer, DrawerId, DrawerStatus, ShiftReport, cash_total},
+    clock::{Clock, SystemClock},
+    context::{Actor, RequestContext, TellerId},
+    customer::{Customer, CustomerId, CustomerStore},
+    errors::{AppError, ClearingError, DomainError, Feature, RepoError, SnapshotError},
+    interbank::{
+        ClearingEvent, ClearingGateway, ClearingOutcome, DeadLetter, ExternalPayment,
+        ExternalPaymentStatus, ExternalTransfer, IncomingPayment, OutgoingPayment,
+    },
+    loan::{Loan, LoanId, LoanStatus, LoanTerms, PayoffQuote, RepaymentSplit},
+    notification::{
+        AlertCondition, AlertRule, AlertRuleId, Notification, Notifier, RateLimit, RateLimiter,
+    },
+    privacy::CustomerDataExport,
+    product::Product,
+    query::{AccountQuery, MAX_PAGE_SIZE, Page, PageRequest},
+    snapshot::{BankSnapshot, SNAPSHOT_VERSION},
+    tenant::Tenant,
+    term_deposit::{
+        MaturityInstruction, MaturityOutcome, TermDeposit, TermDepositId, TermDepositPayout,
+        TermDepositStatus, TermDepositTerms,
+    },
+};
+use bank_infra::{
+    iso20022::{
+        Iso20022Error,
+        pain001::{Pain001Document, PaymentFileHeader},
+    },
+    metrics::{Metrics, outcome},
+    snapshot::{read_snapshot, write_snapshot},
+    storage::InMemoryCustomerStore,
+};
+use chrono::NaiveDate;
+use tracing::{Span, field};
+
+use crate::{
+    account_locks::AccountLocks,
+    authorization::{
+        Approvals, authorize, authorize_approval, check_account_limit, check_transaction_limit,
+    },
 };
 
+/// What a clearing run did.
+#[derive(Debug, Default, PartialEq)]
+pub struct ClearingResults {
+    /// The status each external payment reached, by end-to-end id.
+    pub results: Vec<(String, Result<ExternalPaymentStatus, AppError>)>,
+    /// Events that failed for good during the run and were moved to
+    /// [`Bank::clearing_dead_letters`] instead of being retried.
+    pub dead_lettered: Vec<DeadLetter>,
+}
+
+/// The amount a monthly account run moved on each account it covered, by
+/// account id.
+pub type AccountRunResults = Vec<(AccountId, Result<Money, AppError>)>;
 
 pub struct Bank<R: AccountRepository> {
-    pub next_id: Mutex<AccountId>,
     pub repo: Arc<R>,
+    pub customers: Arc<dyn CustomerStore>,
+    /// The tenant this bank serves, with its products and limits. Every
+    /// repository and customer store call is scoped to it, so banks of
+    /// different tenants can share both.
+    pub tenant: Tenant,
+    /// Issues external account numbers to new accounts when set.
+    pub numbering: Option<AccountNumbering>,
+    pub approvals: Approvals,
+    pub next_loan_id: Mutex<LoanId>,
+    pub loans: Mutex<HashMap<LoanId, Loan>>,
+    pub next_term_deposit_id: Mutex<TermDepositId>,
+    pub term_deposits: Mutex<HashMap<TermDepositId, TermDeposit>>,
+    pub next_drawer_id: Mutex<DrawerId>,
+    pub drawers: Mutex<HashMap<DrawerId, CashDrawer>>,
+    pub next_payment_id: Mutex<u64>,
+    /// External transfers by end-to-end id, which sorts in creation order.
+    pub external_payments: Mutex<BTreeMap<String, ExternalPayment>>,
+    /// Holds the funds of external transfers until they settle.
+    pub suspense_account: Option<AccountId>,
+    /// Carries external transfers to other institutions when set.
+    pub clearing: Option<Arc<dyn ClearingGateway>>,
+    /// Held while payments are handed to the clearing gateway or exported,
+    /// so no payment is submitted twice. The gateway is called without
+    /// holding `external_payments`.
+    pub submitting: Mutex<()>,
+    /// Clearing events that failed to apply but may yet, in the order they
+    /// arrived. They are retried before newer events on every run.
+    pub clearing_backlog: Mutex<Vec<ClearingEvent>>,
+    /// Clearing events that can never apply, kept for someone to look into.
+    pub clearing_dead_letters: Mutex<Vec<DeadLetter>>,
+    /// References of statement entries already credited.
+    pub imported: Mutex<HashSet<String>>,
+    pub next_alert_rule_id: Mutex<AlertRuleId>,
+    pub alert_rules: Mutex<BTreeMap<AlertRuleId, AlertRule>>,
+    /// Delivers alerts raised by committed operations when set.
+    pub notifier: Option<Arc<dyn Notifier>>,
+    pub alert_limiter: RateLimiter,
+    /// Today's date for date-driven operations such as maturity processing.
+    pub clock: Arc<dyn Clock>,
+    pub audit: AuditLog,
+    /// Held shared by every audited operation and exclusively while a
+    /// snapshot is taken or restored, so a snapshot never sees an operation
+    /// half done.
+    pub operations: RwLock<()>,
+    /// Held on the accounts of every audited operation until its record is
+    /// in the audit log.
+    pub account_locks: AccountLocks,
+    /// Counts and times every audited operation.
+    pub metrics: Arc<Metrics>,
 }
 
 impl<R: AccountRepository> Bank<R> {
     pub fn new(repo: Arc<R>) -> Self {
-        Bank { next_id: Mutex::new(0), repo }
+        Bank {
+            repo,
+            customers: Arc::new(InMemoryCustomerStore::new()),
+            tenant: Tenant::default(),
+            numbering: None,
+            approvals: Approvals::default(),
+            next_loan_id: Mutex::new(0),
+            loans: Mutex::new(HashMap::new()),
+            next_term_deposit_id: Mutex::new(0),
+            term_deposits: Mutex::new(HashMap::new()),
+            next_drawer_id: Mutex::new(0),
+            drawers: Mutex::new(HashMap::new()),
+            next_payment_id: Mutex::new(0),
+            external_payments: Mutex::new(BTreeMap::new()),
+            suspense_account: None,
+            clearing: None,
+            submitting: Mutex::new(()),
+            clearing_backlog: Mutex::new(Vec::new()),
+            clearing_dead_letters: Mutex::new(Vec::new()),
+            imported: Mutex::new(HashSet::new()),
+            next_alert_rule_id: Mutex::new(0),
+            alert_rules: Mutex::new(BTreeMap::new()),
+            notifier: None,
+            alert_limiter: RateLimiter::default(),
+            clock: Arc::new(SystemClock),
+            audit: AuditLog::new(),
+            operations: RwLock::new(()),
+            account_locks: AccountLocks::default(),
+            metrics: Arc::new(Metrics::new()),
+        }
+    }
+
+    /// Records into `metrics` instead of a private registry, typically the
+    /// one an [`bank_infra::instrumented::InstrumentedRepo`] also uses.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn with_customer_store(mut self, customers: Arc<dyn CustomerStore>) -> Self {
+        self.customers = customers;
+        self
+    }
+
+    pub fn with_tenant(mut self, tenant: Tenant) -> Self {
+        self.tenant = tenant;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Holds external transfers in `account_id` until they settle. The
+    /// account must already exist.
+    pub fn with_suspense_account(mut self, account_id: AccountId) -> Self {
+        self.suspense_account = Some(account_id);
+        self
+    }
+
+    pub fn with_clearing_gateway(mut self, gateway: Arc<dyn ClearingGateway>) -> Self {
+        self.clearing = Some(gateway);
+        self
+    }
+
+    /// Evaluates customers' alert rules after every committed operation
+    /// and sends what they raise through `notifier`.
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Caps the alerts sent about any one account; see [`RateLimit`].
+    pub fn with_alert_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.alert_limiter = RateLimiter::new(limit);
+        self
+    }
+
+    pub fn with_account_numbering(mut self, numbering: AccountNumbering) -> Self {
+        self.numbering = Some(numbering);
+        self
     }
 
     pub fn create_account(&mut self, owner: CustomerId) -> Result<AccountId, AppError> {
-        let mut id = self.next_id.lock().unwrap();
-        *id += 1;
-        let account = Account::builder(*id, owner).build();
+        self.create_account_as(&RequestContext::system(), owner)
+    }
+
+    pub fn create_account_as(
+        &self,
+        ctx: &RequestContext,
+        owner: CustomerId,
+    ) -> Result<AccountId, AppError> {
+        self.open_account(ctx, owner, None)
+    }
+
+    /// Opens an account for `owner`, on one of the tenant's products if
+    /// `product` names one.
+    pub fn open_account(
+        &self,
+        ctx: &RequestContext,
+        owner: CustomerId,
+        product: Option<&str>,
+    ) -> Result<AccountId, AppError> {
+        let account_id = self.repo.next_id()?;
+        let inputs = match product {
+            Some(code) => format!("owner={owner} product={code}"),
+            None => format!("owner={owner}"),
+        };
+
+        self.audited(ctx, "create_account", inputs, vec![account_id], || {
+            let mut account = Account::builder(account_id, owner).tenant(self.tenant.id.clone());
+            if let Some(code) = product {
+                let product =
+                    self.tenant
+                        .product(code)
+                        .ok_or_else(|| DomainError::UnknownProduct {
+                            tenant: self.tenant.id.clone(),
+                            product: code.into(),
+                        })?;
+                account = account.product(&product.code);
+            }
+            if let Some(numbering) = &self.numbering {
+                account = account.number(numbering.issue(account_id)?);
+            }
+
+            if self.tenant.limits.max_accounts_per_owner.is_some() {
+                self.repo
+                    .create_checked(&self.tenant.id, account.build(), |owned| {
+                        Ok::<_, AppError>(check_account_limit(&self.tenant, owner, owned)?)
+                    })?;
+            } else {
+                self.repo.create(&self.tenant.id, account.build())?;
+            }
+            Ok(account_id)
+        })
+    }
+
+    /// Looks an account up by internal id or external account number.
+    pub fn find_account(&self, reference: &AccountRef) -> Result<Account, AppError> {
+        match reference {
+            AccountRef::Id(id) => self.load(*id),
+            AccountRef::Number(number) => Ok(self
+                .repo
+                .get_by_number(&self.tenant.id, number)?
+                .ok_or_else(|| DomainError::AccountNumberNotFound(number.clone()))?),
+        }
+    }
+
+    /// Processes `txn` as the bank itself, without holder or approval checks.
+    #[tracing::instrument(
+        name = "bank.process",
+        skip(self, txn),
+        fields(kind = txn.kind(), amount = %txn.amount().0, outcome = field::Empty)
+    )]
+    pub fn process(&self, account_id: AccountId, txn: Transaction) -> Result<Money, AppError> {
+        let ctx = RequestContext::system();
+        let inputs = format!("account={account_id} {txn:?}");
+        let accounts = touched(account_id, &txn);
+
+        let result = self.audited(&ctx, "process", inputs, accounts, || {
+            self.execute(account_id, txn)
+        });
+
+        Span::current().record("outcome", outcome(&result));
+        result
+    }
+
+    /// Processes `txn` on behalf of the caller in `ctx`. Customers must be
+    /// allowed to move money out of the account for withdrawals and transfers.
+    /// Debits above the account's approval threshold are held until
+    /// [`Bank::approve`] is called by someone else.
+    #[tracing::instrument(
+        name = "bank.process",
+        skip(self, ctx, txn),
+        fields(
+            actor = ?ctx.actor,
+            request_id = %ctx.request_id,
+            kind = txn.kind(),
+            amount = %txn.amount().0,
+            outcome = field::Empty,
+        )
+    )]
+    pub fn process_as(
+        &self,
+        ctx: &RequestContext,
+        account_id: AccountId,
+        txn: Transaction,
+    ) -> Result<Money, AppError> {
+        let inputs = format!("account={account_id} {txn:?}");
+        let accounts = touched(account_id, &txn);
+
+        let result = self.audited(ctx, "process", inputs, accounts, || {
+            check_transaction_limit(&self.tenant, account_id, &txn)?;
+            if txn.is_debit() {
+                let account = self.load(account_id)?;
+                self.approvals.admit_debit(ctx, &account, &txn)?;
+            }
+
+            self.execute(account_id, txn)
+        });
+
+        Span::current().record("outcome", outcome(&result));
+        result
+    }
+
+    /// Releases a held transaction. Approvers must be transacting holders of
+    /// the account or tellers the tenant lists as approvers, and nobody can
+    /// approve their own request.
+    pub fn approve(
+        &self,
+        ctx: &RequestContext,
+        approval_id: ApprovalId,
+    ) -> Result<Money, AppError> {
+        let accounts = self
+            .approvals
+            .get(approval_id)
+            .map(|approval| touched(approval.account_id, &approval.txn))
+            .unwrap_or_default();
+
+        self.audited(
+            ctx,
+            "approve",
+            format!("approval={approval_id}"),
+            accounts,
+            || {
+                let approval = self.approvals.claim(approval_id, &ctx.actor)?;
+                let result = self.load(approval.account_id).and_then(|account| {
+                    authorize_approval(&self.tenant, &account, &ctx.actor, approval_id)?;
+                    self.execute(approval.account_id, approval.txn.clone())
+                });
+                if result.is_err() {
+                    self.approvals.release(approval);
+                }
+                result
+            },
+        )
+    }
+
+    pub fn freeze(&self, account_id: AccountId) -> Result<(), AppError> {
+        self.freeze_as(&RequestContext::system(), account_id)
+    }
+
+    pub fn freeze_as(&self, ctx: &RequestContext, account_id: AccountId) -> Result<(), AppError> {
+        self.audited(
+            ctx,
+            "freeze",
+            format!("account={account_id}"),
+            vec![account_id],
+            || {
+                self.mutate(account_id, |account| {
+                    authorize(account, &ctx.actor, HolderRole::can_manage)?;
+
+                    account.freeze()?;
+                    Ok(())
+                })
+            },
+        )
+    }
+
+    pub fn close(&self, account_id: AccountId) -> Result<(), AppError> {
+        self.close_as(&RequestContext::system(), account_id)
+    }
+
+    pub fn close_as(&self, ctx: &RequestContext, account_id: AccountId) -> Result<(), AppError> {
+        self.audited(
+            ctx,
+            "close",
+            format!("account={account_id}"),
+            vec![account_id],
+            || {
+                self.mutate(account_id, |account| {
+                    authorize(account, &ctx.actor, HolderRole::can_manage)?;
+
+                    account.close()?;
+                    Ok(())
+                })
+            },
+        )
+    }
+
+    pub fn pending_approvals(&self, account_id: AccountId) -> Vec<PendingApproval> {
+        self.approvals.for_account(account_id)
+    }
+
+    /// Returns the account if the caller may see it.
+    pub fn account_as(
+        &self,
+        ctx: &RequestContext,
+        account_id: AccountId,
+    ) -> Result<Account, AppError> {
+        let account = self.load(account_id)?;
+        authorize(&account, &ctx.actor, |_| true)?;
+        Ok(account)
+    }
+
+    pub fn list_accounts(
+        &self,
+        query: &AccountQuery,
+        page: PageRequest,
+    ) -> Result<Page<Account>, AppError> {
+        Ok(self.repo.list(&self.tenant.id, query, page)?)
+    }
+
+    /// Accounts `owner` holds as primary or joint owner.
+    pub fn accounts_of(
+        &self,
+        owner: CustomerId,
+        page: PageRequest,
+    ) -> Result<Page<Account>, AppError> {
+        self.list_accounts(&AccountQuery::default().owner(owner), page)
+    }
+
+    pub fn add_holder(
+        &self,
+        ctx: &RequestContext,
+        account_id: AccountId,
+        customer: CustomerId,
+        role: HolderRole,
+    ) -> Result<(), AppError> {
+        let inputs = format!("account={account_id} customer={customer} role={role:?}");
+
+        self.audited(ctx, "add_holder", inputs, vec![account_id], || {
+            self.mutate(account_id, |account| {
+                authorize(account, &ctx.actor, HolderRole::can_manage)?;
+
+                account.add_holder(customer, role)?;
+                Ok(())
+            })
+        })
+    }
+
+    pub fn remove_holder(
+        &self,
+        ctx: &RequestContext,
+        account_id: AccountId,
+        customer: CustomerId,
+    ) -> Result<(), AppError> {
+        let inputs = format!("account={account_id} customer={customer}");
+
+        self.audited(ctx, "remove_holder", inputs, vec![account_id], || {
+            self.mutate(account_id, |account| {
+                authorize(account, &ctx.actor, HolderRole::can_manage)?;
+
+                account.remove_holder(customer)?;
+                Ok(())
+            })?;
+            self.alert_rules
+                .lock()
+                .unwrap()
+                .retain(|_, rule| rule.account_id != account_id || rule.customer != customer);
+            Ok(())
+        })
+    }
+
+    pub fn set_approval_threshold(
+        &self,
+        ctx: &RequestContext,
+        account_id: AccountId,
+        threshold: Option<Money>,
+    ) -> Result<(), AppError> {
+        let inputs = format!("account={account_id} threshold={threshold:?}");
+
+        self.audited(
+            ctx,
+            "set_approval_threshold",
+            inputs,
+            vec![account_id],
+            || {
+                self.mutate(account_id, |account| {
+                    authorize(account, &ctx.actor, HolderRole::can_manage)?;
+
+                    account.approval_threshold = threshold;
+                    Ok(())
+                })
+            },
+        )
+    }
+
+    /// Originates a loan for the owner of `account_id`, which it will be
+    /// paid into and repaid from. Nothing moves until it is disbursed.
+    pub fn originate_loan(
+        &self,
+        ctx: &RequestContext,
+        account_id: AccountId,
+        terms: LoanTerms,
+    ) -> Result<LoanId, AppError> {
+        let inputs = format!("account={account_id} {terms:?}");
+
+        self.audited(ctx, "originate_loan", inputs, vec![account_id], || {
+            let account = self.load(account_id)?;
+            authorize(&account, &ctx.actor, HolderRole::can_manage)?;
+
+            let mut next_id = self.next_loan_id.lock().unwrap();
+            let loan = Loan::originate(*next_id + 1, account.owner, account_id, terms)?;
+            *next_id = loan.id;
+
+            self.loans.lock().unwrap().insert(loan.id, loan);
+            Ok(*next_id)
+        })
+    }
+
+    /// Pays the principal into the linked account and returns its new
+    /// balance.
+    pub fn disburse_loan(&self, ctx: &RequestContext, loan_id: LoanId) -> Result<Money, AppError> {
+        let accounts = self.loan_accounts(loan_id);
+
+        self.audited(
+            ctx,
+            "disburse_loan",
+            format!("loan={loan_id}"),
+            accounts,
+            || {
+                self.update_loan(loan_id, |loan| {
+                    let principal = loan.disburse()?;
+                    self.mutate(loan.account_id, |account| {
+                        authorize(account, &ctx.actor, HolderRole::can_manage)?;
+                        Ok(account.deposit(principal)?)
+                    })
+                })
+            },
+        )
+    }
+
+    /// Takes a repayment made on `on` out of the linked account.
+    pub fn repay_loan(
+        &self,
+        ctx: &RequestContext,
+        loan_id: LoanId,
+        amount: Money,
+        on: NaiveDate,
+    ) -> Result<RepaymentSplit, AppError> {
+        let inputs = format!("loan={loan_id} amount={} on={on}", amount.0);
+        let accounts = self.loan_accounts(loan_id);
+
+        self.audited(ctx, "repay_loan", inputs, accounts, || {
+            self.update_loan(loan_id, |loan| {
+                let split = loan.repay(amount, on)?;
+                self.mutate(loan.account_id, |account| {
+                    authorize(account, &ctx.actor, HolderRole::can_transact)?;
+                    Ok(account.withdraw(split.total())?)
+                })?;
+                Ok(split)
+            })
+        })
+    }
+
+    /// Charges late fees for installments overdue on `on`; see
+    /// [`Loan::assess_late_fees`].
+    pub fn assess_late_fees(
+        &self,
+        ctx: &RequestContext,
+        loan_id: LoanId,
+        on: NaiveDate,
+    ) -> Result<Money, AppError> {
+        let inputs = format!("loan={loan_id} on={on}");
+        let accounts = self.loan_accounts(loan_id);
+
+        self.audited(ctx, "assess_late_fees", inputs, accounts, || {
+            self.update_loan(loan_id, |loan| Ok(loan.assess_late_fees(on)?))
+        })
+    }
+
+    pub fn loan(&self, loan_id: LoanId) -> Result<Loan, AppError> {
+        Ok(self
+            .loans
+            .lock()
+            .unwrap()
+            .get(&loan_id)
+            .cloned()
+            .ok_or(DomainError::LoanNotFound(loan_id))?)
+    }
+
+    pub fn payoff_quote(&self, loan_id: LoanId, on: NaiveDate) -> Result<PayoffQuote, AppError> {
+        Ok(self.loan(loan_id)?.payoff_quote(on))
+    }
+
+    /// Moves `terms.principal` out of `source_account` into a deposit
+    /// starting today.
+    pub fn open_term_deposit(
+        &self,
+        ctx: &RequestContext,
+        source_account: AccountId,
+        terms: TermDepositTerms,
+    ) -> Result<TermDepositId, AppError> {
+        let inputs = format!("account={source_account} {terms:?}");
+
+        self.audited(
+            ctx,
+            "open_term_deposit",
+            inputs,
+            vec![source_account],
+            || {
+                let mut next_id = self.next_term_deposit_id.lock().unwrap();
+                let principal = terms.principal;
 
-        self.repo.create(account)?;
-        Ok(*id)
+                let deposit = self.mutate(source_account, |account| {
+                    authorize(account, &ctx.actor, HolderRole::can_transact)?;
+
+                    let deposit = TermDeposit::open(
+                        *next_id + 1,
+                        account.owner,
+                        source_account,
+                        terms,
+                        self.clock.today(),
+                    )?;
+                    account.withdraw(principal)?;
+                    Ok(deposit)
+                })?;
+
+                *next_id = deposit.id;
+                self.term_deposits
+                    .lock()
+                    .unwrap()
+                    .insert(deposit.id, deposit);
+                Ok(*next_id)
+            },
+        )
+    }
+
+    /// Breaks a deposit before maturity, crediting the source account with
+    /// the principal and interest less the early-withdrawal penalty.
+    pub fn withdraw_term_deposit(
+        &self,
+        ctx: &RequestContext,
+        deposit_id: TermDepositId,
+    ) -> Result<TermDepositPayout, AppError> {
+        let accounts = self.term_deposit_accounts(deposit_id);
+
+        self.audited(
+            ctx,
+            "withdraw_term_deposit",
+            format!("deposit={deposit_id}"),
+            accounts,
+            || {
+                self.update_term_deposit(deposit_id, |deposit| {
+                    let payout = deposit.withdraw_early(self.clock.today())?;
+                    self.mutate(deposit.source_account, |account| {
+                        authorize(account, &ctx.actor, HolderRole::can_transact)?;
+                        Ok(account.deposit(payout.total())?)
+                    })?;
+                    Ok(payout)
+                })
+            },
+        )
+    }
+
+    /// Charges every active account on a product with a monthly fee that
+    /// fee, which may use the product's overdraft. Meant to run once a
+    /// month; accounts that cannot pay are reported and left unchanged.
+    pub fn charge_monthly_fees(&self) -> Result<AccountRunResults, AppError> {
+        let ctx = RequestContext::system();
+        let due = self.product_accounts(|account, product| {
+            account.status == AccountStatus::Active && !product.monthly_fee.0.is_zero()
+        })?;
+
+        Ok(due
+            .into_iter()
+            .map(|(account_id, product)| {
+                let inputs = format!("account={account_id} fee={}", product.monthly_fee);
+                let result =
+                    self.audited(&ctx, "charge_monthly_fee", inputs, vec![account_id], || {
+                        self.mutate(account_id, |account| {
+                            account.charge_fee(&product)?;
+                            Ok(product.monthly_fee)
+                        })
+                    });
+                (account_id, result)
+            })
+            .collect())
+    }
+
+    /// Credits every open account on a product a month's interest on its
+    /// balance at the product's rate. Meant to run once a month.
+    pub fn credit_monthly_interest(&self) -> Result<AccountRunResults, AppError> {
+        let ctx = RequestContext::system();
+        let due = self.product_accounts(|account, product| {
+            account.status != AccountStatus::Closed && !product.interest_rate.is_zero()
+        })?;
+
+        Ok(due
+            .into_iter()
+            .map(|(account_id, product)| {
+                let inputs = format!("account={account_id} rate={}", product.interest_rate);
+                let result = self.audited(
+                    &ctx,
+                    "credit_monthly_interest",
+                    inputs,
+                    vec![account_id],
+                    || {
+                        self.mutate(account_id, |account| {
+                            let interest = product.monthly_interest(account.balance);
+                            if !interest.0.is_zero() {
+                                account.deposit(interest)?;
+                            }
+                            Ok(interest)
+                        })
+                    },
+                );
+                (account_id, result)
+            })
+            .collect())
+    }
+
+    /// Accounts opened on one of the tenant's products that `wanted`
+    /// accepts, by id, with their product.
+    fn product_accounts(
+        &self,
+        wanted: impl Fn(&Account, &Product) -> bool,
+    ) -> Result<Vec<(AccountId, Product)>, AppError> {
+        Ok(self
+            .all_accounts()?
+            .into_iter()
+            .filter_map(|account| {
+                let product = self.tenant.product(account.product.as_deref()?)?;
+                wanted(&account, product).then(|| (account.id, product.clone()))
+            })
+            .collect())
+    }
+
+    /// Settles every active deposit that has matured by the clock's date,
+    /// following each one's maturity instruction. Deposits are handled
+    /// independently: one that fails stays active and is retried on the next
+    /// run.
+    pub fn process_maturities(&self) -> Vec<(TermDepositId, Result<MaturityOutcome, AppError>)> {
+        let today = self.clock.today();
+        let mut due: Vec<TermDepositId> = self
+            .term_deposits
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|deposit| {
+                deposit.status == TermDepositStatus::Active && deposit.is_mature(today)
+            })
+            .map(|deposit| deposit.id)
+            .collect();
+        due.sort_unstable();
+
+        due.into_iter()
+            .map(|deposit_id| (deposit_id, self.mature_term_deposit(deposit_id, today)))
+            .collect()
+    }
+
+    pub fn term_deposit(&self, deposit_id: TermDepositId) -> Result<TermDeposit, AppError> {
+        Ok(self
+            .term_deposits
+            .lock()
+            .unwrap()
+            .get(&deposit_id)
+            .cloned()
+            .ok_or(DomainError::TermDepositNotFound(deposit_id))?)
+    }
+
+    /// Opens a cash drawer for the calling teller with `float` in it. A
+    /// teller holds at most one open drawer at a time.
+    pub fn open_drawer(
+        &self,
+        ctx: &RequestContext,
+        float: CashCount,
+    ) -> Result<DrawerId, AppError> {
+        let inputs = format!("float={float:?}");
+
+        self.audited(ctx, "open_drawer", inputs, vec![], || {
+            let teller = Self::teller(&ctx.actor)?;
+            self.ensure_no_open_drawer(teller)?;
+
+            let mut next_id = self.next_drawer_id.lock().unwrap();
+            let drawer = CashDrawer::open(*next_id + 1, teller, self.clock.today(), float)?;
+            *next_id = drawer.id;
+
+            self.drawers.lock().unwrap().insert(drawer.id, drawer);
+            Ok(*next_id)
+        })
     }
 
-    pub fn process(
+    /// Takes a cash deposit or pays out a cash withdrawal over the counter
+    /// at the calling teller's drawer. `notes` are the notes and coins that
+    /// changed hands and must add up to the transaction amount.
+    pub fn process_cash(
         &self,
+        ctx: &RequestContext,
+        drawer_id: DrawerId,
         account_id: AccountId,
-        txn: Transaction
+        txn: Transaction,
+        notes: CashCount,
     ) -> Result<Money, AppError> {
+        let inputs = format!("drawer={drawer_id} account={account_id} {txn:?} notes={notes:?}");
+
+        self.audited(ctx, "process_cash", inputs, vec![account_id], || {
+            let teller = Self::teller(&ctx.actor)?;
+            let direction = match txn {
+                Transaction::Deposit(_) => CashDirection::In,
+                Transaction::Withdraw(_) => CashDirection::Out,
+                Transaction::Transfer { .. } => {
+                    return Err(DomainError::UnsupportedTransaction {
+                        account_id,
+                        kind: txn.kind(),
+                    }
+                    .into());
+                }
+            };
+            let counted = cash_total(&notes);
+            if counted != txn.amount() {
+                return Err(DomainError::CashMismatch {
+                    account_id,
+                    amount: txn.amount(),
+                    counted,
+                }
+                .into());
+            }
+
+            self.update_drawer(drawer_id, |drawer| {
+                drawer.record(teller, account_id, direction, notes)?;
+                self.execute(account_id, txn)
+            })
+        })
+    }
+
+    /// Balances the calling teller's shift on `counted` and passes the drawer
+    /// to teller `to`, who is accountable for it from that count on.
+    pub fn hand_off_drawer(
+        &self,
+        ctx: &RequestContext,
+        drawer_id: DrawerId,
+        to: TellerId,
+        counted: CashCount,
+    ) -> Result<ShiftReport, AppError> {
+        let inputs = format!("drawer={drawer_id} to={to} counted={counted:?}");
 
-        let mut account = self.repo.get(account_id)?
-            .ok_or_else(|| DomainError::AccountNotFound(account_id))?;
+        self.audited(ctx, "hand_off_drawer", inputs, vec![], || {
+            let teller = Self::teller(&ctx.actor)?;
+            self.ensure_no_open_drawer(to)?;
+            self.update_drawer(
+                drawer_id,
+                |drawer| Ok(drawer.hand_off(teller, to, counted)?),
+            )
+        })
+    }
+
+    /// Balances the last shift on `counted` and closes the drawer. The
+    /// report flags any overage or shortage.
+    pub fn close_drawer(
+        &self,
+        ctx: &RequestContext,
+        drawer_id: DrawerId,
+        counted: CashCount,
+    ) -> Result<ShiftReport, AppError> {
+        let inputs = format!("drawer={drawer_id} counted={counted:?}");
+
+        self.audited(ctx, "close_drawer", inputs, vec![], || {
+            let teller = Self::teller(&ctx.actor)?;
+            self.update_drawer(drawer_id, |drawer| Ok(drawer.close(teller, counted)?))
+        })
+    }
+
+    /// Alerts `customer` through the bank's notifier whenever `condition`
+    /// holds for `account_id`, which they must hold.
+    pub fn add_alert_rule(
+        &self,
+        ctx: &RequestContext,
+        customer: CustomerId,
+        account_id: AccountId,
+        condition: AlertCondition,
+    ) -> Result<AlertRuleId, AppError> {
+        let inputs = format!("customer={customer} account={account_id} {condition:?}");
 
-        let result: Result<Money, AppError> = match &txn {
-            Transaction::Transfer { to, amount } => {
-                self.transfer(account_id, *to, *amount)?;
-                Ok(account.balance)
+        self.audited(ctx, "add_alert_rule", inputs, vec![account_id], || {
+            Self::authorize_customer(&ctx.actor, customer)?;
+            let account = self.load(account_id)?;
+            if account.role_of(customer).is_none() {
+                return Err(DomainError::NotAHolder {
+                    account_id,
+                    customer,
+                }
+                .into());
             }
-            _ => {
-                let new_balance = account.apply_transaction(txn)?;
-                self.repo.update(account.clone())?;
-                Ok(new_balance)
+            let (AlertCondition::LargeWithdrawal(threshold)
+            | AlertCondition::LowBalance(threshold)) = condition;
+            if threshold.0.is_sign_negative() {
+                return Err(DomainError::InvalidAmount {
+                    account_id,
+                    amount: threshold,
+                }
+                .into());
             }
-        };
 
-        result.map_err(AppError::from)
+            let mut next_id = self.next_alert_rule_id.lock().unwrap();
+            *next_id += 1;
+            let rule = AlertRule {
+                id: *next_id,
+                customer,
+                account_id,
+                condition,
+            };
+            self.alert_rules.lock().unwrap().insert(rule.id, rule);
+            Ok(*next_id)
+        })
     }
 
-    fn transfer(
+    pub fn remove_alert_rule(
         &self,
-        from: AccountId,
-        to: AccountId,
-        amount: Money
+        ctx: &RequestContext,
+        rule_id: AlertRuleId,
     ) -> Result<(), AppError> {
+        self.audited(
+            ctx,
+            "remove_alert_rule",
+            format!("rule={rule_id}"),
+            vec![],
+            || {
+                let mut rules = self.alert_rules.lock().unwrap();
+                let rule = rules
+                    .get(&rule_id)
+                    .ok_or(DomainError::AlertRuleNotFound(rule_id))?;
+                Self::authorize_customer(&ctx.actor, rule.customer)?;
+                rules.remove(&rule_id);
+                Ok(())
+            },
+        )
+    }
 
-        if from == to {
-            return Err(DomainError::TransferToSelf.into());
+    pub fn alert_rules(&self, customer: CustomerId) -> Vec<AlertRule> {
+        self.alert_rules
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|rule| rule.customer == customer)
+            .cloned()
+            .collect()
+    }
+
+    pub fn cash_drawer(&self, drawer_id: DrawerId) -> Result<CashDrawer, AppError> {
+        Ok(self
+            .drawers
+            .lock()
+            .unwrap()
+            .get(&drawer_id)
+            .cloned()
+            .ok_or(DomainError::DrawerNotFound(drawer_id))?)
+    }
+
+    fn teller(actor: &Actor) -> Result<TellerId, DomainError> {
+        match actor {
+            Actor::Teller(teller) => Ok(*teller),
+            _ => Err(DomainError::TellerRequired),
         }
+    }
 
-        let mut src = self.repo.get(from)?
-            .ok_or_else(|| DomainError::AccountNotFound(from))?;
+    fn ensure_no_open_drawer(&self, teller: TellerId) -> Result<(), DomainError> {
+        let drawers = self.drawers.lock().unwrap();
+        match drawers
+            .values()
+            .find(|drawer| drawer.teller == teller && drawer.status == DrawerStatus::Open)
+        {
+            Some(drawer) => Err(DomainError::DrawerAlreadyHeld {
+                teller,
+                drawer_id: drawer.id,
+            }),
+            None => Ok(()),
+        }
+    }
 
-        let mut dest = self.repo.get(to)?
-            .ok_or_else(|| DomainError::AccountNotFound(to))?;
+    fn update_drawer<T>(
+        &self,
+        drawer_id: DrawerId,
+        f: impl FnOnce(&mut CashDrawer) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let mut drawers = self.drawers.lock().unwrap();
+        let mut drawer = drawers
+            .get(&drawer_id)
+            .cloned()
+            .ok_or(DomainError::DrawerNotFound(drawer_id))?;
 
-        src.withdraw(amount)?;
+        let value = f(&mut drawer)?;
+        drawers.insert(drawer_id, drawer);
+        Ok(value)
+    }
 
-        dest.deposit(amount)?;
+    /// Moves the amount from `from` into the suspense account, where it is
+    /// held until the payment settles or comes back, and queues the
+    /// payment for clearing with `debtor_name` as the payer shown to the
+    /// creditor. The account needs an account number to be identified at
+    /// the other institution.
+    pub fn transfer_external(
+        &self,
+        ctx: &RequestContext,
+        from: AccountId,
+        debtor_name: &str,
+        transfer: ExternalTransfer,
+    ) -> Result<OutgoingPayment, AppError> {
+        let inputs = format!("account={from} {transfer:?}");
+        let suspense = self.suspense_account()?;
 
-        self.repo.update(src)?;
-        self.repo.update(dest)?;
+        self.audited(
+            ctx,
+            "transfer_external",
+            inputs,
+            vec![from, suspense],
+            || {
+                let mut next_id = self.next_payment_id.lock().unwrap();
 
-        Ok(())
+                let payment = self
+                    .repo
+                    .modify(&self.tenant.id, &[from, suspense], |accounts| {
+                        let (src, dest) = accounts.split_at_mut(1);
+                        authorize(&src[0], &ctx.actor, HolderRole::can_transact)?;
+
+                        let debtor_number = src[0]
+                            .number
+                            .clone()
+                            .ok_or(DomainError::AccountNumberMissing(from))?;
+                        debit(
+                            &self.tenant,
+                            &mut src[0],
+                            transfer.amount,
+                            self.clock.today(),
+                        )?;
+                        dest[0].deposit(transfer.amount)?;
+
+                        Ok(OutgoingPayment {
+                            end_to_end_id: format!("E2E{:012}", *next_id + 1),
+                            debtor_account: from,
+                            debtor_name: debtor_name.into(),
+                            debtor_number,
+                            transfer,
+                            requested_execution: self.clock.today(),
+                        })
+                    })
+                    .map_err(not_found)?;
+
+                *next_id += 1;
+                self.external_payments.lock().unwrap().insert(
+                    payment.end_to_end_id.clone(),
+                    ExternalPayment::new(payment.clone()),
+                );
+                Ok(payment)
+            },
+        )
     }
 
-}
+    /// External transfers debited but not yet handed over for clearing.
+    pub fn outgoing_payments(&self) -> Vec<OutgoingPayment> {
+        self.external_payments
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|external| external.status == ExternalPaymentStatus::Pending)
+            .map(|external| external.payment.clone())
+            .collect()
+    }
 
-#[cfg(test)]
-pub mod tests {
-    use std::sync::Arc;
+    pub fn external_payment(&self, end_to_end_id: &str) -> Result<ExternalPayment, AppError> {
+        Ok(self
+            .external_payments
+            .lock()
+            .unwrap()
+            .get(end_to_end_id)
+            .cloned()
+            .ok_or_else(|| DomainError::ExternalPaymentNotFound(end_to_end_id.into()))?)
+    }
 
-    use bank_core::{account::{AccountRepository, Money, Transaction}, customer::Customer};
-    use bank_infra::storage::InMemoryRepo;
+    /// Writes every pending external transfer to a pain.001 file and marks
+    /// them submitted. Nothing is marked until the file has been produced,
+    /// so a file that fails validation can be fixed and exported again.
+    ///
+    /// The file bypasses the clearing gateway, so their outcomes never
+    /// arrive by polling; report them with [`Bank::record_clearing_event`]
+    /// from the clearing house's status report.
+    pub fn export_payment_file(&self, header: &PaymentFileHeader) -> Result<String, Iso20022Error> {
+        let _submitting = self.submitting.lock().unwrap();
+        let mut external_payments = self.external_payments.lock().unwrap();
+        let pending: Vec<&mut ExternalPayment> = external_payments
+            .values_mut()
+            .filter(|external| external.status == ExternalPaymentStatus::Pending)
+            .collect();
 
-    use crate::bank::Bank;
+        let payments: Vec<OutgoingPayment> = pending
+            .iter()
+            .map(|external| external.payment.clone())
+            .collect();
+        let xml = Pain001Document::from_payments(header, &payments).to_xml()?;
 
-    #[test]
-    fn test_bank_will_create_account_successfully() {
-        let customer: Customer = Customer::builder(1).build();
-        let repo: InMemoryRepo = InMemoryRepo::new();
-        let mut bank = Bank::new(Arc::new(repo));
-        let account_id = bank.create_account(customer.id).unwrap();
+        for external in pending {
+            external.status = ExternalPaymentStatus::Submitted;
+        }
+        Ok(xml)
+    }
 
-        assert_eq!(account_id, 1);
-        let account = bank.repo.get(account_id).unwrap().unwrap();
+    /// Hands every pending external transfer to the clearing gateway.
+    /// Payments the clearing house refuses are credited back to the debtor;
+    /// payments that could not be handed over stay pending for the next
+    /// run.
+    pub fn submit_external_payments(&self) -> Result<ClearingResults, AppError> {
+        let gateway = self.clearing_gateway()?;
+        let _submitting = self.submitting.lock().unwrap();
 
-        assert_eq!(account.owner, customer.id);
-        assert_eq!(account.balance.0, 0.into());
+        let results = self
+            .outgoing_payments()
+            .into_iter()
+            .map(|payment| {
+                let submitted = gateway.submit(&payment);
+                let result = self.record_submission(&payment.end_to_end_id, submitted);
+                (payment.end_to_end_id, result)
+            })
+            .collect();
+        Ok(ClearingResults {
+            results,
+            dead_lettered: Vec::new(),
+        })
     }
 
-    #[test]
-    fn test_bank_will_process_deposit() {
-        let repo = Arc::new(InMemoryRepo::default());
-        let mut bank = Bank::new(repo);
+    /// Applies the outcomes the clearing gateway has reported since the last
+    /// run: settled payments leave the suspense account, rejected ones go
+    /// back to the debtor from it, and returned ones are credited to the
+    /// debtor as they come back in. Events that fail for a reason that may
+    /// pass, such as a return to an account that has since been closed, are
+    /// kept in [`Bank::clearing_backlog`] and retried first on the next run.
+    /// The rest go to [`Bank::clearing_dead_letters`].
+    pub fn process_clearing_events(&self) -> Result<ClearingResults, AppError> {
+        let polled = self.clearing_gateway()?.poll()?;
+        let mut events = std::mem::take(&mut *self.clearing_backlog.lock().unwrap());
+        events.extend(polled);
 
-        let customer = Customer::builder(1).build();
-        let account_id = bank.create_account(customer.id).unwrap();
+        let ctx = RequestContext::system();
+        let mut retry = Vec::new();
+        let mut dead_lettered = Vec::new();
+        let results = events
+            .into_iter()
+            .map(|event| {
+                let result = self.apply_clearing_event(&ctx, &event);
+                let end_to_end_id = event.end_to_end_id.clone();
+                match &result {
+                    Ok(_) => {}
+                    Err(err) if may_apply_later(err) => retry.push(event),
+                    Err(err) => dead_lettered.push(DeadLetter {
+                        event,
+                        code: err.code().into(),
+                        message: err.to_string(),
+                    }),
+                }
+                (end_to_end_id, result)
+            })
+            .collect();
 
-        let result = bank.process(account_id, Transaction::Deposit(Money(100.into())));
+        self.clearing_backlog.lock().unwrap().splice(0..0, retry);
+        self.clearing_dead_letters
+            .lock()
+            .unwrap()
+            .extend(dead_lettered.iter().cloned());
+        Ok(ClearingResults {
+            results,
+            dead_lettered,
+        })
+    }
 
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap().0, 100.into());
+    /// Applies an outcome reported outside the clearing gateway, such as
+    /// one read from the status report for an exported payment file.
+    pub fn record_clearing_event(
+        &self,
+        ctx: &RequestContext,
+        event: &ClearingEvent,
+    ) -> Result<ExternalPaymentStatus, AppError> {
+        self.apply_clearing_event(ctx, event)
+    }
+
+    /// Records what the gateway made of a submitted payment.
+    fn record_submission(
+        &self,
+        end_to_end_id: &str,
+        submitted: Result<(), ClearingError>,
+    ) -> Result<ExternalPaymentStatus, AppError> {
+        let ctx = RequestContext::system();
+        let inputs = format!("payment={end_to_end_id}");
+        let accounts = self.external_payment_accounts(end_to_end_id);
+        let suspense = self.suspense_account()?;
+
+        self.audited(&ctx, "submit_external_payment", inputs, accounts, || {
+            self.update_external_payment(end_to_end_id, |external| {
+                match submitted {
+                    Ok(()) => external.submit()?,
+                    Err(ClearingError::Rejected(reason)) => {
+                        external.reject(&reason)?;
+                        let payment = &external.payment;
+                        self.transfer(suspense, payment.debtor_account, payment.transfer.amount)?;
+                    }
+                    Err(err) => return Err(err.into()),
+                }
+                Ok(external.status.clone())
+            })
+        })
+    }
+
+    fn apply_clearing_event(
+        &self,
+        ctx: &RequestContext,
+        event: &ClearingEvent,
+    ) -> Result<ExternalPaymentStatus, AppError> {
+        let inputs = format!("payment={} {:?}", event.end_to_end_id, event.outcome);
+        let accounts = self.external_payment_accounts(&event.end_to_end_id);
+        let suspense = self.suspense_account()?;
+
+        self.audited(ctx, "clearing_event", inputs, accounts, || {
+            self.update_external_payment(&event.end_to_end_id, |external| {
+                let debtor = external.payment.debtor_account;
+                let amount = external.payment.transfer.amount;
+
+                match &event.outcome {
+                    ClearingOutcome::Settled => {
+                        external.settle()?;
+                        self.mutate(suspense, |account| Ok(account.withdraw(amount)?))?;
+                    }
+                    ClearingOutcome::Rejected(reason) => {
+                        external.reject(reason)?;
+                        self.transfer(suspense, debtor, amount)?;
+                    }
+                    ClearingOutcome::Returned(reason) => {
+                        external.return_funds(reason)?;
+                        self.mutate(debtor, |account| Ok(account.deposit(amount)?))?;
+                    }
+                }
+                Ok(external.status.clone())
+            })
+        })
+    }
+
+    fn update_external_payment<T>(
+        &self,
+        end_to_end_id: &str,
+        f: impl FnOnce(&mut ExternalPayment) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let mut external_payments = self.external_payments.lock().unwrap();
+        let mut external = external_payments
+            .get(end_to_end_id)
+            .cloned()
+            .ok_or_else(|| DomainError::ExternalPaymentNotFound(end_to_end_id.into()))?;
+
+        let value = f(&mut external)?;
+        external_payments.insert(end_to_end_id.into(), external);
+        Ok(value)
+    }
+
+    fn external_payment_accounts(&self, end_to_end_id: &str) -> Vec<AccountId> {
+        let debtor = self
+            .external_payments
+            .lock()
+            .unwrap()
+            .get(end_to_end_id)
+            .map(|external| external.payment.debtor_account);
+        debtor.into_iter().chain(self.suspense_account).collect()
+    }
+
+    fn suspense_account(&self) -> Result<AccountId, AppError> {
+        Ok(self
+            .suspense_account
+            .ok_or(DomainError::NotConfigured(Feature::SuspenseAccount))?)
+    }
+
+    fn clearing_gateway(&self) -> Result<Arc<dyn ClearingGateway>, AppError> {
+        Ok(self
+            .clearing
+            .clone()
+            .ok_or(DomainError::NotConfigured(Feature::ClearingGateway))?)
+    }
+
+    /// Credits the statement entries for accounts held in `currency` and
+    /// returns the new balance per entry reference. Entries credited by an
+    /// earlier import are left out, so the same statement can be imported
+    /// twice; entries that fail are retried on the next import.
+    pub fn import_statement(
+        &self,
+        payments: &[IncomingPayment],
+        currency: &str,
+    ) -> Vec<(String, Result<Money, AppError>)> {
+        payments
+            .iter()
+            .filter(|payment| !self.imported.lock().unwrap().contains(&payment.reference))
+            .filter_map(|payment| {
+                let result = self.credit_incoming(payment, currency).transpose()?;
+                Some((payment.reference.clone(), result))
+            })
+            .collect()
+    }
+
+    /// Credits one statement entry, unless a concurrent import got to it
+    /// first. The audit record names the entry the credit came from.
+    fn credit_incoming(
+        &self,
+        payment: &IncomingPayment,
+        currency: &str,
+    ) -> Result<Option<Money>, AppError> {
+        if payment.currency != currency {
+            return Err(DomainError::CurrencyMismatch {
+                expected: currency.into(),
+                found: payment.currency.clone(),
+            }
+            .into());
+        }
+
+        let account = self.find_account(&AccountRef::Number(payment.creditor_account.clone()))?;
+        let inputs = format!(
+            "reference={} account={} amount={}",
+            payment.reference, account.id, payment.amount.0
+        );
+        let ctx = RequestContext::system();
+
+        self.audited(&ctx, "import_payment", inputs, vec![account.id], || {
+            // Claimed before crediting, so the entry is credited once however
+            // many imports of the statement run at the same time.
+            if !self
+                .imported
+                .lock()
+                .unwrap()
+                .insert(payment.reference.clone())
+            {
+                return Ok(None);
+            }
+            let result = self.execute(account.id, Transaction::Deposit(payment.amount));
+            if result.is_err() {
+                self.imported.lock().unwrap().remove(&payment.reference);
+            }
+            result.map(Some)
+        })
+    }
+
+    /// Stores the customer's profile. Only the id is audited, so that the
+    /// personal fields can still be erased later.
+    pub fn register_customer(
+        &self,
+        ctx: &RequestContext,
+        customer: Customer,
+    ) -> Result<(), AppError> {
+        let inputs = format!("customer={}", customer.id);
+
+        self.audited(ctx, "register_customer", inputs, vec![], || {
+            Ok(self.customers.save(&self.tenant.id, customer)?)
+        })
+    }
+
+    pub fn customer(&self, customer_id: CustomerId) -> Result<Customer, AppError> {
+        Ok(self
+            .customers
+            .get(&self.tenant.id, customer_id)?
+            .ok_or(DomainError::CustomerNotFound(customer_id))?)
+    }
+
+    /// Everything held about the customer: profile, accounts in any role,
+    /// their ledger, loans, term deposits and external payments, and the
+    /// audit records of what they did and of what staff and system jobs did
+    /// to their accounts. What other holders of a joint account did is left
+    /// out. Customers may only export their own data; the request is itself
+    /// audited and shows up in the archive.
+    pub fn export_customer_data(
+        &self,
+        ctx: &RequestContext,
+        customer_id: CustomerId,
+    ) -> Result<CustomerDataExport, AppError> {
+        let accounts = self.holdings(customer_id)?;
+        let account_ids: Vec<AccountId> = accounts.iter().map(|account| account.id).collect();
+
+        let inputs = format!("customer={customer_id}");
+        self.audited(
+            ctx,
+            "export_customer_data",
+            inputs,
+            account_ids.clone(),
+            || {
+                Self::authorize_customer(&ctx.actor, customer_id)?;
+                self.customer(customer_id).map(|_| ())
+            },
+        )?;
+
+        let mut ledger = Vec::new();
+        for account_id in &account_ids {
+            ledger.extend(self.audit.ledger(*account_id)?);
+        }
+        ledger.sort_by_key(|entry| entry.seq);
+
+        let audit = self
+            .audit
+            .records()?
+            .into_iter()
+            .filter(|record| match &record.actor {
+                Actor::Customer(actor) => *actor == customer_id,
+                Actor::Teller(_) | Actor::System(_) => {
+                    record.accounts.iter().any(|id| account_ids.contains(id))
+                }
+            })
+            .collect();
+
+        let mut loans: Vec<Loan> = self
+            .loans
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|loan| loan.borrower == customer_id)
+            .cloned()
+            .collect();
+        loans.sort_by_key(|loan| loan.id);
+
+        let mut term_deposits: Vec<TermDeposit> = self
+            .term_deposits
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|deposit| deposit.owner == customer_id)
+            .cloned()
+            .collect();
+        term_deposits.sort_by_key(|deposit| deposit.id);
+
+        let external_payments = self
+            .external_payments
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|payment| account_ids.contains(&payment.payment.debtor_account))
+            .cloned()
+            .collect();
+
+        Ok(CustomerDataExport {
+            customer: self.customer(customer_id)?,
+            exported_on: self.clock.today(),
+            accounts,
+            ledger,
+            audit,
+            loans,
+            term_deposits,
+            external_payments,
+        })
+    }
+
+    /// Pseudonymizes the customer's profile. Accounts, ledger, audit trail
+    /// and payment records are retained as the law requires; they refer to
+    /// the customer by id only, apart from the payer name on external
+    /// payments. Refused while an account the customer holds in any role
+    /// has a balance, or a loan or term deposit of theirs is still open.
+    pub fn erase_customer(
+        &self,
+        ctx: &RequestContext,
+        customer_id: CustomerId,
+    ) -> Result<Customer, AppError> {
+        let account_ids: Vec<AccountId> = self
+            .holdings(customer_id)?
+            .into_iter()
+            .map(|account| account.id)
+            .collect();
+
+        let inputs = format!("customer={customer_id}");
+        self.audited(ctx, "erase_customer", inputs, account_ids.clone(), || {
+            Self::authorize_customer(&ctx.actor, customer_id)?;
+            let mut customer = self.customer(customer_id)?;
+            let pseudonym = getrandom::u64().map_err(|err| {
+                RepoError::Storage(format!("no randomness for a pseudonym: {err}"))
+            })?;
+
+            let loans = self.loans.lock().unwrap();
+            if let Some(loan) = loans
+                .values()
+                .find(|loan| loan.borrower == customer_id && loan.status != LoanStatus::PaidOff)
+            {
+                return Err(DomainError::LoanStatusConflict {
+                    loan_id: loan.id,
+                    status: loan.status.clone(),
+                }
+                .into());
+            }
+            let deposits = self.term_deposits.lock().unwrap();
+            if let Some(deposit) = deposits.values().find(|deposit| {
+                deposit.owner == customer_id && deposit.status == TermDepositStatus::Active
+            }) {
+                return Err(DomainError::TermDepositStatusConflict {
+                    deposit_id: deposit.id,
+                    status: deposit.status.clone(),
+                }
+                .into());
+            }
+
+            // Holding the accounts keeps a deposit from landing between the
+            // balance check and the erasure.
+            self.repo
+                .modify(&self.tenant.id, &account_ids, |accounts| {
+                    if let Some(account) =
+                        accounts.iter().find(|account| !account.balance.0.is_zero())
+                    {
+                        return Err(DomainError::BalanceNotZero {
+                            account_id: account.id,
+                            balance: account.balance,
+                        }
+                        .into());
+                    }
+
+                    customer.pseudonymize(self.clock.today(), pseudonym);
+                    self.customers.save(&self.tenant.id, customer.clone())?;
+                    Ok(customer)
+                })
+                .map_err(not_found)
+        })
+    }
+
+    /// Customers may only act on their own data; staff and system jobs on
+    /// anyone's.
+    fn authorize_customer(actor: &Actor, customer: CustomerId) -> Result<(), DomainError> {
+        match actor {
+            Actor::Customer(caller) if *caller != customer => {
+                Err(DomainError::CustomerAccessDenied {
+                    customer,
+                    caller: *caller,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Every account the customer holds in any role, by id.
+    fn holdings(&self, customer_id: CustomerId) -> Result<Vec<Account>, AppError> {
+        let mut holdings = self.all_accounts()?;
+        holdings.retain(|account| account.role_of(customer_id).is_some());
+        Ok(holdings)
+    }
+
+    /// Every account in the repository, by id.
+    fn all_accounts(&self) -> Result<Vec<Account>, AppError> {
+        let mut all = Vec::new();
+        let mut page = PageRequest::first(MAX_PAGE_SIZE);
+        loop {
+            let accounts = self
+                .repo
+                .list(&self.tenant.id, &AccountQuery::default(), page)?;
+            all.extend(accounts.items);
+            match accounts.next {
+                Some(cursor) => page = PageRequest::next(cursor, MAX_PAGE_SIZE),
+                None => return Ok(all),
+            }
+        }
+    }
+
+    /// The account as it stood at `at`, rebuilt from the audit log. Changes
+    /// recorded at exactly `at` are included.
+    pub fn account_at(&self, account_id: AccountId, at: SystemTime) -> Result<Account, AppError> {
+        Ok(self
+            .audit
+            .account_at(account_id, at)?
+            .ok_or(DomainError::AccountNotFound(account_id))?)
+    }
+
+    /// Copies the whole bank. Operations wait while it is taken, so the
+    /// accounts always agree with the audit log and the products in flight.
+    pub fn take_snapshot(&self) -> Result<BankSnapshot, AppError> {
+        let _quiet = self.operations.write().unwrap();
+
+        let (next_approval_id, pending_approvals) = self.approvals.all();
+        let mut loans: Vec<Loan> = self.loans.lock().unwrap().values().cloned().collect();
+        loans.sort_by_key(|loan| loan.id);
+        let mut term_deposits: Vec<TermDeposit> = self
+            .term_deposits
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect();
+        term_deposits.sort_by_key(|deposit| deposit.id);
+        let mut drawers: Vec<CashDrawer> = self.drawers.lock().unwrap().values().cloned().collect();
+        drawers.sort_by_key(|drawer| drawer.id);
+        let mut imported: Vec<String> = self.imported.lock().unwrap().iter().cloned().collect();
+        imported.sort();
+        let alert_rules = self.alert_rules.lock().unwrap().values().cloned().collect();
+
+        Ok(BankSnapshot {
+            version: SNAPSHOT_VERSION,
+            taken_at: SystemTime::now(),
+            accounts: self.all_accounts()?,
+            customers: self.customers.list(&self.tenant.id)?,
+            next_approval_id,
+            pending_approvals,
+            next_loan_id: *self.next_loan_id.lock().unwrap(),
+            loans,
+            next_term_deposit_id: *self.next_term_deposit_id.lock().unwrap(),
+            term_deposits,
+            next_drawer_id: *self.next_drawer_id.lock().unwrap(),
+            drawers,
+            next_payment_id: *self.next_payment_id.lock().unwrap(),
+            external_payments: self
+                .external_payments
+                .lock()
+                .unwrap()
+                .values()
+                .cloned()
+                .collect(),
+            clearing_backlog: self.clearing_backlog.lock().unwrap().clone(),
+            clearing_dead_letters: self.clearing_dead_letters.lock().unwrap().clone(),
+            imported,
+            next_alert_rule_id: *self.next_alert_rule_id.lock().unwrap(),
+            alert_rules,
+            audit: self.audit.records()?,
+        })
+    }
+
+    /// Loads `snapshot` into this bank, which must serve the tenant it was
+    /// taken from and not hold any accounts or history yet. Configuration
+    /// such as the clock, account numbering and clearing gateway stays as
+    /// the bank was built.
+    pub fn restore_snapshot(&self, snapshot: BankSnapshot) -> Result<(), AppError> {
+        let _quiet = self.operations.write().unwrap();
+
+        let has_accounts = !self
+            .repo
+            .list(
+                &self.tenant.id,
+                &AccountQuery::default(),
+                PageRequest::first(1),
+            )?
+            .items
+            .is_empty();
+        if has_accounts || !self.audit.records()?.is_empty() {
+            return Err(SnapshotError::TargetNotEmpty.into());
+        }
+        if let Some(account) = snapshot
+            .accounts
+            .iter()
+            .find(|account| account.tenant != self.tenant.id)
+        {
+            return Err(SnapshotError::TenantMismatch(account.tenant.clone()).into());
+        }
+
+        // Restored first, as it refuses snapshots whose history was altered.
+        self.audit.restore(snapshot.audit)?;
+        for account in snapshot.accounts {
+            self.repo.create(&self.tenant.id, account)?;
+        }
+        for customer in snapshot.customers {
+            self.customers.save(&self.tenant.id, customer)?;
+        }
+
+        self.approvals
+            .restore(snapshot.next_approval_id, snapshot.pending_approvals);
+        *self.next_loan_id.lock().unwrap() = snapshot.next_loan_id;
+        *self.loans.lock().unwrap() = snapshot
+            .loans
+            .into_iter()
+            .map(|loan| (loan.id, loan))
+            .collect();
+        *self.next_term_deposit_id.lock().unwrap() = snapshot.next_term_deposit_id;
+        *self.term_deposits.lock().unwrap() = snapshot
+            .term_deposits
+            .into_iter()
+            .map(|deposit| (deposit.id, deposit))
+            .collect();
+        *self.next_drawer_id.lock().unwrap() = snapshot.next_drawer_id;
+        *self.drawers.lock().unwrap() = snapshot
+            .drawers
+            .into_iter()
+            .map(|drawer| (drawer.id, drawer))
+            .collect();
+        *self.next_payment_id.lock().unwrap() = snapshot.next_payment_id;
+        *self.external_payments.lock().unwrap() = snapshot
+            .external_payments
+            .into_iter()
+            .map(|payment| (payment.payment.end_to_end_id.clone(), payment))
+            .collect();
+        *self.clearing_backlog.lock().unwrap() = snapshot.clearing_backlog;
+        *self.clearing_dead_letters.lock().unwrap() = snapshot.clearing_dead_letters;
+        *self.imported.lock().unwrap() = snapshot.imported.into_iter().collect();
+        *self.next_alert_rule_id.lock().unwrap() = snapshot.next_alert_rule_id;
+        *self.alert_rules.lock().unwrap() = snapshot
+            .alert_rules
+            .into_iter()
+            .map(|rule| (rule.id, rule))
+            .collect();
+        Ok(())
+    }
+
+    /// Writes a snapshot of the bank to `path`, for seeding test
+    /// environments with [`Bank::load_snapshot`].
+    pub fn save_snapshot(&self, path: impl AsRef<Path>) -> Result<(), AppError> {
+        Ok(write_snapshot(path, &self.take_snapshot()?)?)
+    }
+
+    pub fn load_snapshot(&self, path: impl AsRef<Path>) -> Result<(), AppError> {
+        self.restore_snapshot(read_snapshot(path)?)
+    }
+
+    fn mature_term_deposit(
+        &self,
+        deposit_id: TermDepositId,
+        today: NaiveDate,
+    ) -> Result<MaturityOutcome, AppError> {
+        let ctx = RequestContext::system();
+        let accounts = self.term_deposit_accounts(deposit_id);
+
+        self.audited(
+            &ctx,
+            "mature_term_deposit",
+            format!("deposit={deposit_id}"),
+            accounts,
+            || {
+                let mut next_id = self.next_term_deposit_id.lock().unwrap();
+
+                let (outcome, rollover) = self.update_term_deposit(deposit_id, |deposit| {
+                    let payout = deposit.mature(today)?;
+
+                    let (paid_out, rolled_principal) = match deposit.terms.instruction {
+                        MaturityInstruction::Payout => (payout.total(), None),
+                        MaturityInstruction::RolloverPrincipal => {
+                            (payout.interest, Some(payout.principal))
+                        }
+                        MaturityInstruction::RolloverAll => {
+                            (Money::default(), Some(payout.total()))
+                        }
+                    };
+
+                    let rollover = rolled_principal
+                        .map(|principal| {
+                            let terms = TermDepositTerms {
+                                principal,
+                                ..deposit.terms.clone()
+                            };
+                            TermDeposit::open(
+                                *next_id + 1,
+                                deposit.owner,
+                                deposit.source_account,
+                                terms,
+                                deposit.maturity,
+                            )
+                        })
+                        .transpose()?;
+
+                    if paid_out.0 > 0.into() {
+                        self.mutate(deposit.source_account, |account| {
+                            Ok(account.deposit(paid_out)?)
+                        })?;
+                    }
+                    if let Some(rollover) = &rollover {
+                        deposit.status = TermDepositStatus::RolledOver(rollover.id);
+                    }
+
+                    let outcome = MaturityOutcome {
+                        deposit_id,
+                        payout,
+                        paid_out,
+                        rolled_into: rollover.as_ref().map(|rollover| rollover.id),
+                    };
+                    Ok((outcome, rollover))
+                })?;
+
+                if let Some(rollover) = rollover {
+                    *next_id = rollover.id;
+                    self.term_deposits
+                        .lock()
+                        .unwrap()
+                        .insert(rollover.id, rollover);
+                }
+                Ok(outcome)
+            },
+        )
+    }
+
+    fn update_term_deposit<T>(
+        &self,
+        deposit_id: TermDepositId,
+        f: impl FnOnce(&mut TermDeposit) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let mut deposits = self.term_deposits.lock().unwrap();
+        let mut deposit = deposits
+            .get(&deposit_id)
+            .cloned()
+            .ok_or(DomainError::TermDepositNotFound(deposit_id))?;
+
+        let value = f(&mut deposit)?;
+        deposits.insert(deposit_id, deposit);
+        Ok(value)
+    }
+
+    fn term_deposit_accounts(&self, deposit_id: TermDepositId) -> Vec<AccountId> {
+        self.term_deposits
+            .lock()
+            .unwrap()
+            .get(&deposit_id)
+            .map(|deposit| vec![deposit.source_account])
+            .unwrap_or_default()
+    }
+
+    /// Applies `f` to a copy of the loan and keeps the copy only if `f`
+    /// succeeds, so a failed account posting leaves the loan untouched.
+    fn update_loan<T>(
+        &self,
+        loan_id: LoanId,
+        f: impl FnOnce(&mut Loan) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let mut loans = self.loans.lock().unwrap();
+        let mut loan = loans
+            .get(&loan_id)
+            .cloned()
+            .ok_or(DomainError::LoanNotFound(loan_id))?;
+
+        let value = f(&mut loan)?;
+        loans.insert(loan_id, loan);
+        Ok(value)
+    }
+
+    fn loan_accounts(&self, loan_id: LoanId) -> Vec<AccountId> {
+        self.loans
+            .lock()
+            .unwrap()
+            .get(&loan_id)
+            .map(|loan| vec![loan.account_id])
+            .unwrap_or_default()
+    }
+
+    fn execute(&self, account_id: AccountId, txn: Transaction) -> Result<Money, AppError> {
+        check_transaction_limit(&self.tenant, account_id, &txn)?;
+        match txn {
+            Transaction::Transfer { to, amount } => self.transfer(account_id, to, amount),
+            Transaction::Withdraw(amount) => self.mutate(account_id, |account| {
+                Ok(debit(&self.tenant, account, amount, self.clock.today())?)
+            }),
+            txn => self.mutate(account_id, |account| Ok(account.apply_transaction(txn)?)),
+        }
+    }
+
+    /// Applies `f` to the account while the repository holds it exclusively.
+    fn mutate<T>(
+        &self,
+        account_id: AccountId,
+        f: impl FnOnce(&mut Account) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        self.repo
+            .modify(&self.tenant.id, &[account_id], |accounts| {
+                f(&mut accounts[0])
+            })
+            .map_err(not_found)
+    }
+
+    /// Runs `op` and records it in the audit log along with the state of
+    /// `accounts` before and after. The accounts stay locked from the first
+    /// snapshot until the record is appended, so concurrent operations on
+    /// them neither show up in each other's snapshots nor reach the log out
+    /// of order.
+    fn audited<T: Debug>(
+        &self,
+        ctx: &RequestContext,
+        action: &str,
+        inputs: String,
+        accounts: Vec<AccountId>,
+        op: impl FnOnce() -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let _running = self.operations.read().unwrap();
+        let started = Instant::now();
+        let held = self.account_locks.lock(&accounts);
+        let before = self.snapshot(&accounts)?;
+        let result = op();
+        let after = self.snapshot(&accounts)?;
+
+        let outcome = outcome(&result);
+        self.metrics
+            .record_operation(action, outcome, started.elapsed());
+        match &result {
+            Ok(_) => tracing::debug!(action, "operation succeeded"),
+            Err(err) => tracing::warn!(action, code = err.code(), error = %err, "operation failed"),
+        }
+
+        let entry = AuditEntry::from_result(action, inputs, accounts, before, after, &result);
+        let record = self.audit.append(ctx, entry)?;
+        drop(held);
+        if result.is_ok() {
+            self.dispatch_alerts(&record);
+        }
+
+        result
+    }
+
+    /// Sends the alerts the committed operation in `record` raises. Delivery
+    /// problems are logged rather than failing an operation that already
+    /// happened.
+    fn dispatch_alerts(&self, record: &AuditRecord) {
+        let Some(notifier) = &self.notifier else {
+            return;
+        };
+        let rules: Vec<AlertRule> = self.alert_rules.lock().unwrap().values().cloned().collect();
+
+        for rule in rules {
+            for alert in record
+                .changes
+                .iter()
+                .filter_map(|change| rule.evaluate(change))
+            {
+                if !self.alert_limiter.allow(rule.account_id, record.at) {
+                    tracing::debug!(rule = rule.id, "alert suppressed by rate limit");
+                    continue;
+                }
+
+                let recipient = self
+                    .customers
+                    .get(&self.tenant.id, rule.customer)
+                    .ok()
+                    .flatten()
+                    .and_then(|customer| customer.email);
+                let notification = Notification {
+                    rule_id: rule.id,
+                    customer: rule.customer,
+                    recipient,
+                    account_id: rule.account_id,
+                    alert,
+                    at: record.at,
+                };
+                if let Err(err) = notifier.notify(&notification) {
+                    tracing::warn!(rule = rule.id, error = %err, "alert not delivered");
+                }
+            }
+        }
+    }
+
+    fn snapshot(&self, accounts: &[AccountId]) -> Result<Vec<Option<Account>>, AppError> {
+        accounts
+            .iter()
+            .map(|account_id| Ok(self.repo.get(&self.tenant.id, *account_id)?))
+            .collect()
+    }
+
+    fn load(&self, account_id: AccountId) -> Result<Account, AppError> {
+        Ok(self
+            .repo
+            .get(&self.tenant.id, account_id)?
+            .ok_or(DomainError::AccountNotFound(account_id))?)
+    }
+
+    /// Moves `amount` between the accounts atomically and returns the new
+    /// balance of `from`.
+    #[tracing::instrument(
+        name = "bank.transfer",
+        skip(self, amount),
+        fields(amount = %amount.0, outcome = field::Empty)
+    )]
+    fn transfer(&self, from: AccountId, to: AccountId, amount: Money) -> Result<Money, AppError> {
+        if from == to {
+            Span::current().record("outcome", "error");
+            return Err(DomainError::TransferToSelf(from).into());
+        }
+
+        let result = self
+            .repo
+            .modify(&self.tenant.id, &[from, to], |accounts| {
+                let (src, dest) = accounts.split_at_mut(1);
+
+                debit(&self.tenant, &mut src[0], amount, self.clock.today())?;
+
+                dest[0].deposit(amount)?;
+
+                Ok(src[0].balance)
+            })
+            .map_err(not_found);
+
+        Span::current().record("outcome", outcome(&result));
+        result
+    }
+}
+
+/// Reports accounts the repository could not find as the domain error
+/// callers already expect from [`Bank::process`].
+pub(crate) fn not_found(err: AppError) -> AppError {
+    match err {
+        AppError::Repo(RepoError::NotFound(id)) => DomainError::AccountNotFound(id).into(),
+        err => err,
+    }
+}
+
+/// Whether a clearing event that failed with `err` may still apply once
+/// the account is active again, the funds are there, the bank is configured
+/// or storage recovers. Anything else, such as an unknown payment or a
+/// second outcome for a settled one, will fail the same way every time.
+fn may_apply_later(err: &AppError) -> bool {
+    match err {
+        AppError::Domain(err) => matches!(
+            err,
+            DomainError::AccountNotActive { .. }
+                | DomainError::InsufficientFunds { .. }
+                | DomainError::NotConfigured(_)
+        ),
+        AppError::Repo(_) | AppError::Audit(_) | AppError::Clearing(_) | AppError::Snapshot(_) => {
+            true
+        }
+    }
+}
+
+/// Takes `amount` out of `account` under the terms of the tenant's product
+/// it was opened on, or as a plain withdrawal if it has none.
+pub(crate) fn debit(
+    tenant: &Tenant,
+    account: &mut Account,
+    amount: Money,
+    today: NaiveDate,
+) -> Result<Money, DomainError> {
+    match account
+        .product
+        .as_deref()
+        .and_then(|code| tenant.product(code))
+    {
+        Some(product) => account.withdraw_under(amount, product, today),
+        None => account.withdraw(amount),
+    }
+}
+
+/// The accounts a transaction reads or writes, without duplicates.
+pub(crate) fn touched(account_id: AccountId, txn: &Transaction) -> Vec<AccountId> {
+    match txn {
+        Transaction::Transfer { to, .. } if *to != account_id => vec![account_id, *to],
+        _ => vec![account_id],
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        fmt::Debug,
+        sync::{Arc, Mutex, mpsc},
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+
+    use bank_core::{
+        account::{AccountRepository, AccountStatus, HolderRole, Money, Transaction},
+        account_number::{AccountNumbering, AccountRef},
+        audit::{AuditOutcome, AuditQuery},
+        cash::{CashBalance, CashCount, Denomination, cash_total},
+        clock::FixedClock,
+        context::{Actor, RequestContext},
+        customer::{Customer, CustomerStore},
+        errors::{AppError, ClearingError, DomainError, SnapshotError},
+        interbank::{
+            ClearingEvent, ClearingGateway, ClearingOutcome, ExternalPaymentStatus,
+            ExternalTransfer, IncomingPayment, OutgoingPayment,
+        },
+        loan::{AmortizationMethod, LoanTerms},
+        notification::{AlertCondition, RateLimit},
+        privacy::CustomerDataExport,
+        product::Product,
+        query::{AccountQuery, PageRequest},
+        tenant::{Tenant, TenantId},
+        term_deposit::{MaturityInstruction, TermDepositStatus, TermDepositTerms},
+    };
+    use bank_infra::{
+        clearing::FakeClearingHouse,
+        file::FileRepo,
+        instrumented::InstrumentedRepo,
+        iso20022::pain001::{Pain001Document, PaymentFileHeader},
+        metrics::Metrics,
+        notify::LocalMailServer,
+        storage::{InMemoryCustomerStore, InMemoryRepo},
+    };
+    use chrono::NaiveDate;
+    use rust_decimal::dec;
+    use tracing::{
+        Subscriber,
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+    };
+    use tracing_subscriber::{Registry, layer::Context, prelude::*};
+
+    use crate::bank::{Bank, ClearingResults};
+
+    type Fields = BTreeMap<String, String>;
+
+    /// Collects the name and final fields of every span closed while it is
+    /// the active subscriber.
+    #[derive(Default, Clone)]
+    struct SpanCapture {
+        open: Arc<Mutex<HashMap<u64, (String, Fields)>>>,
+        closed: Arc<Mutex<Vec<(String, Fields)>>>,
+    }
+
+    struct FieldVisitor<'a>(&'a mut Fields);
+
+    impl Visit for FieldVisitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0.insert(field.name().into(), format!("{value:?}"));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().into(), value.into());
+        }
+    }
+
+    impl<S: Subscriber> tracing_subscriber::Layer<S> for SpanCapture {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _: Context<'_, S>) {
+            let mut fields = Fields::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+            let name = attrs.metadata().name().to_string();
+            self.open
+                .lock()
+                .unwrap()
+                .insert(id.into_u64(), (name, fields));
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, _: Context<'_, S>) {
+            if let Some((_, fields)) = self.open.lock().unwrap().get_mut(&id.into_u64()) {
+                values.record(&mut FieldVisitor(fields));
+            }
+        }
+
+        fn on_close(&self, id: Id, _: Context<'_, S>) {
+            if let Some(span) = self.open.lock().unwrap().remove(&id.into_u64()) {
+                self.closed.lock().unwrap().push(span);
+            }
+        }
+    }
+
+    impl SpanCapture {
+        fn find(&self, name: &str) -> Vec<Fields> {
+            self.closed
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(span, _)| span == name)
+                .map(|(_, fields)| fields.clone())
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_bank_will_create_account_successfully() {
+        let customer: Customer = Customer::builder(1).build();
+        let repo: InMemoryRepo = InMemoryRepo::new();
+        let mut bank = Bank::new(Arc::new(repo));
+        let account_id = bank.create_account(customer.id).unwrap();
+
+        assert_eq!(account_id, 1);
+        let account = bank.load(account_id).unwrap();
+
+        assert_eq!(account.owner, customer.id);
+        assert_eq!(account.balance.0, 0.into());
+    }
+
+    #[test]
+    fn test_bank_will_process_deposit() {
+        let repo = Arc::new(InMemoryRepo::default());
+        let mut bank = Bank::new(repo);
+
+        let customer = Customer::builder(1).build();
+        let account_id = bank.create_account(customer.id).unwrap();
+
+        let result = bank.process(account_id, Transaction::Deposit(Money(100.into())));
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().0, 100.into());
+    }
+
+    fn customer(id: u64) -> RequestContext {
+        RequestContext::customer(id, "test")
+    }
+
+    fn joint_bank() -> (Bank<InMemoryRepo>, u64) {
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new()));
+        let account_id = bank.create_account(1).unwrap();
+        bank.process(account_id, Transaction::Deposit(Money(1000.into())))
+            .unwrap();
+        bank.add_holder(&customer(1), account_id, 2, HolderRole::Signatory)
+            .unwrap();
+        bank.add_holder(&customer(1), account_id, 3, HolderRole::Viewer)
+            .unwrap();
+        (bank, account_id)
+    }
+
+    #[test]
+    fn test_bank_will_let_signatory_withdraw_but_not_viewer() {
+        let (bank, account_id) = joint_bank();
+
+        let withdraw = Transaction::Withdraw(Money(100.into()));
+        let result = bank.process_as(&customer(2), account_id, withdraw);
+        assert_eq!(result, Ok(Money(900.into())));
+
+        let withdraw = Transaction::Withdraw(Money(100.into()));
+        let result = bank.process_as(&customer(3), account_id, withdraw);
+        assert_eq!(
+            result,
+            Err(AppError::Domain(DomainError::Unauthorized {
+                account_id,
+                customer: 3
+            }))
+        );
+
+        let withdraw = Transaction::Withdraw(Money(100.into()));
+        let result = bank.process_as(&customer(4), account_id, withdraw);
+        assert_eq!(
+            result,
+            Err(AppError::Domain(DomainError::Unauthorized {
+                account_id,
+                customer: 4
+            }))
+        );
+        assert_eq!(bank.load(account_id).unwrap().balance, Money(900.into()));
+    }
+
+    #[test]
+    fn test_bank_will_let_any_holder_view_account() {
+        let (bank, account_id) = joint_bank();
+
+        assert!(bank.account_as(&customer(3), account_id).is_ok());
+        assert_eq!(
+            bank.account_as(&customer(4), account_id),
+            Err(AppError::Domain(DomainError::Unauthorized {
+                account_id,
+                customer: 4
+            }))
+        );
+    }
+
+    #[test]
+    fn test_bank_will_only_let_owners_manage_holders() {
+        let (bank, account_id) = joint_bank();
+
+        let result = bank.add_holder(&customer(2), account_id, 4, HolderRole::Owner);
+        assert_eq!(
+            result,
+            Err(AppError::Domain(DomainError::Unauthorized {
+                account_id,
+                customer: 2
+            }))
+        );
+
+        bank.remove_holder(&customer(1), account_id, 3).unwrap();
+        assert!(bank.account_as(&customer(3), account_id).is_err());
+    }
+
+    #[test]
+    fn test_bank_will_hold_large_debits_for_second_approval() {
+        let (mut bank, account_id) = joint_bank();
+        let other_id = bank.create_account(5).unwrap();
+        bank.set_approval_threshold(&customer(1), account_id, Some(Money(500.into())))
+            .unwrap();
+
+        let txn = Transaction::Transfer {
+            to: other_id,
+            amount: Money(600.into()),
+        };
+        let result = bank.process_as(&customer(1), account_id, txn.clone());
+        assert_eq!(
+            result,
+            Err(AppError::Domain(DomainError::ApprovalRequired(1)))
+        );
+        assert_eq!(bank.load(account_id).unwrap().balance, Money(1000.into()));
+
+        let pending = bank.pending_approvals(account_id);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].txn, txn);
+
+        assert_eq!(
+            bank.approve(&customer(1), 1),
+            Err(AppError::Domain(DomainError::SelfApproval {
+                approval_id: 1
+            }))
+        );
+        assert_eq!(
+            bank.approve(&customer(3), 1),
+            Err(AppError::Domain(DomainError::Unauthorized {
+                account_id,
+                customer: 3
+            }))
+        );
+
+        assert!(bank.approve(&customer(2), 1).is_ok());
+        assert_eq!(bank.load(account_id).unwrap().balance, Money(400.into()));
+        assert_eq!(bank.load(other_id).unwrap().balance, Money(600.into()));
+        assert_eq!(
+            bank.approve(&customer(2), 1),
+            Err(AppError::Domain(DomainError::ApprovalNotFound(1)))
+        );
+    }
+
+    #[test]
+    fn test_bank_will_only_take_approvals_from_listed_tellers() {
+        let (bank, account_id) = joint_bank();
+        let bank = bank.with_tenant(Tenant::builder(TenantId::DEFAULT).approver(7).build());
+        bank.set_approval_threshold(&customer(1), account_id, Some(Money(500.into())))
+            .unwrap();
+        let txn = Transaction::Withdraw(Money(600.into()));
+        let _ = bank.process_as(&customer(1), account_id, txn);
+
+        let unrelated = RequestContext::teller(8, "approve");
+        assert_eq!(
+            bank.approve(&unrelated, 1),
+            Err(AppError::Domain(DomainError::ApproverRequired {
+                approval_id: 1
+            }))
+        );
+        assert_eq!(
+            bank.approve(&RequestContext::system(), 1),
+            Err(AppError::Domain(DomainError::ApproverRequired {
+                approval_id: 1
+            }))
+        );
+        assert_eq!(bank.pending_approvals(account_id).len(), 1);
+
+        let approver = RequestContext::teller(7, "approve");
+        assert_eq!(bank.approve(&approver, 1), Ok(Money(400.into())));
+    }
+
+    #[test]
+    fn test_bank_will_keep_approvals_whose_transaction_fails() {
+        let (bank, account_id) = joint_bank();
+        bank.set_approval_threshold(&customer(1), account_id, Some(Money(500.into())))
+            .unwrap();
+
+        let txn = Transaction::Withdraw(Money(600.into()));
+        let _ = bank.process_as(&customer(1), account_id, txn.clone());
+        bank.process_as(
+            &customer(1),
+            account_id,
+            Transaction::Withdraw(Money(500.into())),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            bank.approve(&customer(2), 1),
+            Err(AppError::Domain(DomainError::InsufficientFunds { .. }))
+        ));
+        assert_eq!(bank.pending_approvals(account_id)[0].txn, txn);
+
+        bank.process(account_id, Transaction::Deposit(Money(500.into())))
+            .unwrap();
+        assert_eq!(bank.approve(&customer(2), 1), Ok(Money(400.into())));
+        assert!(bank.pending_approvals(account_id).is_empty());
+    }
+
+    #[test]
+    fn test_bank_will_let_tellers_act_without_holder_roles() {
+        let (bank, account_id) = joint_bank();
+        let teller = RequestContext::teller(9, "counter");
+
+        let result = bank.process_as(&teller, account_id, Transaction::Withdraw(Money(50.into())));
+        assert_eq!(result, Ok(Money(950.into())));
+    }
+
+    #[test]
+    fn test_bank_will_audit_operations_with_actor_and_snapshots() {
+        let (bank, account_id) = joint_bank();
+        let ctx = RequestContext::customer(2, "req-42");
+
+        bank.process_as(&ctx, account_id, Transaction::Withdraw(Money(100.into())))
+            .unwrap();
+        let _ = bank.process_as(&ctx, account_id, Transaction::Withdraw(Money(5000.into())));
+
+        let records = bank
+            .audit
+            .query(&AuditQuery::default().actor(Actor::Customer(2)))
+            .unwrap();
+        assert_eq!(records.len(), 2);
+
+        let success = &records[0];
+        assert_eq!(success.request_id, "req-42");
+        assert_eq!(success.action, "process");
+        assert_eq!(
+            success.outcome,
+            AuditOutcome::Success(format!("{:?}", Money(900.into())))
+        );
+        assert_eq!(success.changes.len(), 1);
+        assert_eq!(
+            success.changes[0].before.as_ref().unwrap().balance,
+            Money(1000.into())
+        );
+        assert_eq!(
+            success.changes[0].after.as_ref().unwrap().balance,
+            Money(900.into())
+        );
+
+        let failure = &records[1];
+        assert!(matches!(failure.outcome, AuditOutcome::Failure(_)));
+        assert!(failure.changes.is_empty());
+
+        let for_account = bank
+            .audit
+            .query(&AuditQuery::default().account(account_id))
+            .unwrap();
+        assert_eq!(for_account.len(), 6);
+        assert!(bank.audit.verify().is_ok());
+    }
+
+    #[test]
+    fn test_bank_will_list_accounts_by_owner_and_status() {
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new()));
+        let first = bank.create_account(1).unwrap();
+        let second = bank.create_account(1).unwrap();
+        let other = bank.create_account(2).unwrap();
+        bank.add_holder(&RequestContext::system(), other, 1, HolderRole::Owner)
+            .unwrap();
+        bank.freeze(second).unwrap();
+
+        let owned = bank.accounts_of(1, PageRequest::first(2)).unwrap();
+        let ids: Vec<_> = owned.items.iter().map(|account| account.id).collect();
+        assert_eq!(ids, vec![first, second]);
+
+        let rest = bank
+            .accounts_of(1, PageRequest::next(owned.next.unwrap(), 2))
+            .unwrap();
+        let ids: Vec<_> = rest.items.iter().map(|account| account.id).collect();
+        assert_eq!(ids, vec![other]);
+
+        let frozen = AccountQuery::default().status(AccountStatus::Frozen);
+        let page = bank.list_accounts(&frozen, PageRequest::default()).unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].id, second);
+    }
+
+    #[test]
+    fn test_bank_will_not_reuse_account_ids_after_restart() {
+        let path = std::env::temp_dir().join(format!("bank-restart-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut bank = Bank::new(Arc::new(FileRepo::open(&path).unwrap()));
+        let first = bank.create_account(1).unwrap();
+        bank.process(first, Transaction::Deposit(Money(10.into())))
+            .unwrap();
+        drop(bank);
+
+        let mut bank = Bank::new(Arc::new(FileRepo::open(&path).unwrap()));
+        let second = bank.create_account(2).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(bank.load(first).unwrap().balance, Money(10.into()));
+        assert_eq!(bank.load(second).unwrap().owner, 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bank_will_issue_account_numbers_and_find_by_either_form() {
+        let numbering = AccountNumbering::new("GB", "PROR").unwrap();
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new())).with_account_numbering(numbering);
+        bank.create_account(1).unwrap();
+        let account_id = bank.create_account(2).unwrap();
+
+        let account = bank.find_account(&AccountRef::Id(account_id)).unwrap();
+        let number = account.number.clone().unwrap();
+
+        let printed: AccountRef = number.to_string().parse().unwrap();
+        assert_eq!(bank.find_account(&printed).unwrap(), account);
+
+        let unknown = AccountNumbering::new("GB", "PROR")
+            .unwrap()
+            .issue(99)
+            .unwrap();
+        assert!(matches!(
+            bank.find_account(&AccountRef::Number(unknown)),
+            Err(AppError::Domain(DomainError::AccountNumberNotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn test_bank_will_count_and_time_operations_and_repository_calls() {
+        let metrics = Arc::new(Metrics::new());
+        let repo = InstrumentedRepo::new(InMemoryRepo::new(), Arc::clone(&metrics));
+        let mut bank = Bank::new(Arc::new(repo)).with_metrics(Arc::clone(&metrics));
+        let account_id = bank.create_account(1).unwrap();
+
+        bank.process(account_id, Transaction::Deposit(Money(10.into())))
+            .unwrap();
+        bank.process(account_id, Transaction::Withdraw(Money(11.into())))
+            .unwrap_err();
+
+        assert_eq!(metrics.operation_count("create_account", "ok"), 1);
+        assert_eq!(metrics.operation_count("process", "ok"), 1);
+        assert_eq!(metrics.operation_count("process", "error"), 1);
+        assert_eq!(metrics.repo_call_count("modify", "ok"), 1);
+        assert_eq!(metrics.repo_call_count("modify", "error"), 1);
+
+        let text = metrics.render();
+        assert!(text.contains(r#"bank_operations_total{operation="process",outcome="error"} 1"#));
+        assert!(text.contains(r#"bank_operation_duration_seconds_count{operation="process"} 2"#));
+        assert!(text.contains(r#"bank_repository_call_duration_seconds_count{call="modify"} 2"#));
+    }
+
+    #[test]
+    fn test_bank_will_trace_failed_transfer_with_accounts_amount_and_outcome() {
+        let capture = SpanCapture::default();
+        let subscriber = Registry::default().with(capture.clone());
+        let metrics = Arc::new(Metrics::new());
+        let repo = InstrumentedRepo::new(InMemoryRepo::new(), metrics);
+        let mut bank = Bank::new(Arc::new(repo));
+        let from = bank.create_account(1).unwrap();
+        let to = bank.create_account(2).unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let txn = Transaction::Transfer {
+                to,
+                amount: Money(25.into()),
+            };
+            bank.process(from, txn).unwrap_err();
+        });
+
+        let process = capture.find("bank.process");
+        assert_eq!(process.len(), 1);
+        assert_eq!(process[0]["account_id"], from.to_string());
+        assert_eq!(process[0]["kind"], "transfer");
+        assert_eq!(process[0]["amount"], "25");
+        assert_eq!(process[0]["outcome"], "error");
+
+        let transfer = capture.find("bank.transfer");
+        assert_eq!(transfer.len(), 1);
+        assert_eq!(transfer[0]["from"], from.to_string());
+        assert_eq!(transfer[0]["to"], to.to_string());
+        assert_eq!(transfer[0]["outcome"], "error");
+
+        let modify = capture.find("repo.modify");
+        assert_eq!(modify.len(), 1);
+        assert_eq!(modify[0]["account_ids"], format!("[{from}, {to}]"));
+        assert_eq!(modify[0]["outcome"], "error");
+    }
+
+    #[test]
+    fn test_bank_will_disburse_loan_and_take_repayments_from_linked_account() {
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new()));
+        let account_id = bank.create_account(1).unwrap();
+        let customer = RequestContext::customer(1, "req-1");
+        let teller = RequestContext::teller(9, "req-2");
+        let start = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        let terms = LoanTerms {
+            principal: Money(dec!(1200)),
+            annual_rate: dec!(0.12),
+            term_months: 12,
+            method: AmortizationMethod::EqualPrincipal,
+            start,
+            late_fee: Money(dec!(25)),
+        };
+
+        let loan_id = bank.originate_loan(&customer, account_id, terms).unwrap();
+        assert_eq!(bank.disburse_loan(&teller, loan_id), Ok(Money(dec!(1200))));
+        assert!(bank.disburse_loan(&teller, loan_id).is_err());
+
+        let first_due = NaiveDate::from_ymd_opt(2026, 2, 15).unwrap();
+        let split = bank
+            .repay_loan(&customer, loan_id, Money(dec!(112)), first_due)
+            .unwrap();
+        assert_eq!(
+            (split.interest, split.principal),
+            (Money(dec!(12)), Money(dec!(100)))
+        );
+        assert_eq!(bank.load(account_id).unwrap().balance, Money(dec!(1088)));
+
+        bank.process(account_id, Transaction::Withdraw(Money(dec!(1088))))
+            .unwrap();
+        let result = bank.repay_loan(&customer, loan_id, Money(dec!(111)), first_due);
+        assert_eq!(
+            result,
+            Err(AppError::Domain(DomainError::InsufficientFunds {
+                account_id,
+                requested: Money(dec!(111)),
+                available: Money(dec!(0)),
+            }))
+        );
+        assert_eq!(
+            bank.loan(loan_id).unwrap().outstanding_principal(),
+            Money(dec!(1100))
+        );
+
+        let stranger = RequestContext::customer(2, "req-3");
+        assert_eq!(
+            bank.repay_loan(&stranger, loan_id, Money(dec!(1)), first_due),
+            Err(AppError::Domain(DomainError::Unauthorized {
+                account_id,
+                customer: 2
+            }))
+        );
+        assert!(matches!(
+            bank.loan(99),
+            Err(AppError::Domain(DomainError::LoanNotFound(99)))
+        ));
+    }
+
+    #[test]
+    fn test_bank_will_settle_term_deposits_at_maturity_by_instruction() {
+        let clock = Arc::new(FixedClock::new(
+            NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+        ));
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new())).with_clock(clock.clone());
+        let account_id = bank.create_account(1).unwrap();
+        bank.process(account_id, Transaction::Deposit(Money(dec!(30000))))
+            .unwrap();
+        let customer = RequestContext::customer(1, "req-1");
+        let terms = |instruction| TermDepositTerms {
+            principal: Money(dec!(10000)),
+            annual_rate: dec!(0.0365),
+            term_months: 12,
+            penalty_days: 90,
+            instruction,
+        };
+
+        let payout = bank
+            .open_term_deposit(&customer, account_id, terms(MaturityInstruction::Payout))
+            .unwrap();
+        let rollover = bank
+            .open_term_deposit(
+                &customer,
+                account_id,
+                terms(MaturityInstruction::RolloverAll),
+            )
+            .unwrap();
+        let broken = bank
+            .open_term_deposit(&customer, account_id, terms(MaturityInstruction::Payout))
+            .unwrap();
+        assert_eq!(bank.load(account_id).unwrap().balance, Money(dec!(0)));
+        assert_eq!(
+            bank.open_term_deposit(&customer, account_id, terms(MaturityInstruction::Payout)),
+            Err(AppError::Domain(DomainError::InsufficientFunds {
+                account_id,
+                requested: Money(dec!(10000)),
+                available: Money(dec!(0)),
+            }))
+        );
+
+        clock.advance_days(200);
+        let early = bank.withdraw_term_deposit(&customer, broken).unwrap();
+        assert_eq!(early.total(), Money(dec!(10110.00)));
+        assert!(bank.process_maturities().is_empty());
+
+        clock.advance_days(170);
+        let outcomes = bank.process_maturities();
+
+        assert_eq!(outcomes.len(), 2);
+        let (id, paid) = &outcomes[0];
+        assert_eq!(*id, payout);
+        assert_eq!(paid.as_ref().unwrap().paid_out, Money(dec!(10365.00)));
+        let rolled = outcomes[1].1.as_ref().unwrap();
+        let new_id = rolled.rolled_into.unwrap();
+        let new_deposit = bank.term_deposit(new_id).unwrap();
+        assert_eq!(new_deposit.terms.principal, Money(dec!(10365.00)));
+        assert_eq!(
+            new_deposit.start,
+            NaiveDate::from_ymd_opt(2027, 1, 1).unwrap()
+        );
+        assert_eq!(
+            bank.term_deposit(rollover).unwrap().status,
+            TermDepositStatus::RolledOver(new_id)
+        );
+        assert_eq!(
+            bank.load(account_id).unwrap().balance,
+            Money(dec!(20475.00))
+        );
+        assert!(bank.process_maturities().is_empty());
+    }
+
+    /// A bank with a suspense account and a numbered customer account
+    /// holding 500.
+    fn interbank_bank(clock: Arc<FixedClock>) -> (Bank<InMemoryRepo>, u64, u64) {
+        let numbering = AccountNumbering::new("GB", "PROR").unwrap();
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new()))
+            .with_account_numbering(numbering)
+            .with_clock(clock);
+        let suspense = bank.create_account(0).unwrap();
+        let account_id = bank.create_account(1).unwrap();
+        bank.process(account_id, Transaction::Deposit(Money(dec!(500))))
+            .unwrap();
+        (bank.with_suspense_account(suspense), account_id, suspense)
+    }
+
+    fn external(creditor_account: &str, amount: Money) -> ExternalTransfer {
+        ExternalTransfer {
+            creditor_name: "Charles Babbage".into(),
+            creditor_account: creditor_account.parse().unwrap(),
+            creditor_agent: Some("COBADEFFXXX".into()),
+            amount,
+            remittance: Some("Invoice 42".into()),
+        }
+    }
+
+    #[test]
+    fn test_bank_will_export_external_transfers_and_import_statement_credits_once() {
+        let clock = Arc::new(FixedClock::new(
+            NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+        ));
+        let (bank, account_id, suspense) = interbank_bank(clock);
+        let customer = RequestContext::customer(1, "req-1");
+        let transfer = external("DE89370400440532013000", Money(dec!(120.50)));
+
+        let payment = bank
+            .transfer_external(&customer, account_id, "Ada Lovelace", transfer.clone())
+            .unwrap();
+        assert_eq!(payment.end_to_end_id, "E2E000000000001");
+        assert_eq!(bank.load(account_id).unwrap().balance, Money(dec!(379.50)));
+        assert_eq!(bank.load(suspense).unwrap().balance, Money(dec!(120.50)));
+        let too_much = ExternalTransfer {
+            amount: Money(dec!(1000)),
+            ..transfer
+        };
+        assert_eq!(
+            bank.transfer_external(&customer, account_id, "Ada Lovelace", too_much),
+            Err(AppError::Domain(DomainError::InsufficientFunds {
+                account_id,
+                requested: Money(dec!(1000)),
+                available: Money(dec!(379.50)),
+            }))
+        );
+
+        let header = PaymentFileHeader {
+            message_id: "PRORUST-20260302-1".into(),
+            created_at: NaiveDate::from_ymd_opt(2026, 3, 2)
+                .unwrap()
+                .and_hms_opt(9, 30, 0)
+                .unwrap(),
+            initiating_party: "Pro Rust Bank".into(),
+            debtor_agent: "PRORGB2L".into(),
+            currency: "EUR".into(),
+        };
+        let xml = bank.export_payment_file(&header).unwrap();
+        let exported = Pain001Document::from_xml(&xml).unwrap();
+        let info = &exported.initiation.payment_information[0];
+        assert_eq!(
+            info.transactions[0].payment_id.end_to_end_id,
+            payment.end_to_end_id
+        );
+        assert_eq!(info.requested_execution.date, "2026-03-02");
+        assert!(bank.outgoing_payments().is_empty());
+        assert_eq!(
+            bank.external_payment(&payment.end_to_end_id)
+                .unwrap()
+                .status,
+            ExternalPaymentStatus::Submitted
+        );
+        let settled = ClearingEvent {
+            end_to_end_id: payment.end_to_end_id.clone(),
+            outcome: ClearingOutcome::Settled,
+        };
+        assert_eq!(
+            bank.record_clearing_event(&RequestContext::teller(7, "req-2"), &settled),
+            Ok(ExternalPaymentStatus::Settled)
+        );
+        assert_eq!(bank.load(suspense).unwrap().balance, Money(dec!(0)));
+
+        let number = bank
+            .find_account(&AccountRef::Id(account_id))
+            .unwrap()
+            .number
+            .unwrap();
+        let incoming = |reference: &str, currency: &str| IncomingPayment {
+            reference: reference.into(),
+            creditor_account: number.clone(),
+            debtor_name: Some("Grace Hopper".into()),
+            amount: Money(dec!(20.50)),
+            currency: currency.into(),
+            booking_date: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+            remittance: None,
+        };
+        let statement = vec![incoming("CORR-1", "EUR"), incoming("CORR-2", "USD")];
+
+        let results = bank.import_statement(&statement, "EUR");
+        assert_eq!(results[0], ("CORR-1".to_string(), Ok(Money(dec!(400.00)))));
+        assert_eq!(
+            results[1].1,
+            Err(AppError::Domain(DomainError::CurrencyMismatch {
+                expected: "EUR".into(),
+                found: "USD".into(),
+            }))
+        );
+
+        let again = bank.import_statement(&statement, "EUR");
+        assert_eq!(again.len(), 1);
+        assert_eq!(again[0].0, "CORR-2");
+        assert_eq!(bank.load(account_id).unwrap().balance, Money(dec!(400.00)));
+    }
+
+    #[test]
+    fn test_bank_will_credit_statement_entries_once_across_concurrent_imports() {
+        let clock = Arc::new(FixedClock::new(
+            NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+        ));
+        let (bank, account_id, _) = interbank_bank(clock);
+        let number = bank.load(account_id).unwrap().number.unwrap();
+        let statement: Vec<IncomingPayment> = (0..50)
+            .map(|entry| IncomingPayment {
+                reference: format!("CORR-{entry}"),
+                creditor_account: number.clone(),
+                debtor_name: None,
+                amount: Money(dec!(1)),
+                currency: "EUR".into(),
+                booking_date: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+                remittance: None,
+            })
+            .collect();
+
+        let credited: usize = std::thread::scope(|scope| {
+            let imports: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| bank.import_statement(&statement, "EUR").len()))
+                .collect();
+            imports
+                .into_iter()
+                .map(|import| import.join().unwrap())
+                .sum()
+        });
+
+        assert_eq!(credited, 50);
+        assert_eq!(bank.load(account_id).unwrap().balance, Money(dec!(550)));
+        let records = bank
+            .audit
+            .query(&AuditQuery::default().account(account_id))
+            .unwrap();
+        let credit = records
+            .iter()
+            .find(|record| record.inputs.starts_with("reference=CORR-7 "))
+            .unwrap();
+        assert_eq!(credit.action, "import_payment");
+        assert_eq!(credit.changes.len(), 1);
+    }
+
+    #[test]
+    fn test_bank_will_hold_external_transfers_in_suspense_until_cleared() {
+        let clock = Arc::new(FixedClock::new(
+            NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+        ));
+        let house = Arc::new(FakeClearingHouse::new(clock.clone()).settlement_days(1));
+        house.reject_account("FR7630006000011234567890189", "AC01");
+        house.return_account("GB33BUKB20201555555555", 2, "AC04");
+        let (bank, account_id, suspense) = interbank_bank(clock.clone());
+        let bank = bank.with_clearing_gateway(house.clone());
+        let customer = RequestContext::customer(1, "req-1");
+        let balance = |id| bank.load(id).unwrap().balance;
+
+        let send = |creditor, amount| {
+            let transfer = external(creditor, amount);
+            bank.transfer_external(&customer, account_id, "Ada Lovelace", transfer)
+                .unwrap()
+        };
+
+        let settled = send("DE89370400440532013000", Money(dec!(100)));
+        let rejected = send("FR7630006000011234567890189", Money(dec!(50)));
+        let returned = send("GB33BUKB20201555555555", Money(dec!(25)));
+        assert_eq!(balance(account_id), Money(dec!(325)));
+        assert_eq!(balance(suspense), Money(dec!(175)));
+
+        house.set_available(false);
+        let results = bank.submit_external_payments().unwrap().results;
+        assert!(results.iter().all(|(_, result)| matches!(
+            result,
+            Err(AppError::Clearing(ClearingError::Unavailable(_)))
+        )));
+        assert_eq!(bank.outgoing_payments().len(), 3);
+
+        house.set_available(true);
+        let results = bank.submit_external_payments().unwrap().results;
+        assert_eq!(results[0].1, Ok(ExternalPaymentStatus::Submitted));
+        assert_eq!(
+            results[1].1,
+            Ok(ExternalPaymentStatus::Rejected("AC01".into()))
+        );
+        assert_eq!(balance(account_id), Money(dec!(375)));
+        assert_eq!(balance(suspense), Money(dec!(125)));
+        assert_eq!(
+            bank.process_clearing_events().unwrap(),
+            ClearingResults::default()
+        );
+
+        clock.advance_days(1);
+        let results = bank.process_clearing_events().unwrap().results;
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            bank.external_payment(&settled.end_to_end_id)
+                .unwrap()
+                .status,
+            ExternalPaymentStatus::Settled
+        );
+        assert_eq!(balance(suspense), Money(dec!(0)));
+
+        clock.advance_days(2);
+        let results = bank.process_clearing_events().unwrap().results;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, returned.end_to_end_id);
+        assert_eq!(
+            results[0].1,
+            Ok(ExternalPaymentStatus::Returned("AC04".into()))
+        );
+        assert_eq!(balance(account_id), Money(dec!(400)));
+        assert_eq!(
+            bank.external_payment(&rejected.end_to_end_id)
+                .unwrap()
+                .status,
+            ExternalPaymentStatus::Rejected("AC01".into())
+        );
+        assert!(bank.audit.verify().is_ok());
+    }
+
+    /// Holds every submission until the test lets it through.
+    struct SlowGateway {
+        entered: Mutex<mpsc::Sender<()>>,
+        release: Mutex<mpsc::Receiver<()>>,
+    }
+
+    impl ClearingGateway for SlowGateway {
+        fn submit(&self, _payment: &OutgoingPayment) -> Result<(), ClearingError> {
+            self.entered.lock().unwrap().send(()).unwrap();
+            self.release
+                .lock()
+                .unwrap()
+                .recv_timeout(Duration::from_secs(5))
+                .map_err(|err| ClearingError::Unavailable(err.to_string()))
+        }
+
+        fn poll(&self) -> Result<Vec<ClearingEvent>, ClearingError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn test_bank_will_take_new_external_transfers_while_submitting() {
+        let clock = Arc::new(FixedClock::new(
+            NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+        ));
+        let (entered, entered_rx) = mpsc::channel();
+        let (release_tx, release) = mpsc::channel();
+        let gateway = SlowGateway {
+            entered: Mutex::new(entered),
+            release: Mutex::new(release),
+        };
+        let (bank, account_id, _) = interbank_bank(clock);
+        let bank = bank.with_clearing_gateway(Arc::new(gateway));
+        let customer = RequestContext::customer(1, "req-1");
+        let send = || {
+            let transfer = external("DE89370400440532013000", Money(dec!(10)));
+            bank.transfer_external(&customer, account_id, "Ada Lovelace", transfer)
+                .unwrap()
+        };
+
+        let first = send();
+        std::thread::scope(|scope| {
+            let submission = scope.spawn(|| bank.submit_external_payments().unwrap());
+            entered_rx.recv().unwrap();
+
+            let second = send();
+            assert_eq!(
+                bank.external_payment(&second.end_to_end_id).unwrap().status,
+                ExternalPaymentStatus::Pending
+            );
+            release_tx.send(()).unwrap();
+
+            let results = submission.join().unwrap().results;
+            assert_eq!(
+                results,
+                vec![(first.end_to_end_id, Ok(ExternalPaymentStatus::Submitted))]
+            );
+        });
+        assert_eq!(bank.outgoing_payments().len(), 1);
+    }
+
+    /// Accepts every payment and reports the events queued on it.
+    #[derive(Default)]
+    struct ScriptedGateway {
+        events: Mutex<Vec<ClearingEvent>>,
+    }
+
+    impl ClearingGateway for ScriptedGateway {
+        fn submit(&self, _payment: &OutgoingPayment) -> Result<(), ClearingError> {
+            Ok(())
+        }
+
+        fn poll(&self) -> Result<Vec<ClearingEvent>, ClearingError> {
+            Ok(std::mem::take(&mut *self.events.lock().unwrap()))
+        }
+    }
+
+    #[test]
+    fn test_bank_will_dead_letter_clearing_events_that_can_never_apply() {
+        let clock = Arc::new(FixedClock::new(
+            NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+        ));
+        let gateway = Arc::new(ScriptedGateway::default());
+        let (bank, account_id, suspense) = interbank_bank(clock);
+        let bank = bank.with_clearing_gateway(gateway.clone());
+        let customer = RequestContext::customer(1, "req-1");
+
+        let transfer = external("DE89370400440532013000", Money(dec!(100)));
+        let payment = bank
+            .transfer_external(&customer, account_id, "Ada Lovelace", transfer)
+            .unwrap();
+        bank.submit_external_payments().unwrap();
+        let settled = |end_to_end_id: &str| ClearingEvent {
+            end_to_end_id: end_to_end_id.into(),
+            outcome: ClearingOutcome::Settled,
+        };
+        *gateway.events.lock().unwrap() = vec![
+            settled(&payment.end_to_end_id),
+            settled(&payment.end_to_end_id),
+            settled("E2E999999999999"),
+        ];
+
+        let run = bank.process_clearing_events().unwrap();
+        assert_eq!(run.results[0].1, Ok(ExternalPaymentStatus::Settled));
+        let codes: Vec<&str> = run
+            .dead_lettered
+            .iter()
+            .map(|letter| letter.code.as_str())
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                "external_payment_status_conflict",
+                "external_payment_not_found"
+            ]
+        );
+        assert_eq!(run.dead_lettered[1].event, settled("E2E999999999999"));
+        assert!(bank.clearing_backlog.lock().unwrap().is_empty());
+        assert_eq!(
+            *bank.clearing_dead_letters.lock().unwrap(),
+            run.dead_lettered
+        );
+        assert_eq!(bank.load(suspense).unwrap().balance, Money(dec!(0)));
+
+        assert_eq!(
+            bank.process_clearing_events().unwrap(),
+            ClearingResults::default()
+        );
+        let snapshot = bank.take_snapshot().unwrap();
+        assert_eq!(snapshot.clearing_dead_letters, run.dead_lettered);
+    }
+
+    #[test]
+    fn test_bank_will_retry_clearing_events_that_failed_to_apply() {
+        let clock = Arc::new(FixedClock::new(
+            NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+        ));
+        let house = Arc::new(FakeClearingHouse::new(clock.clone()));
+        house.return_account("GB33BUKB20201555555555", 1, "AC04");
+        let (bank, account_id, suspense) = interbank_bank(clock.clone());
+        let bank = bank.with_clearing_gateway(house);
+        let customer = RequestContext::customer(1, "req-1");
+
+        let transfer = external("GB33BUKB20201555555555", Money(dec!(500)));
+        let payment = bank
+            .transfer_external(&customer, account_id, "Ada Lovelace", transfer)
+            .unwrap();
+        bank.submit_external_payments().unwrap();
+        bank.process_clearing_events().unwrap();
+        bank.close(account_id).unwrap();
+
+        clock.advance_days(1);
+        let results = bank.process_clearing_events().unwrap().results;
+        assert!(results[0].1.is_err());
+        assert_eq!(bank.clearing_backlog.lock().unwrap().len(), 1);
+        assert_eq!(
+            bank.external_payment(&payment.end_to_end_id)
+                .unwrap()
+                .status,
+            ExternalPaymentStatus::Settled
+        );
+
+        bank.repo
+            .modify(&bank.tenant.id, &[account_id], |accounts| {
+                accounts[0].status = AccountStatus::Active;
+                Ok::<_, AppError>(())
+            })
+            .unwrap();
+        let results = bank.process_clearing_events().unwrap().results;
+        assert_eq!(
+            results,
+            vec![(
+                payment.end_to_end_id.clone(),
+                Ok(ExternalPaymentStatus::Returned("AC04".into()))
+            )]
+        );
+        assert!(bank.clearing_backlog.lock().unwrap().is_empty());
+        assert_eq!(bank.load(account_id).unwrap().balance, Money(dec!(500)));
+        assert_eq!(bank.load(suspense).unwrap().balance, Money(dec!(0)));
+    }
+
+    #[test]
+    fn test_bank_will_export_customer_data_and_erase_it_once_balances_are_zero() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let mut bank =
+            Bank::new(Arc::new(InMemoryRepo::new())).with_clock(Arc::new(FixedClock::new(today)));
+        let teller = RequestContext::teller(7, "req-0");
+        let ada = Customer::builder(1)
+            .name("Ada Lovelace")
+            .email("ada@example.com")
+            .build();
+        bank.register_customer(&teller, ada.clone()).unwrap();
+        bank.register_customer(&teller, Customer::builder(2).name("Grace Hopper").build())
+            .unwrap();
+        let account_id = bank.create_account(1).unwrap();
+        let other = bank.create_account(2).unwrap();
+        bank.process(account_id, Transaction::Deposit(Money(dec!(100))))
+            .unwrap();
+        bank.process(other, Transaction::Deposit(Money(dec!(5))))
+            .unwrap();
+
+        let customer = RequestContext::customer(1, "req-1");
+        assert_eq!(
+            bank.export_customer_data(&RequestContext::customer(2, "req-2"), 1),
+            Err(AppError::Domain(DomainError::CustomerAccessDenied {
+                customer: 1,
+                caller: 2
+            }))
+        );
+        assert_eq!(
+            bank.export_customer_data(&teller, 9),
+            Err(AppError::Domain(DomainError::CustomerNotFound(9)))
+        );
+
+        let export = bank.export_customer_data(&customer, 1).unwrap();
+        assert_eq!(export.customer, ada);
+        assert_eq!(export.exported_on, today);
+        assert_eq!(export.accounts.len(), 1);
+        assert_eq!(export.accounts[0].id, account_id);
+        assert_eq!(export.ledger.len(), 1);
+        assert_eq!(export.ledger[0].amount, Money(dec!(100)));
+        assert!(
+            export
+                .audit
+                .iter()
+                .all(|record| record.accounts.contains(&account_id)
+                    || record.actor == Actor::Customer(1))
+        );
+        assert_eq!(export.audit.last().unwrap().action, "export_customer_data");
+        let json = export.to_json().unwrap();
+        assert!(json.contains("ada@example.com"));
+        assert_eq!(CustomerDataExport::from_json(&json).unwrap(), export);
+
+        assert_eq!(
+            bank.erase_customer(&customer, 1),
+            Err(AppError::Domain(DomainError::BalanceNotZero {
+                account_id,
+                balance: Money(dec!(100)),
+            }))
+        );
+        assert_eq!(bank.customer(1).unwrap(), ada);
+
+        bank.process(account_id, Transaction::Withdraw(Money(dec!(100))))
+            .unwrap();
+        let erased = bank.erase_customer(&customer, 1).unwrap();
+        assert_eq!(erased.erased_on, Some(today));
+        assert!(erased.name.starts_with("erased-"));
+        assert_eq!(bank.customer(1).unwrap(), erased);
+
+        let json = bank
+            .export_customer_data(&teller, 1)
+            .unwrap()
+            .to_json()
+            .unwrap();
+        assert!(!json.contains("Ada Lovelace"));
+        assert!(!json.contains("ada@example.com"));
+        let retained = CustomerDataExport::from_json(&json).unwrap();
+        assert_eq!(retained.ledger.len(), 2);
+        assert_eq!(bank.customer(2).unwrap().name, "Grace Hopper");
+        assert!(bank.audit.verify().is_ok());
+    }
+
+    #[test]
+    fn test_bank_will_keep_joint_holders_out_of_export_and_erasure() {
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new()));
+        let teller = RequestContext::teller(7, "req-0");
+        bank.register_customer(&teller, Customer::builder(1).name("Ada").build())
+            .unwrap();
+        bank.register_customer(&teller, Customer::builder(2).name("Grace").build())
+            .unwrap();
+        let joint = bank.create_account(1).unwrap();
+        let ada = RequestContext::customer(1, "req-1");
+        let grace = RequestContext::customer(2, "req-2");
+        bank.add_holder(&ada, joint, 2, HolderRole::Owner).unwrap();
+        bank.process_as(&grace, joint, Transaction::Deposit(Money(dec!(40))))
+            .unwrap();
+        bank.process_as(&teller, joint, Transaction::Deposit(Money(dec!(10))))
+            .unwrap();
+
+        let export = bank.export_customer_data(&ada, 1).unwrap();
+        assert!(
+            export
+                .audit
+                .iter()
+                .all(|record| record.actor != Actor::Customer(2))
+        );
+        assert!(
+            export
+                .audit
+                .iter()
+                .any(|record| record.actor == Actor::Teller(7) && record.action == "process")
+        );
+
+        let viewed = bank.create_account(2).unwrap();
+        bank.add_holder(&grace, viewed, 1, HolderRole::Viewer)
+            .unwrap();
+        bank.process_as(&grace, viewed, Transaction::Deposit(Money(dec!(5))))
+            .unwrap();
+        bank.process_as(&grace, joint, Transaction::Withdraw(Money(dec!(50))))
+            .unwrap();
+        assert_eq!(
+            bank.erase_customer(&ada, 1),
+            Err(AppError::Domain(DomainError::BalanceNotZero {
+                account_id: viewed,
+                balance: Money(dec!(5)),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_bank_will_reconstruct_account_as_of_any_time_from_audit_history() {
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new()));
+        let account_id = bank.create_account(1).unwrap();
+        let last_change =
+            |bank: &Bank<InMemoryRepo>| bank.audit.records().unwrap().last().unwrap().at;
+
+        bank.process(account_id, Transaction::Deposit(Money(dec!(100))))
+            .unwrap();
+        let after_deposit = last_change(&bank);
+        bank.process(account_id, Transaction::Withdraw(Money(dec!(500))))
+            .unwrap_err();
+        bank.process(account_id, Transaction::Withdraw(Money(dec!(30))))
+            .unwrap();
+        let after_withdrawal = last_change(&bank);
+        bank.freeze(account_id).unwrap();
+
+        let at = |when| bank.account_at(account_id, when).unwrap();
+        assert_eq!(at(after_deposit).balance, Money(dec!(100)));
+        assert_eq!(at(after_withdrawal).balance, Money(dec!(70)));
+        assert_eq!(at(after_withdrawal).status, AccountStatus::Active);
+        assert_eq!(at(SystemTime::now()).status, AccountStatus::Frozen);
+        assert_eq!(
+            bank.account_at(account_id, UNIX_EPOCH),
+            Err(AppError::Domain(DomainError::AccountNotFound(account_id)))
+        );
+    }
+
+    #[test]
+    fn test_bank_will_rebuild_balances_from_audit_log_after_concurrent_transfers() {
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new()));
+        let accounts: Vec<u64> = (1..=4)
+            .map(|owner| {
+                let account_id = bank.create_account(owner).unwrap();
+                bank.process(account_id, Transaction::Deposit(Money(dec!(1000))))
+                    .unwrap();
+                account_id
+            })
+            .collect();
+
+        std::thread::scope(|scope| {
+            for (index, from) in accounts.iter().enumerate() {
+                let to = accounts[(index + 1) % accounts.len()];
+                let bank = &bank;
+                scope.spawn(move || {
+                    for amount in 1..=50 {
+                        let txn = Transaction::Transfer {
+                            to,
+                            amount: Money(amount.into()),
+                        };
+                        let _ = bank.process(*from, txn);
+                    }
+                });
+            }
+        });
+
+        let mut balances = HashMap::new();
+        for entry in bank.audit.ledger_after(0).unwrap() {
+            let balance = balances.entry(entry.account_id).or_insert(Money::default());
+            assert_eq!(balance.0 + entry.amount.0, entry.balance.0, "{entry:?}");
+            *balance = entry.balance;
+        }
+        let now = SystemTime::now();
+        for account_id in accounts {
+            let current = bank.load(account_id).unwrap();
+            assert_eq!(balances[&account_id], current.balance);
+            assert_eq!(bank.account_at(account_id, now), Ok(current));
+        }
+    }
+
+    #[test]
+    fn test_bank_will_restore_snapshot_file_into_empty_bank() {
+        let clock = Arc::new(FixedClock::new(
+            NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+        ));
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new())).with_clock(clock.clone());
+        let ctx = RequestContext::customer(1, "req-1");
+        bank.register_customer(&ctx, Customer::builder(1).name("Ada Lovelace").build())
+            .unwrap();
+        let account_id = bank.create_account(1).unwrap();
+        bank.process(account_id, Transaction::Deposit(Money(dec!(20000))))
+            .unwrap();
+        let terms = TermDepositTerms {
+            principal: Money(dec!(10000)),
+            annual_rate: dec!(0.0365),
+            term_months: 12,
+            penalty_days: 90,
+            instruction: MaturityInstruction::Payout,
+        };
+        let deposit_id = bank.open_term_deposit(&ctx, account_id, terms).unwrap();
+
+        let path = std::env::temp_dir().join(format!("bank-seed-{}.json", std::process::id()));
+        bank.save_snapshot(&path).unwrap();
+
+        let mut restored = Bank::new(Arc::new(InMemoryRepo::new())).with_clock(clock);
+        restored.load_snapshot(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let original = bank.take_snapshot().unwrap();
+        let copy = restored.take_snapshot().unwrap();
+        assert_eq!(copy.accounts, original.accounts);
+        assert_eq!(copy.customers, original.customers);
+        assert_eq!(copy.term_deposits, original.term_deposits);
+        assert_eq!(copy.audit, original.audit);
+        assert_eq!(restored.term_deposit(deposit_id).unwrap().id, deposit_id);
+        assert!(restored.audit.verify().is_ok());
+
+        let now = SystemTime::now();
+        assert_eq!(
+            restored.account_at(account_id, now),
+            bank.account_at(account_id, now)
+        );
+        assert_eq!(restored.create_account(2).unwrap(), account_id + 1);
+        assert_eq!(
+            restored.restore_snapshot(original),
+            Err(AppError::Snapshot(SnapshotError::TargetNotEmpty))
+        );
+    }
+
+    fn notes(pieces: &[(i64, u32)]) -> CashCount {
+        pieces
+            .iter()
+            .map(|(value, pieces)| (Denomination((*value).into()), *pieces))
+            .collect()
+    }
+
+    #[test]
+    fn test_bank_will_run_cash_through_teller_drawers_and_balance_each_shift() {
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new()));
+        let account_id = bank.create_account(1).unwrap();
+        let alice = RequestContext::teller(7, "req-1");
+        let bob = RequestContext::teller(8, "req-2");
+
+        let drawer_id = bank
+            .open_drawer(&alice, notes(&[(50, 10), (20, 20)]))
+            .unwrap();
+        assert_eq!(
+            bank.open_drawer(&alice, notes(&[(20, 1)])),
+            Err(AppError::Domain(DomainError::DrawerAlreadyHeld {
+                teller: 7,
+                drawer_id
+            }))
+        );
+        assert_eq!(
+            bank.open_drawer(&customer(1), notes(&[(20, 1)])),
+            Err(AppError::Domain(DomainError::TellerRequired))
+        );
+
+        let deposit = Transaction::Deposit(Money(dec!(300)));
+        let balance = bank
+            .process_cash(&alice, drawer_id, account_id, deposit, notes(&[(100, 3)]))
+            .unwrap();
+        assert_eq!(balance, Money(dec!(300)));
+        let withdraw = Transaction::Withdraw(Money(dec!(70)));
+        assert_eq!(
+            bank.process_cash(
+                &alice,
+                drawer_id,
+                account_id,
+                withdraw.clone(),
+                notes(&[(50, 1)])
+            ),
+            Err(AppError::Domain(DomainError::CashMismatch {
+                account_id,
+                amount: Money(dec!(70)),
+                counted: Money(dec!(50)),
+            }))
+        );
+        let too_much = Transaction::Withdraw(Money(dec!(500)));
+        assert!(
+            bank.process_cash(&alice, drawer_id, account_id, too_much, notes(&[(50, 10)]))
+                .is_err()
+        );
+        assert_eq!(
+            cash_total(&bank.cash_drawer(drawer_id).unwrap().expected),
+            Money(dec!(1200))
+        );
+        let exact = notes(&[(50, 1), (20, 1)]);
+        bank.process_cash(
+            &alice,
+            drawer_id,
+            account_id,
+            withdraw.clone(),
+            exact.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            bank.process_cash(&bob, drawer_id, account_id, withdraw, exact),
+            Err(AppError::Domain(DomainError::DrawerNotHeld {
+                drawer_id,
+                teller: 8
+            }))
+        );
+
+        let alice_shift = bank
+            .hand_off_drawer(&alice, drawer_id, 8, notes(&[(100, 3), (50, 9), (20, 19)]))
+            .unwrap();
+        assert_eq!(alice_shift.teller, 7);
+        assert_eq!(alice_shift.deposits, Money(dec!(300)));
+        assert_eq!(alice_shift.withdrawals, Money(dec!(70)));
+        assert_eq!(alice_shift.balance, CashBalance::Balanced);
+
+        let withdraw = Transaction::Withdraw(Money(dec!(100)));
+        bank.process_cash(&bob, drawer_id, account_id, withdraw, notes(&[(100, 1)]))
+            .unwrap();
+        let bob_shift = bank
+            .close_drawer(&bob, drawer_id, notes(&[(100, 2), (50, 9), (20, 18)]))
+            .unwrap();
+        assert_eq!(bob_shift.teller, 8);
+        assert_eq!(bob_shift.opening, Money(dec!(1130)));
+        assert_eq!(bob_shift.expected, Money(dec!(1030)));
+        assert_eq!(bob_shift.balance, CashBalance::Short(Money(dec!(20))));
+        assert_eq!(bob_shift.variances, [(Denomination(dec!(20)), -1)].into());
+
+        let drawer = bank.cash_drawer(drawer_id).unwrap();
+        assert_eq!(drawer.shifts, vec![alice_shift, bob_shift]);
+        assert_eq!(bank.load(account_id).unwrap().balance, Money(dec!(130)));
+        assert_eq!(
+            bank.close_drawer(&bob, drawer_id, notes(&[])),
+            Err(AppError::Domain(DomainError::DrawerClosed(drawer_id)))
+        );
+        assert!(bank.open_drawer(&alice, notes(&[(20, 1)])).is_ok());
+    }
+
+    #[test]
+    fn test_bank_will_send_rate_limited_alerts_after_committed_operations() {
+        let mail = Arc::new(LocalMailServer::new());
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new()))
+            .with_notifier(mail.clone())
+            .with_alert_rate_limit(RateLimit {
+                max: 2,
+                per: Duration::from_secs(3600),
+            });
+        let teller = RequestContext::teller(7, "req-0");
+        let ada = Customer::builder(1)
+            .name("Ada Lovelace")
+            .email("ada@example.com");
+        bank.register_customer(&teller, ada.build()).unwrap();
+        let account_id = bank.create_account(1).unwrap();
+        bank.process(account_id, Transaction::Deposit(Money(dec!(1000))))
+            .unwrap();
+
+        let large = AlertCondition::LargeWithdrawal(Money(dec!(100)));
+        let low = AlertCondition::LowBalance(Money(dec!(300)));
+        assert_eq!(
+            bank.add_alert_rule(&customer(2), 1, account_id, large),
+            Err(AppError::Domain(DomainError::CustomerAccessDenied {
+                customer: 1,
+                caller: 2
+            }))
+        );
+        assert_eq!(
+            bank.add_alert_rule(&teller, 2, account_id, large),
+            Err(AppError::Domain(DomainError::NotAHolder {
+                account_id,
+                customer: 2
+            }))
+        );
+        let large_rule = bank
+            .add_alert_rule(&customer(1), 1, account_id, large)
+            .unwrap();
+        bank.add_alert_rule(&customer(1), 1, account_id, low)
+            .unwrap();
+        assert_eq!(bank.alert_rules(1).len(), 2);
+
+        let withdraw = |amount| Transaction::Withdraw(Money(amount));
+        bank.process(account_id, withdraw(dec!(150))).unwrap();
+        bank.process(account_id, withdraw(dec!(50))).unwrap();
+        bank.process(account_id, withdraw(dec!(5000))).unwrap_err();
+        assert_eq!(mail.sent().len(), 1);
+        assert_eq!(mail.sent()[0].to, "ada@example.com");
+        assert_eq!(
+            mail.sent()[0].subject,
+            format!("Large withdrawal from account {account_id}")
+        );
+
+        // Raises both alerts, but only one fits in the hour's budget.
+        bank.process(account_id, withdraw(dec!(600))).unwrap();
+        bank.process(account_id, withdraw(dec!(150))).unwrap();
+        let sent = mail.sent();
+        assert_eq!(sent.len(), 2);
+        assert!(sent[1].body.contains("600"));
+
+        bank.remove_alert_rule(&customer(1), large_rule).unwrap();
+        assert_eq!(
+            bank.remove_alert_rule(&customer(1), large_rule),
+            Err(AppError::Domain(DomainError::AlertRuleNotFound(large_rule)))
+        );
+        assert_eq!(bank.alert_rules(1).len(), 1);
+    }
+
+    #[test]
+    fn test_bank_will_stop_alerting_holders_removed_from_an_account() {
+        let mail = Arc::new(LocalMailServer::new());
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new())).with_notifier(mail.clone());
+        let teller = RequestContext::teller(7, "req-0");
+        for (id, name, email) in [
+            (1, "Ada Lovelace", "ada@example.com"),
+            (2, "Charles Babbage", "charles@example.com"),
+        ] {
+            let holder = Customer::builder(id).name(name).email(email);
+            bank.register_customer(&teller, holder.build()).unwrap();
+        }
+        let account_id = bank.create_account(1).unwrap();
+        bank.add_holder(&teller, account_id, 2, HolderRole::Signatory)
+            .unwrap();
+        bank.process(account_id, Transaction::Deposit(Money(dec!(1000))))
+            .unwrap();
+
+        let large = AlertCondition::LargeWithdrawal(Money(dec!(100)));
+        bank.add_alert_rule(&customer(1), 1, account_id, large)
+            .unwrap();
+        bank.add_alert_rule(&customer(2), 2, account_id, large)
+            .unwrap();
+        bank.remove_holder(&teller, account_id, 2).unwrap();
+        assert!(bank.alert_rules(2).is_empty());
+
+        bank.process(account_id, Transaction::Withdraw(Money(dec!(150))))
+            .unwrap();
+        let sent = mail.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "ada@example.com");
+    }
+
+    fn tenant_banks() -> (Bank<InMemoryRepo>, Bank<InMemoryRepo>) {
+        let repo = Arc::new(InMemoryRepo::new());
+        let customers: Arc<dyn CustomerStore> = Arc::new(InMemoryCustomerStore::new());
+        let bank = |tenant| {
+            Bank::new(Arc::clone(&repo))
+                .with_customer_store(Arc::clone(&customers))
+                .with_tenant(Tenant::builder(tenant).build())
+        };
+        (bank("acme"), bank("globex"))
+    }
+
+    #[test]
+    fn test_bank_will_never_let_one_tenant_read_or_move_money_into_another() {
+        let (acme, globex) = tenant_banks();
+        let ctx = customer(1);
+        acme.register_customer(&ctx, Customer::builder(1).name("Ada").build())
+            .unwrap();
+        let target = acme.create_account_as(&ctx, 1).unwrap();
+        let source = globex.create_account_as(&ctx, 1).unwrap();
+        globex
+            .process(source, Transaction::Deposit(Money(dec!(100))))
+            .unwrap();
+
+        let not_found = Err(AppError::Domain(DomainError::AccountNotFound(target)));
+        let transfer = Transaction::Transfer {
+            to: target,
+            amount: Money(dec!(40)),
+        };
+        assert_eq!(globex.process_as(&ctx, source, transfer.clone()), not_found);
+        assert_eq!(globex.process(source, transfer), not_found);
+        let deposit = Transaction::Deposit(Money(dec!(1)));
+        assert_eq!(globex.process(target, deposit), not_found);
+        assert_eq!(
+            globex.freeze(target),
+            Err(AppError::Domain(DomainError::AccountNotFound(target)))
+        );
+        assert_eq!(
+            globex.account_as(&ctx, target),
+            Err(AppError::Domain(DomainError::AccountNotFound(target)))
+        );
+        assert_eq!(
+            globex.find_account(&AccountRef::Id(target)),
+            Err(AppError::Domain(DomainError::AccountNotFound(target)))
+        );
+        let listed = globex.accounts_of(1, PageRequest::first(10)).unwrap();
+        let ids: Vec<_> = listed.items.iter().map(|account| account.id).collect();
+        assert_eq!(ids, vec![source]);
+        assert_eq!(
+            globex.customer(1),
+            Err(AppError::Domain(DomainError::CustomerNotFound(1)))
+        );
+
+        assert_eq!(globex.load(source).unwrap().balance, Money(dec!(100)));
+        assert_eq!(acme.load(target).unwrap().balance, Money(dec!(0)));
+        assert_eq!(acme.load(target).unwrap().tenant, TenantId::new("acme"));
+
+        let snapshot = acme.take_snapshot().unwrap();
+        let (_, empty_globex) = tenant_banks();
+        assert_eq!(
+            empty_globex.restore_snapshot(snapshot),
+            Err(AppError::Snapshot(SnapshotError::TenantMismatch(
+                TenantId::new("acme")
+            )))
+        );
+    }
+
+    #[test]
+    fn test_bank_will_apply_tenant_products_and_limits() {
+        let tenant = Tenant::builder("acme")
+            .product(Product::builder("CHK").name("Checking").build())
+            .max_transaction(Money(dec!(500)))
+            .max_accounts_per_owner(1)
+            .build();
+        let bank = Bank::new(Arc::new(InMemoryRepo::new())).with_tenant(tenant);
+        let ctx = customer(1);
+
+        assert_eq!(
+            bank.open_account(&ctx, 1, Some("SAV")),
+            Err(AppError::Domain(DomainError::UnknownProduct {
+                tenant: TenantId::new("acme"),
+                product: "SAV".into(),
+            }))
+        );
+        let account_id = bank.open_account(&ctx, 1, Some("CHK")).unwrap();
+        assert_eq!(
+            bank.load(account_id).unwrap().product.as_deref(),
+            Some("CHK")
+        );
+        assert_eq!(
+            bank.open_account(&ctx, 1, None),
+            Err(AppError::Domain(DomainError::AccountLimitReached {
+                owner: 1,
+                limit: 1
+            }))
+        );
+        bank.open_account(&ctx, 2, None).unwrap();
+
+        bank.process(account_id, Transaction::Deposit(Money(dec!(500))))
+            .unwrap();
+        assert_eq!(
+            bank.process_as(&ctx, account_id, Transaction::Withdraw(Money(dec!(501)))),
+            Err(AppError::Domain(DomainError::TransactionLimitExceeded {
+                account_id,
+                amount: Money(dec!(501)),
+                limit: Money(dec!(500)),
+            }))
+        );
+        assert_eq!(bank.load(account_id).unwrap().balance, Money(dec!(500)));
+    }
+
+    #[test]
+    fn test_bank_will_apply_product_terms_to_debits_fees_and_interest() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let tenant = Tenant::builder("acme")
+            .product(
+                Product::builder("CHK")
+                    .overdraft_limit(Money(dec!(100)))
+                    .daily_withdrawal_limit(Money(dec!(300)))
+                    .monthly_fee(Money(dec!(5)))
+                    .build(),
+            )
+            .product(Product::builder("SAV").interest_rate(dec!(0.03)).build())
+            .build();
+        let bank = Bank::new(Arc::new(InMemoryRepo::new()))
+            .with_tenant(tenant)
+            .with_clock(Arc::new(FixedClock::new(today)));
+        let ctx = customer(1);
+        let checking = bank.open_account(&ctx, 1, Some("CHK")).unwrap();
+        let savings = bank.open_account(&ctx, 1, Some("SAV")).unwrap();
+        let plain = bank.open_account(&ctx, 1, None).unwrap();
+        bank.process(checking, Transaction::Deposit(Money(dec!(150))))
+            .unwrap();
+        bank.process(savings, Transaction::Deposit(Money(dec!(1000))))
+            .unwrap();
+
+        let to_savings = |amount| Transaction::Transfer {
+            to: savings,
+            amount: Money(amount),
+        };
+        assert_eq!(
+            bank.process_as(&ctx, checking, to_savings(dec!(200))),
+            Ok(Money(dec!(-50)))
+        );
+        assert_eq!(
+            bank.process_as(&ctx, checking, Transaction::Withdraw(Money(dec!(60)))),
+            Err(AppError::Domain(DomainError::InsufficientFunds {
+                account_id: checking,
+                requested: Money(dec!(60)),
+                available: Money(dec!(50)),
+            }))
+        );
+        bank.process(checking, Transaction::Deposit(Money(dec!(500))))
+            .unwrap();
+        assert_eq!(
+            bank.process_as(&ctx, checking, Transaction::Withdraw(Money(dec!(101)))),
+            Err(AppError::Domain(
+                DomainError::DailyWithdrawalLimitExceeded {
+                    account_id: checking,
+                    requested: Money(dec!(101)),
+                    remaining: Money(dec!(100)),
+                }
+            ))
+        );
+        assert_eq!(
+            bank.process_as(&ctx, plain, Transaction::Withdraw(Money(dec!(1)))),
+            Err(AppError::Domain(DomainError::InsufficientFunds {
+                account_id: plain,
+                requested: Money(dec!(1)),
+                available: Money(dec!(0)),
+            }))
+        );
+
+        assert_eq!(
+            bank.charge_monthly_fees().unwrap(),
+            vec![(checking, Ok(Money(dec!(5))))]
+        );
+        assert_eq!(bank.load(checking).unwrap().balance, Money(dec!(445)));
+        assert_eq!(
+            bank.credit_monthly_interest().unwrap(),
+            vec![(savings, Ok(Money(dec!(3))))]
+        );
+        assert_eq!(bank.load(savings).unwrap().balance, Money(dec!(1203)));
+        assert_eq!(bank.load(plain).unwrap().balance, Money(dec!(0)));
+    }
+
+    #[test]
+    fn test_bank_will_hold_account_limit_when_opening_concurrently() {
+        let tenant = Tenant::builder("acme").max_accounts_per_owner(2).build();
+        let bank = Bank::new(Arc::new(InMemoryRepo::new())).with_tenant(tenant);
+
+        let opened: usize = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| bank.open_account(&customer(1), 1, None).is_ok()))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| usize::from(handle.join().unwrap()))
+                .sum()
+        });
+
+        assert_eq!(opened, 2);
+        let owned = bank.accounts_of(1, PageRequest::default()).unwrap();
+        assert_eq!(owned.items.len(), 2);
     }
 }
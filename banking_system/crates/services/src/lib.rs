@@ -1 +1,4 @@
+pub mod account_locks;
+pub mod async_bank;
+pub mod authorization;
 pub mod bank;
@@ -0,0 +1,304 @@
+//! Drives random operation sequences against `Bank<InMemoryRepo>` and a plain
+//! reference model, checking that both agree and that the bank's invariants
+//! hold after every step. Failing sequences are shrunk by proptest.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use bank_core::{
+    account::{AccountId, AccountRepository, AccountStatus, Money, Transaction},
+    errors::{AccountOperation, AppError, DomainError},
+};
+use bank_infra::storage::InMemoryRepo;
+use bank_services::bank::Bank;
+use proptest::prelude::*;
+use rust_decimal::Decimal;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Create {
+        owner: u64,
+    },
+    Deposit {
+        account: AccountId,
+        cents: i64,
+    },
+    Withdraw {
+        account: AccountId,
+        cents: i64,
+    },
+    Transfer {
+        from: AccountId,
+        to: AccountId,
+        cents: i64,
+    },
+    Freeze {
+        account: AccountId,
+    },
+    Close {
+        account: AccountId,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ModelAccount {
+    balance: Decimal,
+    status: AccountStatus,
+}
+
+/// The simplest thing that could possibly be a bank.
+#[derive(Debug, Default)]
+struct Model {
+    accounts: BTreeMap<AccountId, ModelAccount>,
+    next_id: AccountId,
+}
+
+impl Model {
+    fn get(&self, id: AccountId) -> Result<ModelAccount, DomainError> {
+        self.accounts
+            .get(&id)
+            .cloned()
+            .ok_or(DomainError::AccountNotFound(id))
+    }
+
+    fn not_active(
+        id: AccountId,
+        account: &ModelAccount,
+        operation: AccountOperation,
+    ) -> DomainError {
+        DomainError::AccountNotActive {
+            account_id: id,
+            status: account.status.clone(),
+            operation,
+        }
+    }
+
+    fn withdraw(
+        id: AccountId,
+        account: &mut ModelAccount,
+        amount: Decimal,
+    ) -> Result<(), DomainError> {
+        if amount <= Decimal::ZERO {
+            return Err(DomainError::InvalidAmount {
+                account_id: id,
+                amount: Money(amount),
+            });
+        }
+        if amount > account.balance {
+            return Err(DomainError::InsufficientFunds {
+                account_id: id,
+                requested: Money(amount),
+                available: Money(account.balance),
+            });
+        }
+        match account.status {
+            AccountStatus::Closed | AccountStatus::Frozen => {
+                Err(Self::not_active(id, account, AccountOperation::Withdraw))
+            }
+            AccountStatus::Active => {
+                account.balance -= amount;
+                Ok(())
+            }
+        }
+    }
+
+    fn deposit(
+        id: AccountId,
+        account: &mut ModelAccount,
+        amount: Decimal,
+    ) -> Result<(), DomainError> {
+        if amount <= Decimal::ZERO {
+            return Err(DomainError::InvalidAmount {
+                account_id: id,
+                amount: Money(amount),
+            });
+        }
+        if account.status == AccountStatus::Closed {
+            return Err(Self::not_active(id, account, AccountOperation::Deposit));
+        }
+        account.balance += amount;
+        Ok(())
+    }
+
+    /// Applies `op`, returning the balance the bank should report on success.
+    fn apply(&mut self, op: &Op) -> Result<Option<Decimal>, DomainError> {
+        match *op {
+            Op::Create { .. } => {
+                self.next_id += 1;
+                self.accounts.insert(
+                    self.next_id,
+                    ModelAccount {
+                        balance: Decimal::ZERO,
+                        status: AccountStatus::Active,
+                    },
+                );
+                Ok(None)
+            }
+            Op::Deposit { account, cents } => {
+                let mut state = self.get(account)?;
+                Self::deposit(account, &mut state, Decimal::new(cents, 2))?;
+                self.accounts.insert(account, state.clone());
+                Ok(Some(state.balance))
+            }
+            Op::Withdraw { account, cents } => {
+                let mut state = self.get(account)?;
+                Self::withdraw(account, &mut state, Decimal::new(cents, 2))?;
+                self.accounts.insert(account, state.clone());
+                Ok(Some(state.balance))
+            }
+            Op::Transfer { from, to, cents } => {
+                if from == to {
+                    return Err(DomainError::TransferToSelf(from));
+                }
+                let mut src = self.get(from)?;
+                let mut dest = self.get(to)?;
+                Self::withdraw(from, &mut src, Decimal::new(cents, 2))?;
+                Self::deposit(to, &mut dest, Decimal::new(cents, 2))?;
+                self.accounts.insert(from, src.clone());
+                self.accounts.insert(to, dest);
+                Ok(Some(src.balance))
+            }
+            Op::Freeze { account } => {
+                let mut state = self.get(account)?;
+                if state.status == AccountStatus::Closed {
+                    return Err(Self::not_active(account, &state, AccountOperation::Freeze));
+                }
+                state.status = AccountStatus::Frozen;
+                self.accounts.insert(account, state);
+                Ok(None)
+            }
+            Op::Close { account } => {
+                let mut state = self.get(account)?;
+                if state.status == AccountStatus::Closed {
+                    return Err(Self::not_active(account, &state, AccountOperation::Close));
+                }
+                if !state.balance.is_zero() {
+                    return Err(DomainError::BalanceNotZero {
+                        account_id: account,
+                        balance: Money(state.balance),
+                    });
+                }
+                state.status = AccountStatus::Closed;
+                self.accounts.insert(account, state);
+                Ok(None)
+            }
+        }
+    }
+}
+
+fn run(bank: &mut Bank<InMemoryRepo>, op: &Op) -> Result<Option<Decimal>, AppError> {
+    match *op {
+        Op::Create { owner } => bank.create_account(owner).map(|_| None),
+        Op::Deposit { account, cents } => bank
+            .process(account, Transaction::Deposit(Money(Decimal::new(cents, 2))))
+            .map(|balance| Some(balance.0)),
+        Op::Withdraw { account, cents } => bank
+            .process(
+                account,
+                Transaction::Withdraw(Money(Decimal::new(cents, 2))),
+            )
+            .map(|balance| Some(balance.0)),
+        Op::Transfer { from, to, cents } => bank
+            .process(
+                from,
+                Transaction::Transfer {
+                    to,
+                    amount: Money(Decimal::new(cents, 2)),
+                },
+            )
+            .map(|balance| Some(balance.0)),
+        Op::Freeze { account } => bank.freeze(account).map(|_| None),
+        Op::Close { account } => bank.close(account).map(|_| None),
+    }
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    // Ids run one past the accounts a short sequence is likely to create so
+    // that lookups of missing accounts are exercised too.
+    let account = 0..6u64;
+    let cents = -500..20_000i64;
+
+    prop_oneof![
+        2 => (1..4u64).prop_map(|owner| Op::Create { owner }),
+        4 => (account.clone(), cents.clone())
+            .prop_map(|(account, cents)| Op::Deposit { account, cents }),
+        3 => (account.clone(), cents.clone())
+            .prop_map(|(account, cents)| Op::Withdraw { account, cents }),
+        4 => (account.clone(), account.clone(), cents)
+            .prop_map(|(from, to, cents)| Op::Transfer { from, to, cents }),
+        1 => account.clone().prop_map(|account| Op::Freeze { account }),
+        1 => account.prop_map(|account| Op::Close { account }),
+    ]
+}
+
+fn total(bank: &Bank<InMemoryRepo>, ids: &[AccountId]) -> Decimal {
+    ids.iter()
+        .map(|id| {
+            bank.repo
+                .get(&bank.tenant.id, *id)
+                .unwrap()
+                .unwrap()
+                .balance
+                .0
+        })
+        .sum()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn bank_agrees_with_model_and_keeps_invariants(ops in prop::collection::vec(op_strategy(), 1..60)) {
+        let mut bank = Bank::new(Arc::new(InMemoryRepo::new()));
+        let mut model = Model::default();
+        let mut expected_total = Decimal::ZERO;
+
+        for op in &ops {
+            let ids: Vec<AccountId> = model.accounts.keys().copied().collect();
+            let before_total = total(&bank, &ids);
+            let closed_before: Vec<_> = ids
+                .iter()
+                .map(|id| bank.repo.get(&bank.tenant.id, *id).unwrap().unwrap())
+                .filter(|account| account.status == AccountStatus::Closed)
+                .collect();
+
+            let expected = model.apply(op);
+            let actual = run(&mut bank, op);
+
+            match (&expected, &actual) {
+                (Ok(expected), Ok(actual)) => prop_assert_eq!(expected, actual, "op {:?}", op),
+                (Err(expected), Err(AppError::Domain(actual))) => {
+                    prop_assert_eq!(expected, actual, "op {:?}", op)
+                }
+                _ => prop_assert!(false, "op {:?}: model {:?}, bank {:?}", op, expected, actual),
+            }
+
+            if actual.is_ok() {
+                match *op {
+                    Op::Deposit { cents, .. } => expected_total += Decimal::new(cents, 2),
+                    Op::Withdraw { cents, .. } => expected_total -= Decimal::new(cents, 2),
+                    _ => {}
+                }
+            }
+
+            let ids: Vec<AccountId> = model.accounts.keys().copied().collect();
+            if let Op::Transfer { .. } = op {
+                prop_assert_eq!(total(&bank, &ids), before_total, "transfer changed total money");
+            }
+            prop_assert_eq!(total(&bank, &ids), expected_total);
+
+            for id in &ids {
+                let account = bank.repo.get(&bank.tenant.id, *id).unwrap().unwrap();
+                let state = &model.accounts[id];
+                prop_assert!(account.balance.0 >= Decimal::ZERO, "account {} went negative", id);
+                prop_assert_eq!(account.balance.0, state.balance);
+                prop_assert_eq!(&account.status, &state.status);
+            }
+
+            for closed in closed_before {
+                prop_assert_eq!(bank.repo.get(&bank.tenant.id, closed.id).unwrap(), Some(closed));
+            }
+        }
+
+        prop_assert!(bank.audit.verify().is_ok());
+    }
+}
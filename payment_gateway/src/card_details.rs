@@ -0,0 +1,378 @@
+use std::fmt;
+
+use chrono::{Datelike, NaiveDate};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use thiserror::Error;
+
+/// Card errors never carry the card number, so they are safe to log.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CardError {
+    #[error("card number must be 12 to 19 digits")]
+    InvalidNumber,
+    #[error("card number fails its check digit")]
+    FailedLuhn,
+    #[error("card number is not from a supported brand")]
+    UnknownBrand,
+    #[error("{brand:?} card numbers are not {length} digits long")]
+    InvalidLength { brand: CardBrand, length: usize },
+    #[error("expiry {month:02}/{year} is not a valid month")]
+    InvalidExpiry { month: u32, year: i32 },
+    #[error("card expired at the end of {month:02}/{year}")]
+    Expired { month: u32, year: i32 },
+    #[error("{brand:?} security codes are {expected} digits")]
+    InvalidCvv { brand: CardBrand, expected: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CardBrand {
+    Visa,
+    Mastercard,
+    Amex,
+    Verve,
+}
+
+impl CardBrand {
+    /// The brand issuing `digits`, from the ranges of its leading digits.
+    pub fn from_number(digits: &str) -> Option<CardBrand> {
+        let prefix = |len: usize| {
+            digits
+                .get(..len)
+                .and_then(|prefix| prefix.parse::<u32>().ok())
+        };
+        let within =
+            |len, low, high| prefix(len).is_some_and(|prefix| (low..=high).contains(&prefix));
+
+        if within(6, 506_099, 506_198) || within(6, 507_865, 507_964) || within(6, 650_002, 650_027)
+        {
+            Some(CardBrand::Verve)
+        } else if within(2, 34, 34) || within(2, 37, 37) {
+            Some(CardBrand::Amex)
+        } else if within(2, 51, 55) || within(4, 2221, 2720) {
+            Some(CardBrand::Mastercard)
+        } else if within(1, 4, 4) {
+            Some(CardBrand::Visa)
+        } else {
+            None
+        }
+    }
+
+    pub fn lengths(self) -> &'static [usize] {
+        match self {
+            CardBrand::Visa => &[13, 16, 19],
+            CardBrand::Mastercard => &[16],
+            CardBrand::Amex => &[15],
+            CardBrand::Verve => &[16, 18, 19],
+        }
+    }
+
+    pub fn cvv_length(self) -> usize {
+        match self {
+            CardBrand::Amex => 4,
+            _ => 3,
+        }
+    }
+}
+
+/// Whether the last digit of `digits` is the Luhn check digit of the rest.
+/// Anything but ASCII digits fails.
+fn luhn_valid(digits: &str) -> bool {
+    if !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return false;
+    }
+
+    let sum: u32 = digits
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(index, byte)| {
+            let digit = u32::from(byte - b'0');
+            match index % 2 {
+                0 => digit,
+                _ if digit > 4 => digit * 2 - 9,
+                _ => digit * 2,
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// The secret card tokens are keyed with. It must be kept apart from the
+/// stored tokens, in configuration or a key store: without it a token
+/// cannot be matched back to its number by trying every possible one.
+#[derive(Clone)]
+pub struct TokenKey(Vec<u8>);
+
+impl TokenKey {
+    pub fn new(secret: &[u8]) -> TokenKey {
+        TokenKey(secret.to_vec())
+    }
+}
+
+impl fmt::Debug for TokenKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TokenKey(***)")
+    }
+}
+
+/// A card number that passed its check digit and belongs to a supported
+/// brand. Only the first six and last four digits are ever shown.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Pan {
+    digits: String,
+    brand: CardBrand,
+}
+
+impl Pan {
+    /// Parses a card number, ignoring spaces and dashes between digits.
+    pub fn parse(number: &str) -> Result<Pan, CardError> {
+        let digits: String = number.chars().filter(|c| !matches!(c, ' ' | '-')).collect();
+        if !(12..=19).contains(&digits.len()) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(CardError::InvalidNumber);
+        }
+        if !luhn_valid(&digits) {
+            return Err(CardError::FailedLuhn);
+        }
+        let brand = CardBrand::from_number(&digits).ok_or(CardError::UnknownBrand)?;
+        if !brand.lengths().contains(&digits.len()) {
+            return Err(CardError::InvalidLength {
+                brand,
+                length: digits.len(),
+            });
+        }
+
+        Ok(Pan { digits, brand })
+    }
+
+    pub fn brand(&self) -> CardBrand {
+        self.brand
+    }
+
+    pub fn last4(&self) -> &str {
+        &self.digits[self.digits.len() - 4..]
+    }
+
+    /// The number with everything but the first six and last four digits
+    /// hidden, as card receipts show it.
+    pub fn masked(&self) -> String {
+        let hidden = "*".repeat(self.digits.len() - 10);
+        format!("{}{hidden}{}", &self.digits[..6], self.last4())
+    }
+
+    /// A stand-in for the number, for storing and matching cards without
+    /// keeping the number itself: an HMAC-SHA256 of the digits under `key`,
+    /// so the same number and key always give the same token.
+    pub fn token(&self, key: &TokenKey) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&key.0).expect("HMAC accepts keys of any length");
+        mac.update(self.digits.as_bytes());
+        let digest: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .take(16)
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        format!("tok_{digest}_{}", self.last4())
+    }
+}
+
+impl fmt::Debug for Pan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.masked())
+    }
+}
+
+/// The last month a card can be used in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Expiry {
+    pub month: u32,
+    pub year: i32,
+}
+
+impl Expiry {
+    pub fn new(month: u32, year: i32) -> Result<Expiry, CardError> {
+        if (1..=12).contains(&month) && (2000..=2099).contains(&year) {
+            Ok(Expiry { month, year })
+        } else {
+            Err(CardError::InvalidExpiry { month, year })
+        }
+    }
+
+    /// Cards stay valid until the end of their expiry month.
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        (today.year(), today.month()) > (self.year, self.month)
+    }
+}
+
+/// A card security code, never shown.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Cvv(String);
+
+impl Cvv {
+    pub fn parse(code: &str, brand: CardBrand) -> Result<Cvv, CardError> {
+        let expected = brand.cvv_length();
+        if code.len() == expected && code.bytes().all(|b| b.is_ascii_digit()) {
+            Ok(Cvv(code.into()))
+        } else {
+            Err(CardError::InvalidCvv { brand, expected })
+        }
+    }
+}
+
+impl fmt::Debug for Cvv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardDetails {
+    pub pan: Pan,
+    pub expiry: Expiry,
+    /// Only held until the first authorization; card rules forbid keeping
+    /// it afterwards.
+    pub cvv: Option<Cvv>,
+}
+
+impl CardDetails {
+    pub fn new(number: &str, expiry: Expiry, cvv: &str) -> Result<CardDetails, CardError> {
+        let pan = Pan::parse(number)?;
+        let cvv = Cvv::parse(cvv, pan.brand())?;
+        Ok(CardDetails {
+            pan,
+            expiry,
+            cvv: Some(cvv),
+        })
+    }
+
+    /// Drops the security code once it has served its authorization.
+    pub fn forget_cvv(&mut self) {
+        self.cvv = None;
+    }
+
+    pub fn brand(&self) -> CardBrand {
+        self.pan.brand()
+    }
+
+    pub fn ensure_not_expired(&self, today: NaiveDate) -> Result<(), CardError> {
+        if self.expiry.is_expired(today) {
+            Err(CardError::Expired {
+                month: self.expiry.month,
+                year: self.expiry.year,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use chrono::NaiveDate;
+
+    use crate::card_details::{
+        CardBrand, CardDetails, CardError, Expiry, Pan, TokenKey, luhn_valid,
+    };
+
+    #[test]
+    fn test_pan_will_require_a_valid_check_digit() {
+        assert!(luhn_valid("4111111111111111"));
+        assert!(luhn_valid("79927398713"));
+        assert!(!luhn_valid("4111111111111112"));
+        assert!(!luhn_valid("4111 1111 1111 1111"));
+        assert!(!luhn_valid("41é1"));
+
+        assert!(Pan::parse("4111 1111 1111 1111").is_ok());
+        assert!(Pan::parse("4111-1111-1111-1111").is_ok());
+        assert_eq!(Pan::parse("4111111111111112"), Err(CardError::FailedLuhn));
+        assert_eq!(
+            Pan::parse("4111x11111111111"),
+            Err(CardError::InvalidNumber)
+        );
+        assert_eq!(Pan::parse("42"), Err(CardError::InvalidNumber));
+        assert_eq!(Pan::parse("6011111111111117"), Err(CardError::UnknownBrand));
+    }
+
+    #[test]
+    fn test_pan_will_detect_brand_from_bin_ranges() {
+        let brand = |number| Pan::parse(number).map(|pan| pan.brand());
+
+        assert_eq!(brand("4111111111111111"), Ok(CardBrand::Visa));
+        assert_eq!(brand("4222222222222"), Ok(CardBrand::Visa));
+        assert_eq!(brand("5555555555554444"), Ok(CardBrand::Mastercard));
+        assert_eq!(brand("2223003122003222"), Ok(CardBrand::Mastercard));
+        assert_eq!(brand("378282246310005"), Ok(CardBrand::Amex));
+        assert_eq!(brand("341111111111111"), Ok(CardBrand::Amex));
+        assert_eq!(brand("5061000000000000007"), Ok(CardBrand::Verve));
+        assert_eq!(brand("6500020000000000"), Ok(CardBrand::Verve));
+        assert_eq!(brand("4000000000000000006"), Ok(CardBrand::Visa));
+        assert_eq!(
+            brand("3782822463100003"),
+            Err(CardError::InvalidLength {
+                brand: CardBrand::Amex,
+                length: 16,
+            })
+        );
+    }
+
+    #[test]
+    fn test_card_details_will_check_expiry_and_cvv_length() {
+        let expiry = Expiry::new(6, 2030).unwrap();
+        let details = CardDetails::new("378282246310005", expiry, "1234").unwrap();
+
+        assert_eq!(details.ensure_not_expired(date(2030, 6, 30)), Ok(()));
+        assert_eq!(
+            details.ensure_not_expired(date(2030, 7, 1)),
+            Err(CardError::Expired {
+                month: 6,
+                year: 2030
+            })
+        );
+        assert_eq!(
+            Expiry::new(13, 2030),
+            Err(CardError::InvalidExpiry {
+                month: 13,
+                year: 2030
+            })
+        );
+        assert_eq!(
+            CardDetails::new("378282246310005", expiry, "123"),
+            Err(CardError::InvalidCvv {
+                brand: CardBrand::Amex,
+                expected: 4
+            })
+        );
+        assert_eq!(
+            CardDetails::new("4111111111111111", expiry, "1234"),
+            Err(CardError::InvalidCvv {
+                brand: CardBrand::Visa,
+                expected: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_card_details_will_never_print_the_number_or_code() {
+        let expiry = Expiry::new(6, 2030).unwrap();
+        let details = CardDetails::new("5555 5555 5555 4444", expiry, "987").unwrap();
+
+        let printed = format!("{details:?}");
+        assert!(!printed.contains("5555555555554444"));
+        assert!(!printed.contains("987"));
+        assert!(printed.contains("555555******4444"));
+
+        let key = TokenKey::new(b"test key");
+        let token = details.pan.token(&key);
+        assert!(token.starts_with("tok_") && token.ends_with("4444"));
+        assert!(!token.contains("5555555555554444"));
+        assert_eq!(token, Pan::parse("5555555555554444").unwrap().token(&key));
+        assert_ne!(token, Pan::parse("4111111111111111").unwrap().token(&key));
+        assert_ne!(token, details.pan.token(&TokenKey::new(b"other key")));
+        assert_eq!(format!("{key:?}"), "TokenKey(***)");
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+}
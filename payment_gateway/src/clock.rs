@@ -0,0 +1,44 @@
+use std::{fmt, sync::Mutex};
+
+use chrono::{NaiveDate, Utc};
+
+/// Source of the current date, injectable so that card expiry can be tested.
+pub trait Clock: Send + Sync {
+    fn today(&self) -> NaiveDate;
+}
+
+impl fmt::Debug for dyn Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Clock({})", self.today())
+    }
+}
+
+/// The real date, in UTC.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        Utc::now().date_naive()
+    }
+}
+
+/// A clock that only moves when told to.
+#[derive(Debug)]
+pub struct FixedClock(Mutex<NaiveDate>);
+
+impl FixedClock {
+    pub fn new(today: NaiveDate) -> Self {
+        Self(Mutex::new(today))
+    }
+
+    pub fn set(&self, today: NaiveDate) {
+        *self.0.lock().unwrap() = today;
+    }
+}
+
+impl Clock for FixedClock {
+    fn today(&self) -> NaiveDate {
+        *self.0.lock().unwrap()
+    }
+}
@@ -1,2 +1,5 @@
+pub mod card_details;
+pub mod clock;
+pub mod money;
 pub mod services;
 pub mod types;
@@ -1,15 +1,67 @@
-use payment_gateway::services::{
-    card::Card, crypto_wallet::CryptoWallet, payment_service::PaymentService, paypal::Paypal,
+use payment_gateway::{
+    card_details::{CardDetails, Expiry, TokenKey},
+    money::{Currency, Money},
+    services::{
+        card::Card,
+        crypto_wallet::CryptoWallet,
+        payment_service::PaymentService,
+        paypal::Paypal,
+        router::{Route, Router},
+    },
+    types::{PaymentError, PaymentMethod},
 };
+use rust_decimal::dec;
 
-fn main() {
-    let paypal: Box<Paypal> = Box::new(Paypal::new().balance(10.into()).build());
-    let _card: Box<Card> = Box::new(Card::new().build());
-    let _crypto_wallet: Box<CryptoWallet> = Box::new(CryptoWallet::new().balance(12.6).build());
+fn main() -> Result<(), PaymentError> {
+    let usd = |amount| Money::new(amount, Currency::Usd);
+    let paypal: Box<Paypal> = Box::new(Paypal::builder().balance(usd(dec!(10))).build());
+    let details = CardDetails::new("4111 1111 1111 1111", Expiry::new(12, 2099)?, "123")?;
+    if let Ok(secret) = std::env::var("CARD_TOKEN_KEY") {
+        let token = details.pan.token(&TokenKey::new(secret.as_bytes()));
+        println!("card {:?} as {token}", details.pan);
+    }
+    let card: Box<Card> = Box::new(Card::builder(details).balance(usd(dec!(500))).build());
+    let crypto_wallet: Box<CryptoWallet> = Box::new(
+        CryptoWallet::builder()
+            .balance(Money::new(dec!(12.6), Currency::Btc))
+            .build(),
+    );
 
-    let mut payment_service = PaymentService::new(paypal);
-    let auth = payment_service.processor.authorize(12.into());
-    println!("{auth}");
-    let _ = payment_service.processor.capture(12.into());
-    let _ = payment_service.processor.refund(12.into());
+    let router = Router::new()
+        .route(
+            Route::builder("paypal", paypal)
+                .method(PaymentMethod::Paypal)
+                .fee_rate(dec!(0.034))
+                .build(),
+        )
+        .route(
+            Route::builder("card", card)
+                .method(PaymentMethod::Card)
+                .fee_rate(dec!(0.029))
+                .build(),
+        )
+        .route(
+            Route::builder("crypto", crypto_wallet)
+                .method(PaymentMethod::Crypto)
+                .currency(Currency::Btc)
+                .build(),
+        );
+    let mut payment_service = PaymentService::with_router(router);
+
+    let declined = payment_service.create_payment(PaymentMethod::Paypal, usd(dec!(12)));
+    if let Err(err) = payment_service.authorize(declined) {
+        println!("payment {declined}: {err}");
+    }
+
+    let payment = payment_service.create_payment(PaymentMethod::Card, usd(dec!(8)));
+    payment_service.authorize(payment)?;
+    payment_service.capture(payment)?;
+    let refunded = payment_service.refund(payment, usd(dec!(3)))?;
+    println!(
+        "payment {payment} via {}: {:?}, {} refundable",
+        refunded.processor.as_deref().unwrap_or_default(),
+        refunded.status,
+        refunded.refundable()
+    );
+    Ok(())
 }
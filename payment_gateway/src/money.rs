@@ -0,0 +1,219 @@
+use std::{cmp::Ordering, fmt};
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::types::PaymentError;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Currency {
+    #[default]
+    Usd,
+    Eur,
+    Gbp,
+    Ngn,
+    Jpy,
+    Kwd,
+    Btc,
+}
+
+impl Currency {
+    pub fn code(self) -> &'static str {
+        match self {
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+            Currency::Gbp => "GBP",
+            Currency::Ngn => "NGN",
+            Currency::Jpy => "JPY",
+            Currency::Kwd => "KWD",
+            Currency::Btc => "BTC",
+        }
+    }
+
+    /// Digits after the decimal point in the smallest unit that can move.
+    pub fn minor_units(self) -> u32 {
+        match self {
+            Currency::Jpy => 0,
+            Currency::Kwd => 3,
+            Currency::Btc => 8,
+            _ => 2,
+        }
+    }
+
+    /// Fiat amounts round half to even, so that rounding errors cancel out
+    /// over many payments; crypto amounts truncate, so that a payment never
+    /// asks for more than the wallet was told.
+    pub fn rounding(self) -> RoundingStrategy {
+        match self {
+            Currency::Btc => RoundingStrategy::ToZero,
+            _ => RoundingStrategy::MidpointNearestEven,
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// An exact amount in one currency, always held at the currency's minor
+/// units. Amounts in different currencies never add up or compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Money {
+    amount: Decimal,
+    currency: Currency,
+}
+
+impl Money {
+    /// `amount` rounded to the currency's minor units by its rounding rule.
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        let mut amount = amount.round_dp_with_strategy(currency.minor_units(), currency.rounding());
+        amount.rescale(currency.minor_units());
+        Self { amount, currency }
+    }
+
+    /// An amount counted in minor units, such as cents.
+    pub fn from_minor(minor: i64, currency: Currency) -> Self {
+        Self::new(Decimal::new(minor, currency.minor_units()), currency)
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(Decimal::ZERO, currency)
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.amount > Decimal::ZERO
+    }
+
+    pub fn ensure_currency(&self, currency: Currency) -> Result<(), PaymentError> {
+        if self.currency == currency {
+            Ok(())
+        } else {
+            Err(PaymentError::CurrencyMismatch {
+                expected: currency,
+                found: self.currency,
+            })
+        }
+    }
+
+    pub fn checked_add(self, other: Money) -> Result<Money, PaymentError> {
+        other.ensure_currency(self.currency)?;
+        Ok(Money::new(self.amount + other.amount, self.currency))
+    }
+
+    pub fn checked_sub(self, other: Money) -> Result<Money, PaymentError> {
+        other.ensure_currency(self.currency)?;
+        Ok(Money::new(self.amount - other.amount, self.currency))
+    }
+
+    /// The amount times `rate`, rounded by the currency's rule; for fees and
+    /// percentages.
+    pub fn scale(self, rate: Decimal) -> Money {
+        Money::new(self.amount * rate, self.currency)
+    }
+}
+
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.currency == other.currency).then(|| self.amount.cmp(&other.amount))
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use rust_decimal::{Decimal, dec};
+
+    use crate::{
+        money::{Currency, Money},
+        types::PaymentError,
+    };
+
+    pub fn usd(amount: Decimal) -> Money {
+        Money::new(amount, Currency::Usd)
+    }
+
+    #[test]
+    fn test_money_will_round_to_each_currencys_minor_units() {
+        assert_eq!(
+            Money::new(dec!(10.125), Currency::Usd).amount(),
+            dec!(10.12)
+        );
+        assert_eq!(
+            Money::new(dec!(10.135), Currency::Usd).amount(),
+            dec!(10.14)
+        );
+        assert_eq!(Money::new(dec!(100.5), Currency::Jpy).amount(), dec!(100));
+        assert_eq!(Money::new(dec!(101.5), Currency::Jpy).amount(), dec!(102));
+        assert_eq!(
+            Money::new(dec!(1.23456), Currency::Kwd).amount(),
+            dec!(1.235)
+        );
+        assert_eq!(
+            Money::new(dec!(0.123456789), Currency::Btc).amount(),
+            dec!(0.12345678)
+        );
+        assert_eq!(
+            Money::new(dec!(12.6), Currency::Ngn).to_string(),
+            "12.60 NGN"
+        );
+        assert_eq!(
+            Money::from_minor(1999, Currency::Usd),
+            Money::new(dec!(19.99), Currency::Usd)
+        );
+        assert_eq!(
+            Money::new(dec!(80), Currency::Usd)
+                .scale(dec!(0.029))
+                .amount(),
+            dec!(2.32)
+        );
+    }
+
+    #[test]
+    fn test_money_will_not_mix_currencies() {
+        let dollars = Money::new(dec!(5), Currency::Usd);
+        let euros = Money::new(dec!(5), Currency::Eur);
+
+        assert_eq!(
+            dollars.checked_add(euros),
+            Err(PaymentError::CurrencyMismatch {
+                expected: Currency::Usd,
+                found: Currency::Eur,
+            })
+        );
+        assert_eq!(dollars.partial_cmp(&euros), None);
+        assert!(dollars > Money::new(dec!(4.99), Currency::Usd));
+    }
+
+    #[test]
+    fn test_money_will_not_drift_over_many_operations() {
+        let cent = Money::new(dec!(0.01), Currency::Usd);
+        let dime = Money::new(dec!(0.1), Currency::Usd);
+        let mut total = Money::zero(Currency::Usd);
+        for _ in 0..100_000 {
+            total = total.checked_add(dime).unwrap();
+        }
+        assert_eq!(total, Money::new(dec!(10000), Currency::Usd));
+
+        for _ in 0..1_000_000 {
+            total = total.checked_sub(cent).unwrap();
+        }
+        assert_eq!(total, Money::zero(Currency::Usd));
+
+        let float: f64 = (0..100_000).map(|_| 0.1).sum();
+        assert_ne!(float, 10_000.0);
+    }
+}
@@ -1,47 +1,232 @@
-use crate::types::PaymentProcessor;
+use std::{sync::Arc, time::Duration};
 
+use crate::{
+    card_details::CardDetails,
+    clock::{Clock, SystemClock},
+    money::{Currency, Money},
+    types::{
+        AuthorizationBook, AuthorizationId, CaptureReceipt, PaymentError, PaymentProcessor,
+        RefundReceipt,
+    },
+};
+
+/// A card and the funds behind it. Authorizations are refused once the
+/// card has expired by the card's clock, and the security code is dropped
+/// after the first one goes through.
 #[derive(Debug)]
 pub struct Card {
-    pub balance: f64,
+    pub details: CardDetails,
+    pub balance: Money,
+    pub authorizations: AuthorizationBook,
+    pub clock: Arc<dyn Clock>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct CardBuilder {
-    pub balance: Option<f64>,
+    pub details: CardDetails,
+    pub balance: Option<Money>,
+    pub authorization_ttl: Option<Duration>,
+    pub clock: Option<Arc<dyn Clock>>,
 }
 
 impl Card {
-    pub fn new() -> CardBuilder {
+    pub fn builder(details: CardDetails) -> CardBuilder {
         CardBuilder {
-            ..Default::default()
+            details,
+            balance: None,
+            authorization_ttl: None,
+            clock: None,
         }
     }
 }
 
 impl CardBuilder {
-    pub fn balance(mut self, balance: f64) -> Self {
+    pub fn balance(mut self, balance: Money) -> Self {
         self.balance = Some(balance);
         self
     }
 
-    pub fn build(&self) -> Card {
+    pub fn authorization_ttl(mut self, ttl: Duration) -> Self {
+        self.authorization_ttl = Some(ttl);
+        self
+    }
+
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    pub fn build(self) -> Card {
         Card {
-            balance: self.balance.unwrap_or_default(),
+            details: self.details,
+            balance: self.balance.unwrap_or(Money::zero(Currency::default())),
+            authorizations: self
+                .authorization_ttl
+                .map(AuthorizationBook::new)
+                .unwrap_or_default(),
+            clock: self.clock.unwrap_or_else(|| Arc::new(SystemClock)),
         }
     }
 }
 
 impl PaymentProcessor for Card {
-    fn authorize(&self, amount: f64) -> bool {
-        self.balance >= amount
+    fn authorize(&mut self, amount: Money) -> Result<AuthorizationId, PaymentError> {
+        self.details.ensure_not_expired(self.clock.today())?;
+        let authorization = self.authorizations.authorize(self.balance, amount)?;
+        self.details.forget_cvv();
+        Ok(authorization)
+    }
+
+    fn capture(&mut self, authorization: AuthorizationId) -> Result<CaptureReceipt, PaymentError> {
+        let receipt = self.authorizations.capture(authorization)?;
+        self.balance = self.balance.checked_sub(receipt.amount)?;
+        Ok(receipt)
+    }
+
+    fn void(&mut self, authorization: AuthorizationId) -> Result<(), PaymentError> {
+        self.authorizations.void(authorization)
+    }
+
+    fn refund(
+        &mut self,
+        authorization: AuthorizationId,
+        amount: Money,
+    ) -> Result<RefundReceipt, PaymentError> {
+        let receipt = self.authorizations.refund(authorization, amount)?;
+        self.balance = self.balance.checked_add(receipt.amount)?;
+        Ok(receipt)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use chrono::NaiveDate;
+    use rust_decimal::dec;
+
+    use crate::{
+        card_details::{CardDetails, CardError, Expiry},
+        clock::FixedClock,
+        money::tests::usd,
+        services::card::Card,
+        types::{CaptureReceipt, PaymentError, PaymentProcessor, RefundReceipt},
+    };
+
+    /// A Visa test card that expires long after the tests stop running.
+    pub fn visa() -> CardDetails {
+        CardDetails::new("4111 1111 1111 1111", Expiry::new(12, 2099).unwrap(), "123").unwrap()
+    }
+
+    #[test]
+    fn test_card_will_hold_capture_and_refund_an_authorization() {
+        let mut card = Card::builder(visa()).balance(usd(dec!(100))).build();
+
+        let auth = card.authorize(usd(dec!(60))).unwrap();
+        assert_eq!(
+            card.authorize(usd(dec!(50))),
+            Err(PaymentError::InsufficientFunds {
+                requested: usd(dec!(50)),
+                available: usd(dec!(40)),
+            })
+        );
+        assert_eq!(
+            card.capture(auth),
+            Ok(CaptureReceipt {
+                authorization: auth,
+                amount: usd(dec!(60)),
+            })
+        );
+        assert_eq!(card.balance, usd(dec!(40)));
+
+        assert_eq!(
+            card.refund(auth, usd(dec!(20))),
+            Ok(RefundReceipt {
+                authorization: auth,
+                amount: usd(dec!(20)),
+                total_refunded: usd(dec!(20)),
+            })
+        );
+        assert_eq!(card.balance, usd(dec!(60)));
     }
 
-    fn capture(&self, amount: f64) {
-        println!("current {amount}")
+    #[test]
+    fn test_card_will_drop_the_security_code_once_authorized() {
+        let mut card = Card::builder(visa()).balance(usd(dec!(100))).build();
+        assert!(card.details.cvv.is_some());
+
+        card.authorize(usd(dec!(500))).unwrap_err();
+        assert!(card.details.cvv.is_some());
+        card.authorize(usd(dec!(60))).unwrap();
+        assert_eq!(card.details.cvv, None);
+    }
+
+    #[test]
+    fn test_card_will_reject_invalid_captures_and_refunds() {
+        let mut card = Card::builder(visa()).balance(usd(dec!(100))).build();
+        let auth = card.authorize(usd(dec!(30))).unwrap();
+
+        assert_eq!(
+            card.refund(auth, usd(dec!(10))),
+            Err(PaymentError::NotCaptured(auth))
+        );
+        card.capture(auth).unwrap();
+        assert_eq!(card.capture(auth), Err(PaymentError::AlreadyCaptured(auth)));
+        assert_eq!(
+            card.capture(99),
+            Err(PaymentError::AuthorizationNotFound(99))
+        );
+        assert_eq!(
+            card.refund(auth, usd(dec!(31))),
+            Err(PaymentError::RefundExceedsCapture {
+                authorization: auth,
+                requested: usd(dec!(31)),
+                refundable: usd(dec!(30)),
+            })
+        );
+        assert!(matches!(
+            card.authorize(usd(dec!(-1))),
+            Err(PaymentError::Declined(_))
+        ));
     }
 
-    fn refund(&mut self, amount: f64) {
-        self.balance += amount;
-        println!("refunded {amount}\nnew balance: {}", self.balance)
+    #[test]
+    fn test_card_will_not_capture_expired_authorizations() {
+        let mut card = Card::builder(visa())
+            .balance(usd(dec!(100)))
+            .authorization_ttl(Duration::ZERO)
+            .build();
+
+        let auth = card.authorize(usd(dec!(100))).unwrap();
+        assert_eq!(
+            card.capture(auth),
+            Err(PaymentError::AuthorizationExpired(auth))
+        );
+        assert_eq!(card.balance, usd(dec!(100)));
+        assert!(card.authorize(usd(dec!(100))).is_ok());
+    }
+
+    #[test]
+    fn test_card_will_refuse_authorizations_once_expired_by_its_clock() {
+        let clock = Arc::new(FixedClock::new(
+            NaiveDate::from_ymd_opt(2030, 6, 30).unwrap(),
+        ));
+        let details =
+            CardDetails::new("5555555555554444", Expiry::new(6, 2030).unwrap(), "321").unwrap();
+        let mut card = Card::builder(details)
+            .balance(usd(dec!(100)))
+            .clock(clock.clone())
+            .build();
+
+        assert!(card.authorize(usd(dec!(10))).is_ok());
+        clock.set(NaiveDate::from_ymd_opt(2030, 7, 1).unwrap());
+        assert_eq!(
+            card.authorize(usd(dec!(10))),
+            Err(PaymentError::Card(CardError::Expired {
+                month: 6,
+                year: 2030,
+            }))
+        );
+        assert!(!format!("{card:?}").contains("5555555555554444"));
     }
 }
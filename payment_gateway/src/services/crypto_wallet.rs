@@ -1,17 +1,27 @@
-use crate::types::PaymentProcessor;
+use std::time::Duration;
+
+use crate::{
+    money::{Currency, Money},
+    types::{
+        AuthorizationBook, AuthorizationId, CaptureReceipt, PaymentError, PaymentProcessor,
+        RefundReceipt,
+    },
+};
 
 #[derive(Debug)]
 pub struct CryptoWallet {
-    pub balance: f64,
+    pub balance: Money,
+    pub authorizations: AuthorizationBook,
 }
 
 #[derive(Debug, Default)]
 pub struct CryptoWalletBuilder {
-    pub balance: Option<f64>,
+    pub balance: Option<Money>,
+    pub authorization_ttl: Option<Duration>,
 }
 
 impl CryptoWallet {
-    pub fn new() -> CryptoWalletBuilder {
+    pub fn builder() -> CryptoWalletBuilder {
         CryptoWalletBuilder {
             ..Default::default()
         }
@@ -19,29 +29,49 @@ impl CryptoWallet {
 }
 
 impl CryptoWalletBuilder {
-    pub fn balance(mut self, balance: f64) -> Self {
+    pub fn balance(mut self, balance: Money) -> Self {
         self.balance = Some(balance);
         self
     }
 
+    pub fn authorization_ttl(mut self, ttl: Duration) -> Self {
+        self.authorization_ttl = Some(ttl);
+        self
+    }
+
     pub fn build(&self) -> CryptoWallet {
         CryptoWallet {
-            balance: self.balance.unwrap_or_default(),
+            balance: self.balance.unwrap_or(Money::zero(Currency::default())),
+            authorizations: self
+                .authorization_ttl
+                .map(AuthorizationBook::new)
+                .unwrap_or_default(),
         }
     }
 }
 
 impl PaymentProcessor for CryptoWallet {
-    fn authorize(&self, amount: f64) -> bool {
-        self.balance >= amount
+    fn authorize(&mut self, amount: Money) -> Result<AuthorizationId, PaymentError> {
+        self.authorizations.authorize(self.balance, amount)
+    }
+
+    fn capture(&mut self, authorization: AuthorizationId) -> Result<CaptureReceipt, PaymentError> {
+        let receipt = self.authorizations.capture(authorization)?;
+        self.balance = self.balance.checked_sub(receipt.amount)?;
+        Ok(receipt)
     }
 
-    fn capture(&self, amount: f64) {
-        println!("current {amount}")
+    fn void(&mut self, authorization: AuthorizationId) -> Result<(), PaymentError> {
+        self.authorizations.void(authorization)
     }
 
-    fn refund(&mut self, amount: f64) {
-        self.balance += amount;
-        println!("refunded {amount}\nnew balance: {}", self.balance)
+    fn refund(
+        &mut self,
+        authorization: AuthorizationId,
+        amount: Money,
+    ) -> Result<RefundReceipt, PaymentError> {
+        let receipt = self.authorizations.refund(authorization, amount)?;
+        self.balance = self.balance.checked_add(receipt.amount)?;
+        Ok(receipt)
     }
 }
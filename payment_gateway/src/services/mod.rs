@@ -2,3 +2,4 @@ pub mod card;
 pub mod crypto_wallet;
 pub mod payment_service;
 pub mod paypal;
+pub mod router;
@@ -1,26 +1,414 @@
-use crate::types::PaymentProcessor;
+use std::{collections::HashMap, time::SystemTime};
 
-// #[derive(Debug)]
-// pub struct PaymentService<T: PaymentProcessor + std::fmt::Debug> {
-//     pub processor: T,
-// }
+use crate::{
+    money::Money,
+    services::router::{Failover, Route, Router},
+    types::{AuthorizationId, PaymentError, PaymentMethod, PaymentProcessor},
+};
 
-// impl<T: PaymentProcessor + std::fmt::Debug> PaymentService<T> {
-//     pub fn new(processor: T) -> Self {
-//         Self { processor }
-//     }
-// }
+pub type PaymentId = u64;
 
-//dynamic for multiple types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentStatus {
+    Created,
+    Authorized,
+    Captured,
+    PartiallyRefunded,
+    Refunded,
+    Voided,
+    Failed,
+}
+
+impl PaymentStatus {
+    pub fn can_transition_to(self, next: PaymentStatus) -> bool {
+        use PaymentStatus::*;
+
+        matches!(
+            (self, next),
+            (Created, Authorized | Voided | Failed)
+                | (Authorized, Captured | Voided | Failed)
+                | (Captured | PartiallyRefunded, PartiallyRefunded | Refunded)
+        )
+    }
+
+    /// Nothing further can happen to the payment.
+    pub fn is_final(self) -> bool {
+        matches!(
+            self,
+            PaymentStatus::Refunded | PaymentStatus::Voided | PaymentStatus::Failed
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub from: PaymentStatus,
+    pub to: PaymentStatus,
+    pub at: SystemTime,
+    /// Amount moved by the step, for captures and refunds.
+    pub amount: Option<Money>,
+    /// Why the payment failed, for transitions to `Failed`.
+    pub reason: Option<PaymentError>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Payment {
+    pub id: PaymentId,
+    pub method: PaymentMethod,
+    pub amount: Money,
+    pub status: PaymentStatus,
+    /// The route that authorized the payment and takes every later step.
+    pub processor: Option<String>,
+    pub authorization: Option<AuthorizationId>,
+    /// Routes tried before `processor` that could not answer.
+    pub failovers: Vec<Failover>,
+    pub captured: Money,
+    pub refunded: Money,
+    /// Every transition the payment went through, oldest first.
+    pub history: Vec<Transition>,
+}
+
+impl Payment {
+    pub fn refundable(&self) -> Money {
+        Money::new(
+            self.captured.amount() - self.refunded.amount(),
+            self.amount.currency(),
+        )
+    }
+
+    /// The route and authorization to act on for a move to `next`, if the
+    /// payment may make it.
+    fn authorization_for(
+        &self,
+        next: PaymentStatus,
+    ) -> Result<(&str, AuthorizationId), PaymentError> {
+        self.ensure_can(next)?;
+        match (&self.processor, self.authorization) {
+            (Some(processor), Some(authorization)) => Ok((processor, authorization)),
+            _ => Err(self.invalid(next)),
+        }
+    }
+
+    fn ensure_can(&self, next: PaymentStatus) -> Result<(), PaymentError> {
+        if self.status.can_transition_to(next) {
+            Ok(())
+        } else {
+            Err(self.invalid(next))
+        }
+    }
+
+    fn invalid(&self, next: PaymentStatus) -> PaymentError {
+        PaymentError::InvalidTransition {
+            payment: self.id,
+            from: self.status,
+            to: next,
+        }
+    }
+
+    fn transition(
+        &mut self,
+        to: PaymentStatus,
+        amount: Option<Money>,
+        reason: Option<PaymentError>,
+    ) {
+        self.history.push(Transition {
+            from: self.status,
+            to,
+            at: SystemTime::now(),
+            amount,
+            reason,
+        });
+        self.status = to;
+    }
+}
 
 type Processor = Box<dyn PaymentProcessor>;
+
+/// The route of a service built around a single processor.
+pub const DEFAULT_ROUTE: &str = "default";
+
+/// Takes payments from creation through authorization, capture and refunds,
+/// or to being voided or failing, through the processors of its router.
 #[derive(Debug)]
 pub struct PaymentService {
-    pub processor: Processor,
+    pub router: Router,
+    pub next_id: PaymentId,
+    pub payments: HashMap<PaymentId, Payment>,
 }
 
 impl PaymentService {
+    /// A service sending every payment to `processor`.
     pub fn new(processor: Processor) -> Self {
-        Self { processor }
+        Self::with_router(Router::new().route(Route::builder(DEFAULT_ROUTE, processor).build()))
+    }
+
+    pub fn with_router(router: Router) -> Self {
+        Self {
+            router,
+            next_id: 1,
+            payments: HashMap::new(),
+        }
+    }
+
+    pub fn create_payment(&mut self, method: PaymentMethod, amount: Money) -> PaymentId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.payments.insert(
+            id,
+            Payment {
+                id,
+                method,
+                amount,
+                status: PaymentStatus::Created,
+                processor: None,
+                authorization: None,
+                failovers: Vec::new(),
+                captured: Money::zero(amount.currency()),
+                refunded: Money::zero(amount.currency()),
+                history: Vec::new(),
+            },
+        );
+        id
+    }
+
+    pub fn payment(&self, id: PaymentId) -> Result<&Payment, PaymentError> {
+        self.payments
+            .get(&id)
+            .ok_or(PaymentError::PaymentNotFound(id))
+    }
+
+    /// Authorizes the full amount on the first route that answers. A refusal,
+    /// or every route failing, fails the payment.
+    pub fn authorize(&mut self, id: PaymentId) -> Result<&Payment, PaymentError> {
+        let payment = self
+            .payments
+            .get_mut(&id)
+            .ok_or(PaymentError::PaymentNotFound(id))?;
+        payment.ensure_can(PaymentStatus::Authorized)?;
+
+        match self.router.authorize(payment.method, payment.amount) {
+            Ok(routed) => {
+                payment.processor = Some(routed.processor);
+                payment.authorization = Some(routed.authorization);
+                payment.failovers = routed.failovers;
+                payment.transition(PaymentStatus::Authorized, Some(payment.amount), None);
+                Ok(payment)
+            }
+            Err(err) => {
+                payment.transition(PaymentStatus::Failed, None, Some(err.clone()));
+                Err(err)
+            }
+        }
+    }
+
+    /// Captures the authorized amount. An authorization that expired first
+    /// fails the payment; other refusals leave it authorized.
+    pub fn capture(&mut self, id: PaymentId) -> Result<&Payment, PaymentError> {
+        let payment = self
+            .payments
+            .get_mut(&id)
+            .ok_or(PaymentError::PaymentNotFound(id))?;
+        let (processor, authorization) = payment.authorization_for(PaymentStatus::Captured)?;
+
+        match self.router.processor(processor)?.capture(authorization) {
+            Ok(receipt) => {
+                payment.captured = receipt.amount;
+                payment.transition(PaymentStatus::Captured, Some(receipt.amount), None);
+                Ok(payment)
+            }
+            Err(err @ PaymentError::AuthorizationExpired(_)) => {
+                payment.transition(PaymentStatus::Failed, None, Some(err.clone()));
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Refunds part or all of what is left of the captured amount.
+    pub fn refund(&mut self, id: PaymentId, amount: Money) -> Result<&Payment, PaymentError> {
+        let payment = self
+            .payments
+            .get_mut(&id)
+            .ok_or(PaymentError::PaymentNotFound(id))?;
+        amount.ensure_currency(payment.amount.currency())?;
+        let next = if amount < payment.refundable() {
+            PaymentStatus::PartiallyRefunded
+        } else {
+            PaymentStatus::Refunded
+        };
+        let (processor, authorization) = payment.authorization_for(next)?;
+        if amount > payment.refundable() {
+            return Err(PaymentError::RefundExceedsCapture {
+                authorization,
+                requested: amount,
+                refundable: payment.refundable(),
+            });
+        }
+
+        let receipt = self
+            .router
+            .processor(processor)?
+            .refund(authorization, amount)?;
+        payment.refunded = payment.refunded.checked_add(receipt.amount)?;
+        payment.transition(next, Some(receipt.amount), None);
+        Ok(payment)
+    }
+
+    /// Cancels a payment that has not been captured, releasing any hold.
+    pub fn void(&mut self, id: PaymentId) -> Result<&Payment, PaymentError> {
+        let payment = self
+            .payments
+            .get_mut(&id)
+            .ok_or(PaymentError::PaymentNotFound(id))?;
+        payment.ensure_can(PaymentStatus::Voided)?;
+
+        if let (Some(processor), Some(authorization)) = (&payment.processor, payment.authorization)
+        {
+            self.router.processor(processor)?.void(authorization)?;
+        }
+        payment.transition(PaymentStatus::Voided, None, None);
+        Ok(payment)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use rust_decimal::dec;
+
+    use crate::{
+        money::{Currency, Money, tests::usd},
+        services::{
+            card::{Card, tests::visa},
+            payment_service::{PaymentService, PaymentStatus},
+        },
+        types::{PaymentError, PaymentMethod},
+    };
+
+    fn service(balance: Money) -> PaymentService {
+        PaymentService::new(Box::new(Card::builder(visa()).balance(balance).build()))
+    }
+
+    #[test]
+    fn test_payment_service_will_move_payments_through_their_lifecycle() {
+        let mut service = service(usd(dec!(100)));
+        let id = service.create_payment(PaymentMethod::Card, usd(dec!(80)));
+
+        service.authorize(id).unwrap();
+        service.capture(id).unwrap();
+        let payment = service.refund(id, usd(dec!(30))).unwrap();
+        assert_eq!(payment.status, PaymentStatus::PartiallyRefunded);
+        assert_eq!(payment.refundable(), usd(dec!(50)));
+
+        let payment = service.refund(id, usd(dec!(50))).unwrap();
+        assert_eq!(payment.status, PaymentStatus::Refunded);
+        assert_eq!(
+            (payment.captured, payment.refunded),
+            (usd(dec!(80)), usd(dec!(80)))
+        );
+
+        let steps: Vec<_> = payment
+            .history
+            .iter()
+            .map(|step| (step.from, step.to))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (PaymentStatus::Created, PaymentStatus::Authorized),
+                (PaymentStatus::Authorized, PaymentStatus::Captured),
+                (PaymentStatus::Captured, PaymentStatus::PartiallyRefunded),
+                (PaymentStatus::PartiallyRefunded, PaymentStatus::Refunded),
+            ]
+        );
+        assert_eq!(payment.history[3].amount, Some(usd(dec!(50))));
+        assert!(payment.status.is_final());
+    }
+
+    #[test]
+    fn test_payment_service_will_reject_illegal_transitions_and_excess_refunds() {
+        let mut service = service(usd(dec!(100)));
+        let id = service.create_payment(PaymentMethod::Card, usd(dec!(40)));
+
+        assert_eq!(
+            service.capture(id).unwrap_err(),
+            PaymentError::InvalidTransition {
+                payment: id,
+                from: PaymentStatus::Created,
+                to: PaymentStatus::Captured,
+            }
+        );
+        service.authorize(id).unwrap();
+        service.capture(id).unwrap();
+        assert!(matches!(
+            service.void(id),
+            Err(PaymentError::InvalidTransition { .. })
+        ));
+        assert!(matches!(
+            service.refund(id, usd(dec!(40.01))),
+            Err(PaymentError::RefundExceedsCapture { .. })
+        ));
+
+        service.refund(id, usd(dec!(40))).unwrap();
+        assert!(matches!(
+            service.refund(id, usd(dec!(1))),
+            Err(PaymentError::InvalidTransition { .. })
+        ));
+        assert_eq!(service.payment(id).unwrap().history.len(), 3);
+        assert_eq!(service.payment(99), Err(PaymentError::PaymentNotFound(99)));
+    }
+
+    #[test]
+    fn test_payment_service_will_fail_declined_payments_and_void_held_ones() {
+        let mut service = service(usd(dec!(100)));
+
+        let declined = service.create_payment(PaymentMethod::Card, usd(dec!(150)));
+        assert!(service.authorize(declined).is_err());
+        let payment = service.payment(declined).unwrap();
+        assert_eq!(payment.status, PaymentStatus::Failed);
+        assert!(matches!(
+            payment.history[0].reason,
+            Some(PaymentError::InsufficientFunds { .. })
+        ));
+
+        let held = service.create_payment(PaymentMethod::Card, usd(dec!(100)));
+        service.authorize(held).unwrap();
+        let other = service.create_payment(PaymentMethod::Card, usd(dec!(100)));
+        assert!(service.authorize(other).is_err());
+
+        assert_eq!(service.void(held).unwrap().status, PaymentStatus::Voided);
+        let retry = service.create_payment(PaymentMethod::Card, usd(dec!(100)));
+        assert_eq!(
+            service.authorize(retry).unwrap().status,
+            PaymentStatus::Authorized
+        );
+    }
+
+    #[test]
+    fn test_payment_service_will_keep_exact_totals_over_thousands_of_payments() {
+        let mut service = service(usd(dec!(1000)));
+        for _ in 0..5_000 {
+            let id = service.create_payment(PaymentMethod::Card, usd(dec!(0.10)));
+            service.authorize(id).unwrap();
+            service.capture(id).unwrap();
+            service.refund(id, usd(dec!(0.03))).unwrap();
+        }
+        let captured = service.create_payment(PaymentMethod::Card, usd(dec!(650)));
+        service.authorize(captured).unwrap();
+        service.capture(captured).unwrap();
+
+        let drained = service.create_payment(PaymentMethod::Card, usd(dec!(0.01)));
+        assert!(matches!(
+            service.authorize(drained),
+            Err(PaymentError::InsufficientFunds { available, .. }) if available == usd(dec!(0))
+        ));
+
+        let euros = service.create_payment(PaymentMethod::Card, Money::new(dec!(1), Currency::Eur));
+        assert_eq!(
+            service.authorize(euros).unwrap_err(),
+            PaymentError::CurrencyMismatch {
+                expected: Currency::Usd,
+                found: Currency::Eur,
+            }
+        );
     }
 }
@@ -0,0 +1,364 @@
+use rust_decimal::Decimal;
+
+use crate::{
+    money::{Currency, Money},
+    types::{AuthorizationId, PaymentError, PaymentMethod, PaymentProcessor},
+};
+
+/// A processor and the payments it may take.
+#[derive(Debug)]
+pub struct Route {
+    pub name: String,
+    pub processor: Box<dyn PaymentProcessor>,
+    /// Methods the processor takes; empty means any.
+    pub methods: Vec<PaymentMethod>,
+    /// Currencies the processor settles; empty means any.
+    pub currencies: Vec<Currency>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    /// Share of the amount the processor charges, such as `0.029` for 2.9%.
+    pub fee_rate: Decimal,
+    /// Lower runs first; routes of equal priority run cheapest first.
+    pub priority: u32,
+}
+
+impl Route {
+    pub fn builder(name: &str, processor: Box<dyn PaymentProcessor>) -> RouteBuilder {
+        RouteBuilder {
+            name: name.into(),
+            processor,
+            methods: Vec::new(),
+            currencies: Vec::new(),
+            min_amount: None,
+            max_amount: None,
+            fee_rate: Decimal::ZERO,
+            priority: 0,
+        }
+    }
+
+    pub fn accepts(&self, method: PaymentMethod, amount: Money) -> bool {
+        (self.methods.is_empty() || self.methods.contains(&method))
+            && (self.currencies.is_empty() || self.currencies.contains(&amount.currency()))
+            && self.min_amount.is_none_or(|min| amount.amount() >= min)
+            && self.max_amount.is_none_or(|max| amount.amount() <= max)
+    }
+
+    /// What the processor would charge to take `amount`.
+    pub fn cost(&self, amount: Money) -> Money {
+        amount.scale(self.fee_rate)
+    }
+}
+
+#[derive(Debug)]
+pub struct RouteBuilder {
+    pub name: String,
+    pub processor: Box<dyn PaymentProcessor>,
+    pub methods: Vec<PaymentMethod>,
+    pub currencies: Vec<Currency>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub fee_rate: Decimal,
+    pub priority: u32,
+}
+
+impl RouteBuilder {
+    pub fn method(mut self, method: PaymentMethod) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    pub fn currency(mut self, currency: Currency) -> Self {
+        self.currencies.push(currency);
+        self
+    }
+
+    pub fn min_amount(mut self, min: Decimal) -> Self {
+        self.min_amount = Some(min);
+        self
+    }
+
+    pub fn max_amount(mut self, max: Decimal) -> Self {
+        self.max_amount = Some(max);
+        self
+    }
+
+    pub fn fee_rate(mut self, rate: Decimal) -> Self {
+        self.fee_rate = rate;
+        self
+    }
+
+    pub fn priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn build(self) -> Route {
+        Route {
+            name: self.name,
+            processor: self.processor,
+            methods: self.methods,
+            currencies: self.currencies,
+            min_amount: self.min_amount,
+            max_amount: self.max_amount,
+            fee_rate: self.fee_rate,
+            priority: self.priority,
+        }
+    }
+}
+
+/// A processor that could not take a payment for technical reasons, before
+/// the router moved on to the next.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failover {
+    pub processor: String,
+    pub error: PaymentError,
+}
+
+/// Where an authorization ended up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Routed {
+    pub processor: String,
+    pub authorization: AuthorizationId,
+    pub failovers: Vec<Failover>,
+}
+
+/// Picks a processor for each payment from the routes that accept it, and
+/// fails over to the next when one cannot answer.
+#[derive(Debug, Default)]
+pub struct Router {
+    pub routes: Vec<Route>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn route(mut self, route: Route) -> Self {
+        self.routes.push(route);
+        self
+    }
+
+    /// Names of the routes that accept the payment, in the order they are
+    /// tried: by priority, then by cost, then by registration.
+    pub fn candidates(&self, method: PaymentMethod, amount: Money) -> Vec<&str> {
+        let mut candidates: Vec<&Route> = self
+            .routes
+            .iter()
+            .filter(|route| route.accepts(method, amount))
+            .collect();
+        candidates.sort_by_key(|route| (route.priority, route.cost(amount).amount()));
+        candidates
+            .into_iter()
+            .map(|route| route.name.as_str())
+            .collect()
+    }
+
+    /// Authorizes on the first candidate that answers. Technical failures
+    /// move on to the next candidate; any other refusal is final.
+    pub fn authorize(
+        &mut self,
+        method: PaymentMethod,
+        amount: Money,
+    ) -> Result<Routed, PaymentError> {
+        let candidates: Vec<String> = self
+            .candidates(method, amount)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let mut failovers = Vec::new();
+
+        for name in candidates {
+            match self.processor(&name)?.authorize(amount) {
+                Ok(authorization) => {
+                    return Ok(Routed {
+                        processor: name,
+                        authorization,
+                        failovers,
+                    });
+                }
+                Err(error) if error.is_technical() => failovers.push(Failover {
+                    processor: name,
+                    error,
+                }),
+                Err(error) => return Err(error),
+            }
+        }
+
+        Err(match failovers.pop() {
+            Some(last) => last.error,
+            None => PaymentError::NoRoute { method, amount },
+        })
+    }
+
+    pub fn processor(
+        &mut self,
+        name: &str,
+    ) -> Result<&mut (dyn PaymentProcessor + 'static), PaymentError> {
+        self.routes
+            .iter_mut()
+            .find(|route| route.name == name)
+            .map(|route| route.processor.as_mut())
+            .ok_or_else(|| PaymentError::ProcessorNotFound(name.into()))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use rust_decimal::dec;
+
+    use crate::{
+        money::{Currency, Money, tests::usd},
+        services::{
+            card::{Card, tests::visa},
+            payment_service::{PaymentService, PaymentStatus},
+            router::{Failover, Route, Router},
+        },
+        types::{
+            AuthorizationId, CaptureReceipt, PaymentError, PaymentMethod, PaymentProcessor,
+            RefundReceipt,
+        },
+    };
+
+    /// A processor whose provider cannot be reached.
+    #[derive(Debug)]
+    pub struct Offline;
+
+    impl PaymentProcessor for Offline {
+        fn authorize(&mut self, _: Money) -> Result<AuthorizationId, PaymentError> {
+            Err(PaymentError::ProcessorUnavailable(
+                "connection refused".into(),
+            ))
+        }
+
+        fn capture(&mut self, _: AuthorizationId) -> Result<CaptureReceipt, PaymentError> {
+            Err(PaymentError::ProcessorUnavailable(
+                "connection refused".into(),
+            ))
+        }
+
+        fn void(&mut self, _: AuthorizationId) -> Result<(), PaymentError> {
+            Err(PaymentError::ProcessorUnavailable(
+                "connection refused".into(),
+            ))
+        }
+
+        fn refund(&mut self, _: AuthorizationId, _: Money) -> Result<RefundReceipt, PaymentError> {
+            Err(PaymentError::ProcessorUnavailable(
+                "connection refused".into(),
+            ))
+        }
+    }
+
+    fn card(balance: Money) -> Box<Card> {
+        Box::new(Card::builder(visa()).balance(balance).build())
+    }
+
+    #[test]
+    fn test_router_will_order_candidates_by_rules_priority_and_cost() {
+        let route = |name| Route::builder(name, card(usd(dec!(0))));
+        let router = Router::new()
+            .route(route("pricey").fee_rate(dec!(0.035)).priority(1).build())
+            .route(route("cheap").fee_rate(dec!(0.015)).priority(1).build())
+            .route(
+                route("preferred")
+                    .method(PaymentMethod::Card)
+                    .max_amount(dec!(100))
+                    .fee_rate(dec!(0.05))
+                    .build(),
+            )
+            .route(route("fallback").priority(9).build())
+            .route(
+                route("naira")
+                    .currency(Currency::Ngn)
+                    .min_amount(dec!(1000))
+                    .build(),
+            );
+
+        assert_eq!(
+            router.candidates(PaymentMethod::Card, usd(dec!(50))),
+            vec!["preferred", "cheap", "pricey", "fallback"]
+        );
+        assert_eq!(
+            router.candidates(PaymentMethod::Card, usd(dec!(500))),
+            vec!["cheap", "pricey", "fallback"]
+        );
+        assert_eq!(
+            router.candidates(PaymentMethod::Paypal, usd(dec!(50))),
+            vec!["cheap", "pricey", "fallback"]
+        );
+        let naira = |amount| Money::new(amount, Currency::Ngn);
+        assert!(
+            !router
+                .candidates(PaymentMethod::Card, naira(dec!(999)))
+                .contains(&"naira")
+        );
+        assert_eq!(
+            router.candidates(PaymentMethod::Card, naira(dec!(1000)))[0],
+            "naira"
+        );
+    }
+
+    #[test]
+    fn test_router_will_fail_over_on_technical_declines_and_record_the_processor() {
+        let router = Router::new()
+            .route(Route::builder("primary", Box::new(Offline)).build())
+            .route(
+                Route::builder("secondary", card(usd(dec!(100))))
+                    .priority(1)
+                    .build(),
+            );
+        let mut service = PaymentService::with_router(router);
+
+        let id = service.create_payment(PaymentMethod::Card, usd(dec!(60)));
+        let payment = service.authorize(id).unwrap();
+        assert_eq!(payment.processor.as_deref(), Some("secondary"));
+        assert_eq!(
+            payment.failovers,
+            vec![Failover {
+                processor: "primary".into(),
+                error: PaymentError::ProcessorUnavailable("connection refused".into()),
+            }]
+        );
+        service.capture(id).unwrap();
+        assert_eq!(
+            service.refund(id, usd(dec!(60))).unwrap().status,
+            PaymentStatus::Refunded
+        );
+
+        let declined = service.create_payment(PaymentMethod::Card, usd(dec!(500)));
+        assert!(matches!(
+            service.authorize(declined),
+            Err(PaymentError::InsufficientFunds { .. })
+        ));
+        assert_eq!(service.payment(declined).unwrap().failovers, vec![]);
+    }
+
+    #[test]
+    fn test_router_will_fail_payments_no_processor_can_take() {
+        let router = Router::new()
+            .route(Route::builder("offline", Box::new(Offline)).build())
+            .route(
+                Route::builder("crypto", card(usd(dec!(0))))
+                    .method(PaymentMethod::Crypto)
+                    .build(),
+            );
+        let mut service = PaymentService::with_router(router);
+
+        let id = service.create_payment(PaymentMethod::Card, usd(dec!(5)));
+        assert_eq!(
+            service.authorize(id).unwrap_err(),
+            PaymentError::ProcessorUnavailable("connection refused".into())
+        );
+        assert_eq!(service.payment(id).unwrap().status, PaymentStatus::Failed);
+
+        let mut router = Router::new();
+        assert_eq!(
+            router.authorize(PaymentMethod::Paypal, usd(dec!(5))),
+            Err(PaymentError::NoRoute {
+                method: PaymentMethod::Paypal,
+                amount: usd(dec!(5)),
+            })
+        );
+    }
+}
@@ -1,10 +1,43 @@
 use core::fmt;
-use std::fmt::{Debug, Formatter};
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    time::{Duration, SystemTime},
+};
+
+use thiserror::Error;
+
+use crate::{
+    card_details::CardError,
+    money::{Currency, Money},
+    services::payment_service::{PaymentId, PaymentStatus},
+};
+
+pub type AuthorizationId = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PaymentMethod {
+    Card,
+    Paypal,
+    Crypto,
+}
+
+/// How long an authorization holds funds before it can no longer be captured.
+pub const DEFAULT_AUTHORIZATION_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
 
 pub trait PaymentProcessor {
-    fn authorize(&self, amount: f64) -> bool;
-    fn capture(&self, amount: f64);
-    fn refund(&mut self, amount: f64);
+    /// Holds `amount` against the payer's funds until it is captured.
+    fn authorize(&mut self, amount: Money) -> Result<AuthorizationId, PaymentError>;
+    /// Takes the funds held by the authorization.
+    fn capture(&mut self, authorization: AuthorizationId) -> Result<CaptureReceipt, PaymentError>;
+    /// Releases the funds held by an authorization that will not be captured.
+    fn void(&mut self, authorization: AuthorizationId) -> Result<(), PaymentError>;
+    /// Returns up to the captured amount to the payer, in one go or several.
+    fn refund(
+        &mut self,
+        authorization: AuthorizationId,
+        amount: Money,
+    ) -> Result<RefundReceipt, PaymentError>;
 }
 
 impl Debug for dyn PaymentProcessor {
@@ -12,3 +45,222 @@ impl Debug for dyn PaymentProcessor {
         write!(f, "PaymentProcessor")
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum PaymentError {
+    #[error("payment {0} not found")]
+    PaymentNotFound(PaymentId),
+    #[error("payment {payment} cannot go from {from:?} to {to:?}")]
+    InvalidTransition {
+        payment: PaymentId,
+        from: PaymentStatus,
+        to: PaymentStatus,
+    },
+    #[error("no processor takes {method:?} payments of {amount}")]
+    NoRoute {
+        method: PaymentMethod,
+        amount: Money,
+    },
+    #[error("processor {0} not found")]
+    ProcessorNotFound(String),
+    #[error("processor unavailable: {0}")]
+    ProcessorUnavailable(String),
+    #[error("card rejected: {0}")]
+    Card(#[from] CardError),
+    #[error("payment declined: {0}")]
+    Declined(String),
+    #[error("insufficient funds: requested {requested}, available {available}")]
+    InsufficientFunds { requested: Money, available: Money },
+    #[error("amount in {found} where {expected} was expected")]
+    CurrencyMismatch { expected: Currency, found: Currency },
+    #[error("authorization {0} not found")]
+    AuthorizationNotFound(AuthorizationId),
+    #[error("authorization {0} has expired")]
+    AuthorizationExpired(AuthorizationId),
+    #[error("authorization {0} is already captured")]
+    AlreadyCaptured(AuthorizationId),
+    #[error("authorization {0} has not been captured")]
+    NotCaptured(AuthorizationId),
+    #[error("refund of {requested} exceeds the {refundable} left on authorization {authorization}")]
+    RefundExceedsCapture {
+        authorization: AuthorizationId,
+        requested: Money,
+        refundable: Money,
+    },
+}
+
+impl PaymentError {
+    /// The processor could not answer, rather than refusing the payment, so
+    /// another processor may still take it.
+    pub fn is_technical(&self) -> bool {
+        matches!(self, PaymentError::ProcessorUnavailable(_))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureReceipt {
+    pub authorization: AuthorizationId,
+    pub amount: Money,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefundReceipt {
+    pub authorization: AuthorizationId,
+    pub amount: Money,
+    /// Everything refunded on the authorization so far, this refund included.
+    pub total_refunded: Money,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Authorization {
+    pub id: AuthorizationId,
+    pub amount: Money,
+    pub expires_at: SystemTime,
+    pub captured: bool,
+    pub refunded: Money,
+}
+
+impl Authorization {
+    /// Still holding funds: neither captured nor expired.
+    pub fn is_open(&self, now: SystemTime) -> bool {
+        !self.captured && now < self.expires_at
+    }
+}
+
+/// Authorizations a processor has issued, so that every processor applies
+/// the same hold, capture and refund rules to its own balance.
+#[derive(Debug)]
+pub struct AuthorizationBook {
+    pub ttl: Duration,
+    pub next_id: AuthorizationId,
+    pub authorizations: HashMap<AuthorizationId, Authorization>,
+}
+
+impl Default for AuthorizationBook {
+    fn default() -> Self {
+        Self::new(DEFAULT_AUTHORIZATION_TTL)
+    }
+}
+
+impl AuthorizationBook {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            next_id: 1,
+            authorizations: HashMap::new(),
+        }
+    }
+
+    /// Total of the authorizations still holding funds. Every authorization
+    /// is in the currency of the balance it was checked against.
+    pub fn held(&self, currency: Currency) -> Money {
+        let now = SystemTime::now();
+        let held = self
+            .authorizations
+            .values()
+            .filter(|auth| auth.is_open(now))
+            .map(|auth| auth.amount.amount())
+            .sum();
+        Money::new(held, currency)
+    }
+
+    /// Holds `amount` if `balance` still covers it after the existing holds.
+    pub fn authorize(
+        &mut self,
+        balance: Money,
+        amount: Money,
+    ) -> Result<AuthorizationId, PaymentError> {
+        amount.ensure_currency(balance.currency())?;
+        if !amount.is_positive() {
+            return Err(PaymentError::Declined(format!("invalid amount {amount}")));
+        }
+        let available = balance.checked_sub(self.held(balance.currency()))?;
+        if available < amount {
+            return Err(PaymentError::InsufficientFunds {
+                requested: amount,
+                available,
+            });
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.authorizations.insert(
+            id,
+            Authorization {
+                id,
+                amount,
+                expires_at: SystemTime::now() + self.ttl,
+                captured: false,
+                refunded: Money::zero(amount.currency()),
+            },
+        );
+        Ok(id)
+    }
+
+    /// Marks the authorization captured; the caller takes the funds.
+    pub fn capture(&mut self, id: AuthorizationId) -> Result<CaptureReceipt, PaymentError> {
+        let auth = self
+            .authorizations
+            .get_mut(&id)
+            .ok_or(PaymentError::AuthorizationNotFound(id))?;
+        if auth.captured {
+            return Err(PaymentError::AlreadyCaptured(id));
+        }
+        if SystemTime::now() >= auth.expires_at {
+            return Err(PaymentError::AuthorizationExpired(id));
+        }
+
+        auth.captured = true;
+        Ok(CaptureReceipt {
+            authorization: id,
+            amount: auth.amount,
+        })
+    }
+
+    /// Drops an authorization that has not been captured, releasing its hold.
+    pub fn void(&mut self, id: AuthorizationId) -> Result<(), PaymentError> {
+        match self.authorizations.get(&id) {
+            None => Err(PaymentError::AuthorizationNotFound(id)),
+            Some(auth) if auth.captured => Err(PaymentError::AlreadyCaptured(id)),
+            Some(_) => {
+                self.authorizations.remove(&id);
+                Ok(())
+            }
+        }
+    }
+
+    /// Records a refund against a captured authorization; the caller
+    /// returns the funds.
+    pub fn refund(
+        &mut self,
+        id: AuthorizationId,
+        amount: Money,
+    ) -> Result<RefundReceipt, PaymentError> {
+        let auth = self
+            .authorizations
+            .get_mut(&id)
+            .ok_or(PaymentError::AuthorizationNotFound(id))?;
+        if !auth.captured {
+            return Err(PaymentError::NotCaptured(id));
+        }
+        amount.ensure_currency(auth.amount.currency())?;
+        if !amount.is_positive() {
+            return Err(PaymentError::Declined(format!("invalid amount {amount}")));
+        }
+        let refundable = auth.amount.checked_sub(auth.refunded)?;
+        if amount > refundable {
+            return Err(PaymentError::RefundExceedsCapture {
+                authorization: id,
+                requested: amount,
+                refundable,
+            });
+        }
+
+        auth.refunded = auth.refunded.checked_add(amount)?;
+        Ok(RefundReceipt {
+            authorization: id,
+            amount,
+            total_refunded: auth.refunded,
+        })
+    }
+}